@@ -29,7 +29,7 @@ pub mod informant;
 use client::ExecutionStrategies;
 use service::{
 	config::Configuration,
-	ServiceBuilderExport, ServiceBuilderImport, ServiceBuilderRevert,
+	ServiceBuilderExport, ServiceBuilderImport, ServiceBuilderRevert, ServiceBuilderExportState,
 	RuntimeGenesis, ChainSpecExtension, PruningMode, ChainSpec,
 };
 use network::{
@@ -40,6 +40,7 @@ use network::{
 	},
 };
 use primitives::H256;
+use sr_primitives::traits::Block as BlockT;
 
 use std::{
 	io::{Write, Read, Seek, Cursor, stdin, stdout, ErrorKind}, iter, fs::{self, File},
@@ -53,6 +54,7 @@ use structopt::{StructOpt, clap::AppSettings};
 pub use structopt::clap::App;
 use params::{
 	RunCmd, PurgeChainCmd, RevertCmd, ImportBlocksCmd, ExportBlocksCmd, BuildSpecCmd,
+	ExportStateCmd, ImportStateCmd, MigrateDbCmd, MigratePruningCmd,
 	NetworkConfigurationParams, MergeParameters, TransactionPoolParams,
 	NodeKeyParams, NodeKeyType, Cors,
 };
@@ -231,6 +233,18 @@ where
 		params::CoreParams::ImportBlocks(params) => ParseAndPrepare::ImportBlocks(
 			ParseAndPrepareImport { params, version }
 		),
+		params::CoreParams::ExportState(params) => ParseAndPrepare::ExportState(
+			ParseAndPrepareExportState { params, version }
+		),
+		params::CoreParams::ImportState(params) => ParseAndPrepare::ImportState(
+			ParseAndPrepareImportState { params, version }
+		),
+		params::CoreParams::MigrateDb(params) => ParseAndPrepare::MigrateDb(
+			ParseAndPrepareMigrateDb { params, version }
+		),
+		params::CoreParams::MigratePruning(params) => ParseAndPrepare::MigratePruning(
+			ParseAndPrepareMigratePruning { params, version }
+		),
 		params::CoreParams::PurgeChain(params) => ParseAndPrepare::PurgeChain(
 			ParseAndPreparePurge { params, version }
 		),
@@ -252,6 +266,14 @@ pub enum ParseAndPrepare<'a, CC, RP> {
 	ExportBlocks(ParseAndPrepareExport<'a>),
 	/// Command ready to import the chain.
 	ImportBlocks(ParseAndPrepareImport<'a>),
+	/// Command ready to export a state snapshot.
+	ExportState(ParseAndPrepareExportState<'a>),
+	/// Command ready to import a state snapshot.
+	ImportState(ParseAndPrepareImportState<'a>),
+	/// Command ready to migrate the database.
+	MigrateDb(ParseAndPrepareMigrateDb<'a>),
+	/// Command ready to change the pruning mode of the database.
+	MigratePruning(ParseAndPrepareMigratePruning<'a>),
 	/// Command ready to purge the chain.
 	PurgeChain(ParseAndPreparePurge<'a>),
 	/// Command ready to revert the chain.
@@ -403,6 +425,134 @@ impl<'a> ParseAndPrepareImport<'a> {
 	}
 }
 
+/// Command ready to export a state snapshot.
+pub struct ParseAndPrepareExportState<'a> {
+	params: ExportStateCmd,
+	version: &'a VersionInfo,
+}
+
+impl<'a> ParseAndPrepareExportState<'a> {
+	/// Runs the command and exports the state of a finalized block.
+	pub fn run_with_builder<C, G, E, F, B, S>(
+		self,
+		builder: F,
+		spec_factory: S,
+	) -> error::Result<()> where
+		S: FnOnce(&str) -> Result<Option<ChainSpec<G, E>>, String>,
+		F: FnOnce(Configuration<C, G, E>) -> Result<B, error::Error>,
+		B: ServiceBuilderExportState,
+		C: Default,
+		G: RuntimeGenesis,
+		E: ChainSpecExtension,
+	{
+		let config = create_config_with_db_path(spec_factory, &self.params.shared_params, self.version)?;
+
+		info!("DB path: {}", config.database_path.display());
+		let at = self.params.at;
+
+		let file: Box<dyn Write> = match self.params.output {
+			Some(filename) => Box::new(File::create(filename)?),
+			None => Box::new(stdout()),
+		};
+
+		builder(config)?.export_state(file, at.map(Into::into))?;
+		Ok(())
+	}
+}
+
+/// Command ready to import a state snapshot.
+pub struct ParseAndPrepareImportState<'a> {
+	params: ImportStateCmd,
+	version: &'a VersionInfo,
+}
+
+impl<'a> ParseAndPrepareImportState<'a> {
+	/// Runs the command and starts a new database from a state snapshot.
+	///
+	/// `B` is the block type of the chain; the database must not exist yet.
+	pub fn run<B, G, E, S>(
+		self,
+		spec_factory: S,
+	) -> error::Result<()> where
+		B: BlockT<Hash=H256>,
+		S: FnOnce(&str) -> Result<Option<ChainSpec<G, E>>, String>,
+		G: RuntimeGenesis,
+		E: ChainSpecExtension,
+	{
+		let hash = H256::from_str(self.params.hash.trim_start_matches("0x"))
+			.map_err(|_| format!("Invalid block hash {}", self.params.hash))?;
+		let mut config = create_config_with_db_path::<(), _, _, _>(
+			spec_factory, &self.params.shared_params, self.version
+		)?;
+		config.pruning = parse_pruning(self.params.pruning)?;
+
+		info!("DB path: {}", config.database_path.display());
+		let file: Box<dyn Read> = match self.params.input {
+			Some(filename) => Box::new(File::open(filename)?),
+			None => Box::new(stdin()),
+		};
+
+		service::chain_ops::import_state::<B, _, _, _>(&config, file, hash)?;
+		Ok(())
+	}
+}
+
+/// Command ready to migrate the database.
+pub struct ParseAndPrepareMigrateDb<'a> {
+	params: MigrateDbCmd,
+	version: &'a VersionInfo,
+}
+
+impl<'a> ParseAndPrepareMigrateDb<'a> {
+	/// Runs the command and moves the database to another kind of database.
+	pub fn run<G, E, S>(
+		self,
+		spec_factory: S,
+	) -> error::Result<()> where
+		S: FnOnce(&str) -> Result<Option<ChainSpec<G, E>>, String>,
+		G: RuntimeGenesis,
+		E: ChainSpecExtension,
+	{
+		let config = create_config_with_db_path::<(), _, _, _>(
+			spec_factory, &self.params.shared_params, self.version
+		)?;
+
+		info!("DB path: {}", config.database_path.display());
+		service::chain_ops::migrate_database(&config, self.params.to.into())?;
+		Ok(())
+	}
+}
+
+/// Command ready to change the pruning mode of the database.
+pub struct ParseAndPrepareMigratePruning<'a> {
+	params: MigratePruningCmd,
+	version: &'a VersionInfo,
+}
+
+impl<'a> ParseAndPrepareMigratePruning<'a> {
+	/// Runs the command and changes the pruning mode of the database.
+	///
+	/// `B` is the block type of the chain.
+	pub fn run<B, G, E, S>(
+		self,
+		spec_factory: S,
+	) -> error::Result<()> where
+		B: BlockT<Hash=H256>,
+		S: FnOnce(&str) -> Result<Option<ChainSpec<G, E>>, String>,
+		G: RuntimeGenesis,
+		E: ChainSpecExtension,
+	{
+		let config = create_config_with_db_path::<(), _, _, _>(
+			spec_factory, &self.params.shared_params, self.version
+		)?;
+		let pruning = parse_pruning(Some(self.params.pruning))?;
+
+		info!("DB path: {}", config.database_path.display());
+		service::chain_ops::change_pruning_mode::<B, _, _, _>(&config, pruning)?;
+		Ok(())
+	}
+}
+
 /// Command ready to purge the chain.
 pub struct ParseAndPreparePurge<'a> {
 	params: PurgeChainCmd,
@@ -484,6 +634,18 @@ impl<'a> ParseAndPrepareRevert<'a> {
 	}
 }
 
+/// Parse the `--pruning` parameter, a number of blocks to keep or `archive`.
+fn parse_pruning(pruning: Option<String>) -> error::Result<PruningMode> {
+	Ok(match pruning {
+		Some(ref s) if s == "archive" => PruningMode::ArchiveAll,
+		Some(ref s) if s == "archive-canonical" => PruningMode::ArchiveCanonical,
+		None => PruningMode::default(),
+		Some(s) => PruningMode::keep_blocks(s.parse()
+			.map_err(|_| error::Error::Input("Invalid pruning mode specified".to_string()))?
+		),
+	})
+}
+
 /// Create a `NodeKeyConfig` from the given `NodeKeyParams` in the context
 /// of an optional network config storage directory.
 fn node_key_config<P>(params: NodeKeyParams, net_config_dir: &Option<P>)
@@ -673,15 +835,10 @@ where
 	);
 
 	config.database_path = db_path(&base_path, config.chain_spec.id());
+	config.database = cli.shared_params.database.into();
 	config.database_cache_size = cli.database_cache_size;
 	config.state_cache_size = cli.state_cache_size;
-	config.pruning = match cli.pruning {
-		Some(ref s) if s == "archive" => PruningMode::ArchiveAll,
-		None => PruningMode::default(),
-		Some(s) => PruningMode::keep_blocks(s.parse()
-			.map_err(|_| error::Error::Input("Invalid pruning mode specified".to_string()))?
-		),
-	};
+	config.pruning = parse_pruning(cli.pruning)?;
 
 	let is_dev = cli.shared_params.dev;
 
@@ -819,6 +976,7 @@ where
 
 	let mut config = service::Configuration::default_with_spec(spec.clone());
 	config.database_path = db_path(&base_path, spec.id());
+	config.database = cli.database.into();
 
 	Ok(config)
 }
@@ -61,6 +61,27 @@ impl Into<service::config::WasmExecutionMethod> for WasmExecutionMethod {
 	}
 }
 
+arg_enum! {
+	/// Kind of database the node storage is kept in.
+	#[allow(missing_docs)]
+	#[derive(Debug, Clone, Copy)]
+	pub enum DatabaseKind {
+		// RocksDB.
+		RocksDb,
+		// Append-only, log-structured store.
+		LogDb,
+	}
+}
+
+impl Into<service::config::DatabaseKind> for DatabaseKind {
+	fn into(self) -> service::config::DatabaseKind {
+		match self {
+			DatabaseKind::RocksDb => service::config::DatabaseKind::RocksDb,
+			DatabaseKind::LogDb => service::config::DatabaseKind::LogDb,
+		}
+	}
+}
+
 arg_enum! {
 	/// Whether off-chain workers are enabled.
 	#[allow(missing_docs)]
@@ -90,6 +111,16 @@ pub struct SharedParams {
 	/// Sets a custom logging filter.
 	#[structopt(short = "l", long = "log", value_name = "LOG_PATTERN")]
 	pub log: Option<String>,
+
+	/// Select the kind of database the node storage is kept in.
+	#[structopt(
+		long = "database",
+		value_name = "DB",
+		possible_values = &DatabaseKind::variants(),
+		case_insensitive = true,
+		default_value = "RocksDb"
+	)]
+	pub database: DatabaseKind,
 }
 
 impl GetLogFilter for SharedParams {
@@ -366,9 +397,9 @@ pub struct RunCmd {
 	#[structopt(long = "rpc-cors", value_name = "ORIGINS", parse(try_from_str = parse_cors))]
 	pub rpc_cors: Option<Cors>,
 
-	/// Specify the pruning mode, a number of blocks to keep or 'archive'.
+	/// Specify the pruning mode, a number of blocks to keep, 'archive' or 'archive-canonical'.
 	///
-	/// Default is 256.
+	/// Must match the pruning mode of an existing database, see `migrate-pruning`. Default is 256.
 	#[structopt(long = "pruning", value_name = "PRUNING_MODE")]
 	pub pruning: Option<String>,
 
@@ -685,6 +716,91 @@ pub struct ImportBlocksCmd {
 
 impl_get_log_filter!(ImportBlocksCmd);
 
+/// The `export-state` command used to export a state snapshot.
+#[derive(Debug, StructOpt, Clone)]
+pub struct ExportStateCmd {
+	/// Output file name or stdout if unspecified.
+	#[structopt(parse(from_os_str))]
+	pub output: Option<PathBuf>,
+
+	/// Specify the block whose state is exported. It must be finalized and have a justification.
+	///
+	/// Default is the last finalized block with a justification.
+	#[structopt(long = "at", value_name = "BLOCK")]
+	pub at: Option<u32>,
+
+	#[allow(missing_docs)]
+	#[structopt(flatten)]
+	pub shared_params: SharedParams,
+}
+
+impl_get_log_filter!(ExportStateCmd);
+
+/// The `import-state` command used to start a new database from a state snapshot.
+#[derive(Debug, StructOpt, Clone)]
+pub struct ImportStateCmd {
+	/// Input file or stdin if unspecified.
+	#[structopt(parse(from_os_str))]
+	pub input: Option<PathBuf>,
+
+	/// Hash of the snapshot block, as logged by `export-state`.
+	///
+	/// Only a snapshot of the block with this hash is imported. The state is checked against the
+	/// state root of the block, but nothing else in the snapshot is trusted, so take the hash
+	/// from a source you trust, such as a synced node of your own.
+	#[structopt(long = "hash", value_name = "HASH")]
+	pub hash: String,
+
+	/// Specify the pruning mode of the new database, a number of blocks to keep, 'archive' or
+	/// 'archive-canonical'.
+	///
+	/// Must match the pruning mode the node is later run with. Default is 256.
+	#[structopt(long = "pruning", value_name = "PRUNING_MODE")]
+	pub pruning: Option<String>,
+
+	#[allow(missing_docs)]
+	#[structopt(flatten)]
+	pub shared_params: SharedParams,
+}
+
+impl_get_log_filter!(ImportStateCmd);
+
+/// The `migrate-db` command used to move the node database to another kind of database.
+#[derive(Debug, StructOpt, Clone)]
+pub struct MigrateDbCmd {
+	/// Kind of database to migrate to.
+	#[structopt(
+		long = "to",
+		value_name = "DB",
+		possible_values = &DatabaseKind::variants(),
+		case_insensitive = true
+	)]
+	pub to: DatabaseKind,
+
+	#[allow(missing_docs)]
+	#[structopt(flatten)]
+	pub shared_params: SharedParams,
+}
+
+impl_get_log_filter!(MigrateDbCmd);
+
+/// The `migrate-pruning` command used to change the pruning mode of the node database.
+#[derive(Debug, StructOpt, Clone)]
+pub struct MigratePruningCmd {
+	/// Pruning mode to change to, a number of blocks to keep or 'archive-canonical'.
+	///
+	/// A pruned database can become 'archive-canonical', keeping all state from now on. An
+	/// archive can become pruned, removing its historic state.
+	#[structopt(long = "pruning", value_name = "PRUNING_MODE")]
+	pub pruning: String,
+
+	#[allow(missing_docs)]
+	#[structopt(flatten)]
+	pub shared_params: SharedParams,
+}
+
+impl_get_log_filter!(MigratePruningCmd);
+
 /// The `revert` command used revert the chain to a previous state.
 #[derive(Debug, StructOpt, Clone)]
 pub struct RevertCmd {
@@ -732,6 +848,18 @@ pub enum CoreParams<CC, RP> {
 	/// Import blocks from file.
 	ImportBlocks(ImportBlocksCmd),
 
+	/// Export the state of a finalized block to a file.
+	ExportState(ExportStateCmd),
+
+	/// Start a new database from a state snapshot file.
+	ImportState(ImportStateCmd),
+
+	/// Move the node database to another kind of database.
+	MigrateDb(MigrateDbCmd),
+
+	/// Change the pruning mode of the node database.
+	MigratePruning(MigratePruningCmd),
+
 	/// Revert chain to the previous state.
 	Revert(RevertCmd),
 
@@ -765,6 +893,26 @@ impl<CC, RP> StructOpt for CoreParams<CC, RP> where
 			ImportBlocksCmd::augment_clap(SubCommand::with_name("import-blocks"))
 				.about("Import blocks from file.")
 		)
+		.subcommand(
+			ExportStateCmd::augment_clap(SubCommand::with_name("export-state"))
+				.about("Export the state of a finalized block to a file.")
+		)
+		.subcommand(
+			ImportStateCmd::augment_clap(SubCommand::with_name("import-state"))
+				.about("Start a new database from a state snapshot file. The node then syncs \
+						forward from the snapshot block."
+					)
+		)
+		.subcommand(
+			MigrateDbCmd::augment_clap(SubCommand::with_name("migrate-db"))
+				.about("Move the node database to another kind of database. The current \
+						database is kept as a backup next to the new one."
+					)
+		)
+		.subcommand(
+			MigratePruningCmd::augment_clap(SubCommand::with_name("migrate-pruning"))
+				.about("Change the pruning mode of the node database without resyncing.")
+		)
 		.subcommand(
 			RevertCmd::augment_clap(SubCommand::with_name("revert"))
 				.about("Revert chain to the previous state.")
@@ -783,6 +931,14 @@ impl<CC, RP> StructOpt for CoreParams<CC, RP> where
 				CoreParams::ExportBlocks(ExportBlocksCmd::from_clap(matches)),
 			("import-blocks", Some(matches)) =>
 				CoreParams::ImportBlocks(ImportBlocksCmd::from_clap(matches)),
+			("export-state", Some(matches)) =>
+				CoreParams::ExportState(ExportStateCmd::from_clap(matches)),
+			("import-state", Some(matches)) =>
+				CoreParams::ImportState(ImportStateCmd::from_clap(matches)),
+			("migrate-db", Some(matches)) =>
+				CoreParams::MigrateDb(MigrateDbCmd::from_clap(matches)),
+			("migrate-pruning", Some(matches)) =>
+				CoreParams::MigratePruning(MigratePruningCmd::from_clap(matches)),
 			("revert", Some(matches)) => CoreParams::Revert(RevertCmd::from_clap(matches)),
 			("purge-chain", Some(matches)) =>
 				CoreParams::PurgeChain(PurgeChainCmd::from_clap(matches)),
@@ -799,6 +955,10 @@ impl<CC, RP> GetLogFilter for CoreParams<CC, RP> where CC: GetLogFilter {
 			CoreParams::BuildSpec(c) => c.get_log_filter(),
 			CoreParams::ExportBlocks(c) => c.get_log_filter(),
 			CoreParams::ImportBlocks(c) => c.get_log_filter(),
+			CoreParams::ExportState(c) => c.get_log_filter(),
+			CoreParams::ImportState(c) => c.get_log_filter(),
+			CoreParams::MigrateDb(c) => c.get_log_filter(),
+			CoreParams::MigratePruning(c) => c.get_log_filter(),
 			CoreParams::PurgeChain(c) => c.get_log_filter(),
 			CoreParams::Revert(c) => c.get_log_filter(),
 			CoreParams::Custom(c) => c.get_log_filter(),
@@ -0,0 +1,290 @@
+// Copyright 2019 Parity Technologies (UK) Ltd.
+// This file is part of Substrate.
+
+// Substrate is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Substrate is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Substrate.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Key-value databases the client storage can be kept in.
+//!
+//! [`Database`] is the interface a store has to provide: numbered columns, atomic transactions
+//! and iteration over a column. [`KvdbDatabase`] provides it for any `kvdb` database, such as
+//! RocksDB, and [`AsKeyValueDB`] runs the full and light storages, which are written against
+//! `kvdb`, on top of any other [`Database`].
+//!
+//! The storages are not ported to [`Database`] themselves. They share `kvdb` with the state
+//! database, the changes trie storage, the offchain storage and the test helpers, so moving
+//! them means moving all of those at once. Until then, `open_database` wraps every store but
+//! RocksDB in [`AsKeyValueDB`]. The shim costs a copy of each value read and an adapter of
+//! each `kvdb` transaction, and it maps the `Option<u32>` columns of `kvdb` to [`ColumnId`].
+//! A new store only has to implement [`Database`]; nothing in the storages changes with it.
+
+use std::{io, sync::Arc};
+
+use kvdb::{KeyValueDB, DBTransaction, DBOp};
+use log::error;
+use trie::DBValue;
+
+/// Index of a column.
+pub type ColumnId = u32;
+
+/// A single change of a transaction.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Change {
+	/// Set the value of a key.
+	Set(ColumnId, Vec<u8>, Vec<u8>),
+	/// Remove a key.
+	Remove(ColumnId, Vec<u8>),
+}
+
+/// A set of changes applied atomically, in order.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Transaction(pub Vec<Change>);
+
+impl Transaction {
+	/// Create an empty transaction.
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	/// Set the value of `key` in column `col`.
+	pub fn set(&mut self, col: ColumnId, key: &[u8], value: &[u8]) {
+		self.0.push(Change::Set(col, key.to_vec(), value.to_vec()))
+	}
+
+	/// Remove `key` from column `col`.
+	pub fn remove(&mut self, col: ColumnId, key: &[u8]) {
+		self.0.push(Change::Remove(col, key.to_vec()))
+	}
+}
+
+/// A key-value database with numbered columns.
+pub trait Database: Send + Sync {
+	/// Apply all changes of the transaction, or none of them.
+	fn commit(&self, transaction: Transaction) -> io::Result<()>;
+
+	/// Get the value of `key` in column `col`.
+	fn get(&self, col: ColumnId, key: &[u8]) -> io::Result<Option<Vec<u8>>>;
+
+	/// Iterate over all entries of column `col`, in key order.
+	fn iter<'a>(&'a self, col: ColumnId) -> Box<dyn Iterator<Item=io::Result<(Vec<u8>, Vec<u8>)>> + 'a>;
+
+	/// Iterate over the entries of column `col` whose key starts with `prefix`, in key order.
+	fn iter_from_prefix<'a>(
+		&'a self,
+		col: ColumnId,
+		prefix: &'a [u8],
+	) -> Box<dyn Iterator<Item=io::Result<(Vec<u8>, Vec<u8>)>> + 'a> {
+		Box::new(self.iter(col).filter(move |entry| match entry {
+			Ok((key, _)) => key.starts_with(prefix),
+			Err(_) => true,
+		}))
+	}
+
+	/// Make sure all committed transactions are on disk.
+	fn flush(&self) -> io::Result<()> {
+		Ok(())
+	}
+}
+
+/// A `kvdb` database used as a [`Database`].
+pub struct KvdbDatabase(pub Arc<dyn KeyValueDB>);
+
+impl Database for KvdbDatabase {
+	fn commit(&self, transaction: Transaction) -> io::Result<()> {
+		let mut tx = DBTransaction::new();
+		for change in transaction.0 {
+			match change {
+				Change::Set(col, key, value) => tx.put_vec(Some(col), &key, value),
+				Change::Remove(col, key) => tx.delete(Some(col), &key),
+			}
+		}
+		self.0.write(tx)
+	}
+
+	fn get(&self, col: ColumnId, key: &[u8]) -> io::Result<Option<Vec<u8>>> {
+		self.0.get(Some(col), key).map(|value| value.map(|v| v.into_vec()))
+	}
+
+	fn iter<'a>(&'a self, col: ColumnId) -> Box<dyn Iterator<Item=io::Result<(Vec<u8>, Vec<u8>)>> + 'a> {
+		Box::new(self.0.iter(Some(col)).map(|(key, value)| Ok((key.into_vec(), value.into_vec()))))
+	}
+
+	fn iter_from_prefix<'a>(
+		&'a self,
+		col: ColumnId,
+		prefix: &'a [u8],
+	) -> Box<dyn Iterator<Item=io::Result<(Vec<u8>, Vec<u8>)>> + 'a> {
+		Box::new(self.0.iter_from_prefix(Some(col), prefix)
+			.map(|(key, value)| Ok((key.into_vec(), value.into_vec()))))
+	}
+
+	fn flush(&self) -> io::Result<()> {
+		self.0.flush()
+	}
+}
+
+/// A [`Database`] used as a `kvdb` database.
+///
+/// The client storage only uses numbered columns; the default `None` column is rejected.
+/// Iteration stops at the first entry that can't be read, after logging the error.
+pub struct AsKeyValueDB(pub Arc<dyn Database>);
+
+fn column(col: Option<u32>) -> io::Result<ColumnId> {
+	col.ok_or_else(|| io::Error::new(
+		io::ErrorKind::InvalidInput,
+		"The default column is not used by the client database",
+	))
+}
+
+fn entries<'a>(
+	iter: Box<dyn Iterator<Item=io::Result<(Vec<u8>, Vec<u8>)>> + 'a>,
+) -> Box<dyn Iterator<Item=(Box<[u8]>, Box<[u8]>)> + 'a> {
+	Box::new(iter
+		.map(|entry| entry.map_err(|e| error!(target: "db", "Error iterating database: {}", e)))
+		.take_while(Result::is_ok)
+		.filter_map(Result::ok)
+		.map(|(key, value)| (key.into_boxed_slice(), value.into_boxed_slice())))
+}
+
+impl KeyValueDB for AsKeyValueDB {
+	fn get(&self, col: Option<u32>, key: &[u8]) -> io::Result<Option<DBValue>> {
+		self.0.get(column(col)?, key).map(|value| value.map(|v| DBValue::from_slice(&v)))
+	}
+
+	fn get_by_prefix(&self, col: Option<u32>, prefix: &[u8]) -> Option<Box<[u8]>> {
+		self.iter_from_prefix(col, prefix).next().map(|(_, value)| value)
+	}
+
+	fn write_buffered(&self, transaction: DBTransaction) {
+		if let Err(e) = self.write(transaction) {
+			error!(target: "db", "Error writing to database: {}", e);
+		}
+	}
+
+	fn write(&self, transaction: DBTransaction) -> io::Result<()> {
+		let mut changes = Vec::with_capacity(transaction.ops.len());
+		for op in transaction.ops {
+			changes.push(match op {
+				DBOp::Insert { col, key, value } => Change::Set(column(col)?, key.to_vec(), value.to_vec()),
+				DBOp::Delete { col, key } => Change::Remove(column(col)?, key.to_vec()),
+			});
+		}
+		self.0.commit(Transaction(changes))
+	}
+
+	fn flush(&self) -> io::Result<()> {
+		self.0.flush()
+	}
+
+	fn iter<'a>(&'a self, col: Option<u32>) -> Box<dyn Iterator<Item=(Box<[u8]>, Box<[u8]>)> + 'a> {
+		match col {
+			Some(col) => entries(self.0.iter(col)),
+			None => Box::new(std::iter::empty()),
+		}
+	}
+
+	fn iter_from_prefix<'a>(
+		&'a self,
+		col: Option<u32>,
+		prefix: &'a [u8],
+	) -> Box<dyn Iterator<Item=(Box<[u8]>, Box<[u8]>)> + 'a> {
+		match col {
+			Some(col) => entries(self.0.iter_from_prefix(col, prefix)),
+			None => Box::new(std::iter::empty()),
+		}
+	}
+
+	fn restore(&self, _new_db: &str) -> io::Result<()> {
+		Err(io::Error::new(io::ErrorKind::Other, "Restoring is not supported"))
+	}
+}
+
+/// Copy every entry of the first `columns` columns of `from` into `to`.
+///
+/// Entries are written in transactions of at most `batch` entries. `progress` is called after
+/// each transaction with the column being copied and the number of entries copied so far.
+pub fn copy_database(
+	from: &dyn Database,
+	to: &dyn Database,
+	columns: u32,
+	batch: usize,
+	mut progress: impl FnMut(ColumnId, u64),
+) -> io::Result<u64> {
+	let mut copied = 0u64;
+	for col in 0..columns {
+		let mut transaction = Transaction::new();
+		for entry in from.iter(col) {
+			let (key, value) = entry?;
+			transaction.0.push(Change::Set(col, key, value));
+			copied += 1;
+			if transaction.0.len() >= batch {
+				to.commit(std::mem::replace(&mut transaction, Transaction::new()))?;
+				progress(col, copied);
+			}
+		}
+		if !transaction.0.is_empty() {
+			to.commit(transaction)?;
+		}
+		progress(col, copied);
+	}
+	to.flush()?;
+	Ok(copied)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn kvdb_roundtrip_through_database() {
+		let memory = Arc::new(kvdb_memorydb::create(2));
+		let db = AsKeyValueDB(Arc::new(KvdbDatabase(memory.clone())));
+
+		let mut tx = DBTransaction::new();
+		tx.put(Some(0), b"a", b"1");
+		tx.put(Some(1), b"pre-b", b"2");
+		tx.put(Some(1), b"pre-c", b"3");
+		tx.put(Some(1), b"other", b"4");
+		db.write(tx).unwrap();
+
+		assert_eq!(db.get(Some(0), b"a").unwrap().map(|v| v.to_vec()), Some(b"1".to_vec()));
+		assert_eq!(memory.get(Some(1), b"other").unwrap().map(|v| v.to_vec()), Some(b"4".to_vec()));
+		assert_eq!(db.iter_from_prefix(Some(1), b"pre").count(), 2);
+		assert!(db.get(None, b"a").is_err());
+
+		let mut tx = DBTransaction::new();
+		tx.delete(Some(0), b"a");
+		db.write(tx).unwrap();
+		assert_eq!(db.iter(Some(0)).count(), 0);
+	}
+
+	#[test]
+	fn copy_database_copies_all_columns() {
+		let from = KvdbDatabase(Arc::new(kvdb_memorydb::create(3)));
+		let mut tx = Transaction::new();
+		for i in 0..10u8 {
+			tx.set((i % 3) as ColumnId, &[i], &[i, i]);
+		}
+		from.commit(tx).unwrap();
+
+		let to = KvdbDatabase(Arc::new(kvdb_memorydb::create(3)));
+		let mut reports = Vec::new();
+		let copied = copy_database(&from, &to, 3, 2, |col, copied| reports.push((col, copied))).unwrap();
+
+		assert_eq!(copied, 10);
+		assert_eq!(reports.last(), Some(&(2, 10)));
+		for i in 0..10u8 {
+			assert_eq!(to.get((i % 3) as ColumnId, &[i]).unwrap(), Some(vec![i, i]));
+		}
+	}
+}
@@ -16,6 +16,10 @@
 
 //! Client backend that uses RocksDB database as storage.
 //!
+//! The database can also be any other [`database::Database`], such as the [`log_db`] store.
+//! The storages are written against `kvdb` and run on such a database through
+//! [`database::AsKeyValueDB`]; see the [`database`] module for why.
+//!
 //! # Canonicality vs. Finality
 //!
 //! Finality indicates that a block will not be reverted, according to the consensus algorithm,
@@ -26,15 +30,19 @@
 
 #![warn(missing_docs)]
 
+pub mod database;
 pub mod light;
+pub mod log_db;
 pub mod offchain;
 
 mod cache;
+mod pruning_mode;
+mod snapshot;
 mod storage_cache;
 mod utils;
 
 use std::sync::Arc;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::io;
 use std::collections::{HashMap, HashSet};
 
@@ -70,6 +78,8 @@ use header_metadata::{CachedHeaderMetadata, HeaderMetadata, HeaderMetadataCache}
 use crate::storage_cache::{CachingState, SharedCache, new_shared_cache};
 use log::{trace, debug, warn};
 pub use state_db::PruningMode;
+pub use crate::snapshot::StateSnapshot;
+pub use crate::pruning_mode::{change_pruning_mode, PruningProgress};
 
 #[cfg(feature = "test-helpers")]
 use client::in_mem::Backend as InMemoryBackend;
@@ -201,6 +211,32 @@ pub struct DatabaseSettings {
 	pub path: PathBuf,
 	/// Pruning mode.
 	pub pruning: PruningMode,
+	/// Kind of key-value database the storage is kept in.
+	pub kind: DatabaseKind,
+}
+
+/// Kind of key-value database.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DatabaseKind {
+	/// RocksDB.
+	RocksDb,
+	/// Append-only, log-structured store, see [`log_db`].
+	LogDb,
+}
+
+impl Default for DatabaseKind {
+	fn default() -> Self {
+		DatabaseKind::RocksDb
+	}
+}
+
+impl std::fmt::Display for DatabaseKind {
+	fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+		match self {
+			DatabaseKind::RocksDb => write!(f, "rocksdb"),
+			DatabaseKind::LogDb => write!(f, "logdb"),
+		}
+	}
 }
 
 /// Create an instance of db-backed client.
@@ -235,6 +271,76 @@ pub fn new_client<E, S, Block, RA>(
 	))
 }
 
+/// Open the database and bootstrap it from a state snapshot.
+///
+/// The snapshot must have been taken from the chain built from the given genesis storage.
+pub fn import_state<S, Block>(
+	settings: DatabaseSettings,
+	genesis_storage: S,
+	snapshot: StateSnapshot<Block>,
+) -> Result<(), client::error::Error>
+	where
+		Block: BlockT<Hash=H256>,
+		S: BuildStorage,
+{
+	use client::backend::{Backend as ClientBackend, BlockImportOperation};
+
+	let backend = Backend::<Block>::new(settings, CANONICALIZATION_DELAY)?;
+
+	let (genesis_storage, children_genesis_storage) = genesis_storage.build_storage()?;
+	let mut op = backend.begin_operation()?;
+	let state_root = op.reset_storage(genesis_storage, children_genesis_storage)?;
+	let genesis_block = client::genesis::construct_genesis_block::<Block>(state_root);
+	if genesis_block.header().hash() != snapshot.genesis.hash() {
+		return Err(client::error::Error::Backend(format!(
+			"State snapshot was taken from a chain with genesis {}, expected {}",
+			snapshot.genesis.hash(),
+			genesis_block.header().hash(),
+		)));
+	}
+
+	backend.import_state(snapshot)
+}
+
+/// Copy the storage kept in one database into an empty database of another kind.
+///
+/// `progress` is called with the column being copied and the number of entries copied so far.
+/// Returns the number of entries copied.
+pub fn migrate_database(
+	from: &DatabaseSettings,
+	to: &DatabaseSettings,
+	progress: impl FnMut(u32, u64),
+) -> ClientResult<u64> {
+	const BATCH: usize = 10_000;
+	let col_meta = columns::META.expect("meta column is numbered; qed");
+
+	let source = crate::utils::open_key_value_database(from, None)?;
+	let trie_column = match source.get(col_meta, meta_keys::TYPE).map_err(db_err)? {
+		Some(ref db_type) if db_type == b"full" => columns::STATE,
+		Some(ref db_type) if db_type == b"light" => None,
+		_ => return Err(ClientError::Backend(
+			format!("No client database found at {}", from.path.display())
+		)),
+	};
+
+	let destination = crate::utils::open_key_value_database(to, trie_column)?;
+	if destination.get(col_meta, meta_keys::TYPE).map_err(db_err)?.is_some() {
+		return Err(ClientError::Backend(
+			format!("Database at {} is not empty", to.path.display())
+		));
+	}
+
+	crate::database::copy_database(&*source, &*destination, crate::utils::NUM_COLUMNS, BATCH, progress)
+		.map_err(db_err)
+}
+
+/// Path a migrated database is built at before it takes the place of the database at `path`.
+pub fn migration_path(path: &Path) -> PathBuf {
+	let mut name = path.file_name().map(|name| name.to_os_string()).unwrap_or_default();
+	name.push(".migrating");
+	path.with_file_name(name)
+}
+
 pub(crate) mod columns {
 	pub const META: Option<u32> = crate::utils::COLUMN_META;
 	pub const STATE: Option<u32> = Some(1);
@@ -779,14 +885,8 @@ impl<Block: BlockT<Hash=H256>> Backend<Block> {
 	}
 
 	fn new_inner(config: DatabaseSettings, canonicalization_delay: u64) -> ClientResult<Self> {
-		#[cfg(feature = "kvdb-rocksdb")]
-		let db = crate::utils::open_database(&config, columns::META, "full")?;
-		#[cfg(not(feature = "kvdb-rocksdb"))]
-		let db = {
-			log::warn!("Running without the RocksDB feature. The database will NOT be saved.");
-			Arc::new(kvdb_memorydb::create(crate::utils::NUM_COLUMNS))
-		};
-		Self::from_kvdb(db as Arc<_>, canonicalization_delay, &config)
+		let db = crate::utils::open_database(&config, columns::META, "full", columns::STATE)?;
+		Self::from_kvdb(db, canonicalization_delay, &config)
 	}
 
 	/// Create new memory-backed client backend for tests.
@@ -806,6 +906,7 @@ impl<Block: BlockT<Hash=H256>> Backend<Block> {
 			state_cache_child_ratio: Some((50, 100)),
 			path: Default::default(),
 			pruning: PruningMode::keep_blocks(keep_blocks),
+			kind: DatabaseKind::default(),
 		};
 		Self::from_kvdb(
 			db,
@@ -823,6 +924,18 @@ impl<Block: BlockT<Hash=H256>> Backend<Block> {
 		let blockchain = BlockchainDb::new(db.clone())?;
 		let meta = blockchain.meta.clone();
 		let map_e = |e: state_db::Error<io::Error>| ::client::error::Error::from(format!("State database error: {:?}", e));
+		// The state database takes a database without pruning metadata to be new, but one with
+		// blocks is an `ArchiveAll` database created before the pruning mode was recorded.
+		let has_blocks = meta.read().genesis_hash != Default::default();
+		if has_blocks && config.pruning != PruningMode::ArchiveAll &&
+			state_db::stored_pruning_mode(&StateMetaDb(&*db)).map_err(map_e)?.is_none()
+		{
+			return Err(::client::error::Error::from(format!(
+				"State database error: database uses {} pruning, {} was requested",
+				PruningMode::ArchiveAll.id(),
+				config.pruning.id(),
+			)));
+		}
 		let state_db: StateDb<_, _> = StateDb::new(config.pruning.clone(), &StateMetaDb(&*db)).map_err(map_e)?;
 		let storage_db = StorageDb {
 			db: db.clone(),
@@ -1484,7 +1597,7 @@ impl<Block> client::backend::Backend<Block, Blake2Hasher> for Backend<Block> whe
 		match self.blockchain.header(block) {
 			Ok(Some(ref hdr)) => {
 				let hash = hdr.hash();
-				if let Ok(()) = self.storage.state_db.pin(&hash) {
+				if let Ok(()) = self.storage.state_db.pin(&hash, hdr.number().saturated_into::<u64>()) {
 					let root = H256::from_slice(hdr.state_root().as_ref());
 					let db_state = DbState::new(self.storage.clone(), root);
 					let state = RefTrackingState::new(db_state, self.storage.clone(), Some(hash.clone()));
@@ -0,0 +1,758 @@
+// Copyright 2019 Parity Technologies (UK) Ltd.
+// This file is part of Substrate.
+
+// Substrate is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Substrate is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Substrate.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Append-only, log-structured key-value store.
+//!
+//! Every committed transaction is appended to a single log file as one checksummed record, so
+//! existing data is never rewritten. An in-memory index maps each live key to the position of
+//! its value in the log, and a read takes a single positional read, which does not wait for
+//! other reads. A record cut short by a crash is dropped when the log is opened again.
+//!
+//! Trie nodes are stored under the hash of their content, so in a content-addressed column a
+//! key always maps to the same value. Setting such a key again is skipped rather than
+//! appending a copy of the node. Pruned nodes and overwritten values leave garbage behind;
+//! once garbage takes more than half of the log, a background thread copies the live entries
+//! to a new log. Commits go on meanwhile, and the records they append are moved over to the
+//! new log when it replaces the old one.
+
+use std::collections::{BTreeMap, HashSet};
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, BufReader, BufWriter, Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, atomic::{AtomicBool, Ordering}};
+use std::thread;
+
+use log::{info, warn};
+use parking_lot::Mutex;
+use primitives::twox_64;
+
+use crate::database::{Change, ColumnId, Database, Transaction};
+
+/// Name of the log inside the database directory.
+pub const LOG_FILE: &str = "log.db";
+/// Name of the log being written by a compaction.
+const COMPACTION_FILE: &str = "log.db.compact";
+
+/// Logs with less garbage than this are not compacted.
+const MIN_COMPACTION_GARBAGE: u64 = 64 * 1024 * 1024;
+/// Number of entries per record written by a compaction.
+const COMPACTION_RECORD_ENTRIES: usize = 4096;
+
+/// Length of the body length and checksum preceding each record.
+const RECORD_HEADER_LEN: u64 = 12;
+const TAG_SET: u8 = 0;
+const TAG_REMOVE: u8 = 1;
+
+/// Settings of a `LogDb`.
+#[derive(Debug, Clone)]
+pub struct LogDbConfig {
+	/// Number of columns.
+	pub columns: u32,
+	/// Columns whose keys are hashes of their values, such as the trie node column.
+	pub content_addressed: Vec<ColumnId>,
+}
+
+/// Position of a value in the log.
+#[derive(Debug, Clone, Copy)]
+struct Location {
+	offset: u64,
+	len: u32,
+}
+
+/// An entry of a record body: column, key and, unless removed, the value position in the body.
+type BodyEntry = (ColumnId, Vec<u8>, Option<(usize, u32)>);
+
+type Index = Vec<BTreeMap<Vec<u8>, Location>>;
+
+struct Inner {
+	/// The log. Reads holding the log replaced by a compaction finish on the old file.
+	file: Arc<File>,
+	/// End of the last complete record.
+	len: u64,
+	/// Bytes of the log taken by values that are no longer live.
+	garbage: u64,
+	index: Index,
+}
+
+/// A new log holding the live entries of the log up to `upto`.
+struct Compaction {
+	file: File,
+	len: u64,
+	index: Index,
+	upto: u64,
+	/// Garbage of the log up to `upto`, which the new log drops.
+	garbage: u64,
+}
+
+struct Shared {
+	path: PathBuf,
+	config: LogDbConfig,
+	inner: Mutex<Inner>,
+	/// Held for the whole of a compaction, so that only one runs at a time.
+	compaction: Mutex<()>,
+	/// Whether a background compaction is running.
+	compacting: AtomicBool,
+}
+
+/// Append-only, log-structured key-value store.
+pub struct LogDb {
+	shared: Arc<Shared>,
+}
+
+impl LogDb {
+	/// Open the store in directory `path`, creating it if needed.
+	pub fn open(path: &Path, config: LogDbConfig) -> io::Result<Self> {
+		fs::create_dir_all(path)?;
+
+		// A compaction that did not finish never replaced the log.
+		match fs::remove_file(path.join(COMPACTION_FILE)) {
+			Err(ref e) if e.kind() == io::ErrorKind::NotFound => {},
+			result => result?,
+		}
+
+		let file = OpenOptions::new()
+			.read(true)
+			.write(true)
+			.create(true)
+			.open(path.join(LOG_FILE))?;
+
+		let mut inner = Inner {
+			file: Arc::new(file),
+			len: 0,
+			garbage: 0,
+			index: vec![BTreeMap::new(); config.columns as usize],
+		};
+		inner.replay()?;
+
+		Ok(LogDb {
+			shared: Arc::new(Shared {
+				path: path.to_path_buf(),
+				config,
+				inner: Mutex::new(inner),
+				compaction: Mutex::new(()),
+				compacting: AtomicBool::new(false),
+			}),
+		})
+	}
+
+	/// Copy the live entries to a new log, dropping all garbage.
+	pub fn compact(&self) -> io::Result<()> {
+		self.shared.compact()
+	}
+
+	/// Compact the log on a background thread, unless a compaction is running already.
+	fn compact_in_background(&self) {
+		if self.shared.compacting.swap(true, Ordering::SeqCst) {
+			return;
+		}
+
+		let shared = self.shared.clone();
+		let spawned = thread::Builder::new()
+			.name("log-db-compaction".into())
+			.spawn(move || {
+				if let Err(e) = shared.compact() {
+					warn!(target: "db", "Failed to compact {}: {}", shared.path.display(), e);
+				}
+				shared.compacting.store(false, Ordering::SeqCst);
+			});
+		if let Err(e) = spawned {
+			warn!(target: "db", "Failed to start compacting {}: {}", self.shared.path.display(), e);
+			self.shared.compacting.store(false, Ordering::SeqCst);
+		}
+	}
+
+	fn check_column(&self, col: ColumnId) -> io::Result<()> {
+		if col < self.shared.config.columns {
+			Ok(())
+		} else {
+			Err(invalid_column(col))
+		}
+	}
+
+	fn keys(&self, col: ColumnId, prefix: &[u8]) -> io::Result<Vec<Vec<u8>>> {
+		self.check_column(col)?;
+		let inner = self.shared.inner.lock();
+		Ok(inner.index[col as usize]
+			.range(prefix.to_vec()..)
+			.map(|(key, _)| key)
+			.take_while(|key| key.starts_with(prefix))
+			.cloned()
+			.collect())
+	}
+
+	fn iter_keys<'a>(
+		&'a self,
+		col: ColumnId,
+		keys: io::Result<Vec<Vec<u8>>>,
+	) -> Box<dyn Iterator<Item=io::Result<(Vec<u8>, Vec<u8>)>> + 'a> {
+		match keys {
+			// Keys removed since the iteration started are skipped.
+			Ok(keys) => Box::new(keys.into_iter().filter_map(move |key| match self.get(col, &key) {
+				Ok(Some(value)) => Some(Ok((key, value))),
+				Ok(None) => None,
+				Err(e) => Some(Err(e)),
+			})),
+			Err(e) => Box::new(std::iter::once(Err(e))),
+		}
+	}
+}
+
+impl Shared {
+	fn compact(&self) -> io::Result<()> {
+		let _compaction = self.compaction.lock();
+		let compaction_path = self.path.join(COMPACTION_FILE);
+
+		let result = self.copy_live(&compaction_path)
+			.and_then(|compaction| self.replace_log(&compaction_path, compaction));
+		if result.is_err() {
+			let _ = fs::remove_file(&compaction_path);
+		}
+		result
+	}
+
+	/// Copy the live entries of the log to a new log at `compaction_path`.
+	///
+	/// The index is only locked to take a copy of it, so commits go on during the copy.
+	fn copy_live(&self, compaction_path: &Path) -> io::Result<Compaction> {
+		let (file, live, upto, garbage) = {
+			let inner = self.inner.lock();
+			(inner.file.clone(), inner.index.clone(), inner.len, inner.garbage)
+		};
+
+		info!(
+			target: "db",
+			"Compacting {} ({} of {} bytes are garbage)",
+			self.path.display(),
+			garbage,
+			upto,
+		);
+
+		let out = OpenOptions::new()
+			.read(true)
+			.write(true)
+			.create(true)
+			.truncate(true)
+			.open(compaction_path)?;
+		let mut out = BufWriter::new(out);
+		let mut index = vec![BTreeMap::new(); live.len()];
+		let mut len = 0u64;
+
+		let mut body = Vec::new();
+		let mut entries = Vec::new();
+		for (col, keys) in live.iter().enumerate() {
+			for (key, location) in keys {
+				let value = read_value(&file, *location)?;
+				let position = encode_change(&mut body, col as ColumnId, key, Some(&value));
+				entries.push((col as ColumnId, key.clone(), position));
+
+				if entries.len() >= COMPACTION_RECORD_ENTRIES {
+					len = write_record(&mut out, len, &body, entries.drain(..), &mut index)?;
+					body.clear();
+				}
+			}
+		}
+		if !entries.is_empty() {
+			len = write_record(&mut out, len, &body, entries.drain(..), &mut index)?;
+		}
+
+		out.flush()?;
+		let file = out.into_inner().map_err(|e| e.into_error())?;
+		Ok(Compaction { file, len, index, upto, garbage })
+	}
+
+	/// Move the records committed since `compaction` started over to the new log and make it
+	/// the log.
+	fn replace_log(&self, compaction_path: &Path, compaction: Compaction) -> io::Result<()> {
+		let Compaction { file, len, index, upto, garbage } = compaction;
+		let mut inner = self.inner.lock();
+
+		let mut tail = vec![0u8; (inner.len - upto) as usize];
+		read_exact_at(&inner.file, &mut tail, upto)?;
+		write_all_at(&file, &tail, len)?;
+		file.sync_all()?;
+		fs::rename(compaction_path, self.path.join(LOG_FILE))?;
+
+		for (col, keys) in inner.index.iter_mut().enumerate() {
+			for (key, location) in keys.iter_mut() {
+				if location.offset >= upto {
+					location.offset = location.offset - upto + len;
+				} else {
+					*location = *index[col].get(key)
+						.expect("values before `upto` were live when the copy started and so were copied; qed");
+				}
+			}
+		}
+		inner.file = Arc::new(file);
+		inner.len = len + tail.len() as u64;
+		inner.garbage = inner.garbage.saturating_sub(garbage);
+		Ok(())
+	}
+}
+
+impl Database for LogDb {
+	fn commit(&self, transaction: Transaction) -> io::Result<()> {
+		let mut inner = self.shared.inner.lock();
+
+		let mut body = Vec::new();
+		let mut entries = Vec::with_capacity(transaction.0.len());
+		let mut touched = HashSet::new();
+		for change in transaction.0 {
+			match change {
+				Change::Set(col, key, value) => {
+					self.check_column(col)?;
+					let present = inner.index[col as usize].contains_key(&key);
+					let content_addressed = self.shared.config.content_addressed.contains(&col);
+					if present && content_addressed && !touched.contains(&(col, key.clone())) {
+						continue;
+					}
+					let position = encode_change(&mut body, col, &key, Some(&value));
+					touched.insert((col, key.clone()));
+					entries.push((col, key, position));
+				},
+				Change::Remove(col, key) => {
+					self.check_column(col)?;
+					let present = inner.index[col as usize].contains_key(&key);
+					if !present && !touched.contains(&(col, key.clone())) {
+						continue;
+					}
+					let position = encode_change(&mut body, col, &key, None);
+					touched.insert((col, key.clone()));
+					entries.push((col, key, position));
+				},
+			}
+		}
+
+		if entries.is_empty() {
+			return Ok(());
+		}
+
+		let start = inner.len;
+		let record = encode_record(&body)?;
+		if let Err(e) = write_all_at(&inner.file, &record, start) {
+			// Leave no partial record behind the last complete one.
+			let _ = inner.file.set_len(start);
+			return Err(e);
+		}
+		inner.len += record.len() as u64;
+
+		for (col, key, position) in entries {
+			let location = position.map(|(offset, len)| Location {
+				offset: start + RECORD_HEADER_LEN + offset as u64,
+				len,
+			});
+			inner.apply(col, key, location);
+		}
+
+		let compact = inner.garbage > MIN_COMPACTION_GARBAGE && inner.garbage * 2 > inner.len;
+		drop(inner);
+		if compact {
+			self.compact_in_background();
+		}
+		Ok(())
+	}
+
+	fn get(&self, col: ColumnId, key: &[u8]) -> io::Result<Option<Vec<u8>>> {
+		self.check_column(col)?;
+		let (file, location) = {
+			let inner = self.shared.inner.lock();
+			match inner.index[col as usize].get(key) {
+				Some(location) => (inner.file.clone(), *location),
+				None => return Ok(None),
+			}
+		};
+		read_value(&file, location).map(Some)
+	}
+
+	fn iter<'a>(&'a self, col: ColumnId) -> Box<dyn Iterator<Item=io::Result<(Vec<u8>, Vec<u8>)>> + 'a> {
+		self.iter_keys(col, self.keys(col, &[]))
+	}
+
+	fn iter_from_prefix<'a>(
+		&'a self,
+		col: ColumnId,
+		prefix: &'a [u8],
+	) -> Box<dyn Iterator<Item=io::Result<(Vec<u8>, Vec<u8>)>> + 'a> {
+		self.iter_keys(col, self.keys(col, prefix))
+	}
+
+	fn flush(&self) -> io::Result<()> {
+		let file = self.shared.inner.lock().file.clone();
+		file.sync_data()
+	}
+}
+
+impl Inner {
+	/// Rebuild the index from the log, dropping a trailing incomplete or corrupted record.
+	fn replay(&mut self) -> io::Result<()> {
+		let file_len = self.file.metadata()?.len();
+		let mut reader = BufReader::new(self.file.try_clone()?);
+		reader.seek(SeekFrom::Start(0))?;
+		let mut position = 0u64;
+		loop {
+			let mut header = [0u8; RECORD_HEADER_LEN as usize];
+			if position + RECORD_HEADER_LEN > file_len || reader.read_exact(&mut header).is_err() {
+				break;
+			}
+			let mut body_len = [0u8; 4];
+			body_len.copy_from_slice(&header[..4]);
+			let body_len = u32::from_le_bytes(body_len) as u64;
+			if position + RECORD_HEADER_LEN + body_len > file_len {
+				break;
+			}
+
+			let mut body = vec![0u8; body_len as usize];
+			reader.read_exact(&mut body)?;
+			if twox_64(&body)[..] != header[4..] {
+				break;
+			}
+			let entries = match decode_body(&body, self.index.len() as u32) {
+				Some(entries) => entries,
+				None => break,
+			};
+
+			for (col, key, value) in entries {
+				let location = value.map(|(offset, len)| Location {
+					offset: position + RECORD_HEADER_LEN + offset as u64,
+					len,
+				});
+				self.apply(col, key, location);
+			}
+			position += RECORD_HEADER_LEN + body_len;
+		}
+		self.len = position;
+
+		if self.len < file_len {
+			warn!(
+				target: "db",
+				"Dropping {} bytes of incomplete or corrupted data at the end of the database log",
+				file_len - self.len,
+			);
+			self.file.set_len(self.len)?;
+		}
+		Ok(())
+	}
+
+	/// Point `key` at its new value, or remove it, and account for the garbage left behind.
+	fn apply(&mut self, col: ColumnId, key: Vec<u8>, location: Option<Location>) {
+		let key_len = key.len();
+		let index = &mut self.index[col as usize];
+		let previous = match location {
+			Some(location) => index.insert(key, location),
+			None => index.remove(&key),
+		};
+		if let Some(previous) = previous {
+			self.garbage += entry_len(key_len, Some(previous.len));
+		}
+		if location.is_none() {
+			self.garbage += entry_len(key_len, None);
+		}
+	}
+}
+
+fn invalid_column(col: ColumnId) -> io::Error {
+	io::Error::new(io::ErrorKind::InvalidInput, format!("Invalid column {}", col))
+}
+
+/// Length of an encoded change.
+fn entry_len(key_len: usize, value_len: Option<u32>) -> u64 {
+	9 + key_len as u64 + value_len.map_or(0, |len| 4 + len as u64)
+}
+
+/// Append a change to a record body, returning the position and length of the value.
+fn encode_change(body: &mut Vec<u8>, col: ColumnId, key: &[u8], value: Option<&[u8]>) -> Option<(usize, u32)> {
+	body.push(if value.is_some() { TAG_SET } else { TAG_REMOVE });
+	body.extend_from_slice(&col.to_le_bytes());
+	body.extend_from_slice(&(key.len() as u32).to_le_bytes());
+	body.extend_from_slice(key);
+	value.map(|value| {
+		body.extend_from_slice(&(value.len() as u32).to_le_bytes());
+		let position = body.len();
+		body.extend_from_slice(value);
+		(position, value.len() as u32)
+	})
+}
+
+/// Decode a record body, or `None` if it is malformed.
+fn decode_body(body: &[u8], columns: u32) -> Option<Vec<BodyEntry>> {
+	fn read_u32(body: &[u8], position: &mut usize) -> Option<u32> {
+		let bytes = body.get(*position..*position + 4)?;
+		*position += 4;
+		let mut buf = [0u8; 4];
+		buf.copy_from_slice(bytes);
+		Some(u32::from_le_bytes(buf))
+	}
+
+	let mut entries = Vec::new();
+	let mut position = 0;
+	while position < body.len() {
+		let tag = body[position];
+		position += 1;
+		let col = read_u32(body, &mut position)?;
+		if col >= columns {
+			return None;
+		}
+		let key_len = read_u32(body, &mut position)? as usize;
+		let key = body.get(position..position + key_len)?.to_vec();
+		position += key_len;
+
+		let value = match tag {
+			TAG_SET => {
+				let len = read_u32(body, &mut position)?;
+				let value_position = position;
+				position += len as usize;
+				if position > body.len() {
+					return None;
+				}
+				Some((value_position, len))
+			},
+			TAG_REMOVE => None,
+			_ => return None,
+		};
+		entries.push((col, key, value));
+	}
+	Some(entries)
+}
+
+/// Prefix a record body with its length and checksum.
+fn encode_record(body: &[u8]) -> io::Result<Vec<u8>> {
+	if body.len() > u32::max_value() as usize {
+		return Err(io::Error::new(io::ErrorKind::InvalidInput, "Transaction is too large"));
+	}
+	let mut record = Vec::with_capacity(RECORD_HEADER_LEN as usize + body.len());
+	record.extend_from_slice(&(body.len() as u32).to_le_bytes());
+	record.extend_from_slice(&twox_64(body));
+	record.extend_from_slice(body);
+	Ok(record)
+}
+
+/// Write a record at `start` of a new log and index its entries, returning the end of the log.
+fn write_record(
+	out: &mut impl Write,
+	start: u64,
+	body: &[u8],
+	entries: impl Iterator<Item=BodyEntry>,
+	index: &mut [BTreeMap<Vec<u8>, Location>],
+) -> io::Result<u64> {
+	let record = encode_record(body)?;
+	out.write_all(&record)?;
+	for (col, key, position) in entries {
+		if let Some((offset, len)) = position {
+			index[col as usize].insert(key, Location { offset: start + RECORD_HEADER_LEN + offset as u64, len });
+		}
+	}
+	Ok(start + record.len() as u64)
+}
+
+fn read_value(file: &File, location: Location) -> io::Result<Vec<u8>> {
+	let mut value = vec![0u8; location.len as usize];
+	read_exact_at(file, &mut value, location.offset)?;
+	Ok(value)
+}
+
+#[cfg(unix)]
+fn read_exact_at(file: &File, buf: &mut [u8], offset: u64) -> io::Result<()> {
+	std::os::unix::fs::FileExt::read_exact_at(file, buf, offset)
+}
+
+#[cfg(unix)]
+fn write_all_at(file: &File, buf: &[u8], offset: u64) -> io::Result<()> {
+	std::os::unix::fs::FileExt::write_all_at(file, buf, offset)
+}
+
+#[cfg(windows)]
+fn read_exact_at(file: &File, mut buf: &mut [u8], mut offset: u64) -> io::Result<()> {
+	use std::os::windows::fs::FileExt;
+
+	while !buf.is_empty() {
+		match file.seek_read(buf, offset) {
+			Ok(0) => return Err(io::Error::new(io::ErrorKind::UnexpectedEof, "failed to fill whole buffer")),
+			Ok(n) => {
+				buf = &mut buf[n..];
+				offset += n as u64;
+			},
+			Err(ref e) if e.kind() == io::ErrorKind::Interrupted => {},
+			Err(e) => return Err(e),
+		}
+	}
+	Ok(())
+}
+
+#[cfg(windows)]
+fn write_all_at(file: &File, mut buf: &[u8], mut offset: u64) -> io::Result<()> {
+	use std::os::windows::fs::FileExt;
+
+	while !buf.is_empty() {
+		match file.seek_write(buf, offset) {
+			Ok(0) => return Err(io::Error::new(io::ErrorKind::WriteZero, "failed to write whole buffer")),
+			Ok(n) => {
+				buf = &buf[n..];
+				offset += n as u64;
+			},
+			Err(ref e) if e.kind() == io::ErrorKind::Interrupted => {},
+			Err(e) => return Err(e),
+		}
+	}
+	Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn config() -> LogDbConfig {
+		LogDbConfig { columns: 2, content_addressed: vec![1] }
+	}
+
+	fn log_len(path: &Path) -> u64 {
+		fs::metadata(path.join(LOG_FILE)).unwrap().len()
+	}
+
+	#[test]
+	fn entries_survive_reopening() {
+		let dir = tempfile::tempdir().unwrap();
+		{
+			let db = LogDb::open(dir.path(), config()).unwrap();
+			let mut tx = Transaction::new();
+			tx.set(0, b"b", b"2");
+			tx.set(0, b"a", b"1");
+			tx.set(0, b"c", b"3");
+			db.commit(tx).unwrap();
+
+			let mut tx = Transaction::new();
+			tx.remove(0, b"c");
+			tx.set(0, b"a", b"4");
+			db.commit(tx).unwrap();
+		}
+
+		let db = LogDb::open(dir.path(), config()).unwrap();
+		assert_eq!(db.get(0, b"a").unwrap(), Some(b"4".to_vec()));
+		assert_eq!(db.get(0, b"c").unwrap(), None);
+		assert_eq!(
+			db.iter(0).collect::<io::Result<Vec<_>>>().unwrap(),
+			vec![(b"a".to_vec(), b"4".to_vec()), (b"b".to_vec(), b"2".to_vec())],
+		);
+		assert!(db.get(2, b"a").is_err());
+	}
+
+	#[test]
+	fn content_addressed_values_are_written_once() {
+		let dir = tempfile::tempdir().unwrap();
+		let db = LogDb::open(dir.path(), config()).unwrap();
+
+		let mut tx = Transaction::new();
+		tx.set(1, b"hash", b"node");
+		db.commit(tx.clone()).unwrap();
+		let len = log_len(dir.path());
+
+		db.commit(tx).unwrap();
+		assert_eq!(log_len(dir.path()), len);
+
+		// a node removed and inserted again in the same transaction stays.
+		let mut tx = Transaction::new();
+		tx.remove(1, b"hash");
+		tx.set(1, b"hash", b"node");
+		db.commit(tx).unwrap();
+		assert_eq!(db.get(1, b"hash").unwrap(), Some(b"node".to_vec()));
+	}
+
+	#[test]
+	fn incomplete_record_is_dropped() {
+		let dir = tempfile::tempdir().unwrap();
+		{
+			let db = LogDb::open(dir.path(), config()).unwrap();
+			let mut tx = Transaction::new();
+			tx.set(0, b"a", b"1");
+			db.commit(tx).unwrap();
+		}
+		let len = log_len(dir.path());
+		{
+			let mut file = OpenOptions::new().append(true).open(dir.path().join(LOG_FILE)).unwrap();
+			file.write_all(&[10, 0, 0, 0, 1, 2, 3]).unwrap();
+		}
+
+		let db = LogDb::open(dir.path(), config()).unwrap();
+		assert_eq!(log_len(dir.path()), len);
+		assert_eq!(db.get(0, b"a").unwrap(), Some(b"1".to_vec()));
+
+		let mut tx = Transaction::new();
+		tx.set(0, b"b", b"2");
+		db.commit(tx).unwrap();
+		drop(db);
+		let db = LogDb::open(dir.path(), config()).unwrap();
+		assert_eq!(db.get(0, b"b").unwrap(), Some(b"2".to_vec()));
+	}
+
+	#[test]
+	fn commits_during_compaction_are_kept() {
+		let dir = tempfile::tempdir().unwrap();
+		let db = LogDb::open(dir.path(), config()).unwrap();
+		for i in 0..10u8 {
+			let mut tx = Transaction::new();
+			tx.set(0, &[i], &[i; 16]);
+			tx.set(0, b"counter", &[i]);
+			db.commit(tx).unwrap();
+		}
+
+		let compaction_path = dir.path().join(COMPACTION_FILE);
+		let compaction = db.shared.copy_live(&compaction_path).unwrap();
+
+		let mut tx = Transaction::new();
+		tx.set(0, b"counter", &[10]);
+		tx.set(0, b"new", b"value");
+		tx.remove(0, &[0]);
+		db.commit(tx).unwrap();
+
+		db.shared.replace_log(&compaction_path, compaction).unwrap();
+		let check = |db: &LogDb| {
+			assert_eq!(db.get(0, b"counter").unwrap(), Some(vec![10]));
+			assert_eq!(db.get(0, b"new").unwrap(), Some(b"value".to_vec()));
+			assert_eq!(db.get(0, &[0]).unwrap(), None);
+			assert_eq!(db.get(0, &[9]).unwrap(), Some(vec![9; 16]));
+			assert_eq!(db.iter(0).count(), 11);
+		};
+		check(&db);
+		drop(db);
+
+		check(&LogDb::open(dir.path(), config()).unwrap());
+	}
+
+	#[test]
+	fn compaction_drops_garbage() {
+		let dir = tempfile::tempdir().unwrap();
+		let db = LogDb::open(dir.path(), config()).unwrap();
+		for i in 0..100u8 {
+			let mut tx = Transaction::new();
+			tx.set(0, b"counter", &[i; 32]);
+			tx.set(1, &[i], &[i]);
+			if i > 0 {
+				tx.remove(1, &[i - 1]);
+			}
+			db.commit(tx).unwrap();
+		}
+		let len = log_len(dir.path());
+
+		db.compact().unwrap();
+		assert!(log_len(dir.path()) < len / 10);
+		assert_eq!(db.get(0, b"counter").unwrap(), Some(vec![99; 32]));
+		assert_eq!(db.iter(1).count(), 1);
+		drop(db);
+
+		let db = LogDb::open(dir.path(), config()).unwrap();
+		assert_eq!(db.get(0, b"counter").unwrap(), Some(vec![99; 32]));
+		assert_eq!(db.get(1, &[99]).unwrap(), Some(vec![99]));
+		assert_eq!(db.get(1, &[98]).unwrap(), None);
+	}
+}
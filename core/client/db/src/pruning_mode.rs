@@ -0,0 +1,384 @@
+// Copyright 2019 Parity Technologies (UK) Ltd.
+// This file is part of Substrate.
+
+// Substrate is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Substrate is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Substrate.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Offline change of the pruning mode of a full database.
+
+use std::collections::HashSet;
+use std::io;
+use std::sync::Arc;
+
+use client::error::{Result as ClientResult, Error as ClientError};
+use codec::Decode;
+use hash_db::Prefix;
+use kvdb::{KeyValueDB, DBTransaction};
+use parking_lot::Mutex;
+use primitives::{H256, Blake2Hasher};
+use primitives::storage::well_known_keys;
+use sr_primitives::generic::BlockId;
+use sr_primitives::traits::{Block as BlockT, Header as HeaderT, SaturatedConversion};
+use state_machine::backend::Backend as StateBackend;
+use trie::prefixed_key;
+
+use crate::utils::{self, db_err};
+use crate::{DatabaseSettings, DbState, PruningMode, StateMetaDb, apply_state_commit, columns};
+
+/// Number of state entries checked between writes when removing pruned nodes.
+const SWEEP_BATCH: u64 = 10_000;
+/// Number of keys visited between progress reports when looking for live nodes.
+const MARK_REPORT_INTERVAL: u64 = 100_000;
+
+/// Progress of a pruning mode change.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PruningProgress {
+	/// Number of live state nodes found so far.
+	Marking(u64),
+	/// Number of state entries checked so far, and how many of them were removed.
+	Sweeping {
+		/// Entries checked.
+		checked: u64,
+		/// Entries removed.
+		removed: u64,
+	},
+}
+
+/// Trie node storage that remembers the database keys of all nodes read.
+struct MarkingStorage {
+	db: Arc<dyn KeyValueDB>,
+	marked: Mutex<HashSet<Vec<u8>>>,
+	missing: Mutex<Option<H256>>,
+}
+
+impl state_machine::Storage<Blake2Hasher> for MarkingStorage {
+	fn get(&self, key: &H256, prefix: Prefix) -> Result<Option<state_machine::DBValue>, String> {
+		let db_key = prefixed_key::<Blake2Hasher>(key, prefix);
+		let value = self.db.get(columns::STATE, &db_key).map_err(|e| format!("Database backend error: {:?}", e))?;
+		match value {
+			Some(value) => {
+				self.marked.lock().insert(db_key);
+				Ok(Some(state_machine::DBValue::from_slice(&value)))
+			},
+			None => {
+				self.missing.lock().get_or_insert(*key);
+				Ok(None)
+			},
+		}
+	}
+}
+
+/// Change the pruning mode of an existing full database.
+///
+/// See `state_db::change_pruning_mode` for the supported changes. When an archive becomes
+/// constrained, only the state of the last canonical block and of the blocks above it is kept,
+/// and the pruning window grows back as new blocks are finalized. The new mode is recorded
+/// before the historic state is removed, so an interrupted removal only leaves unused nodes
+/// behind.
+pub fn change_pruning_mode<Block: BlockT<Hash=H256>>(
+	settings: &DatabaseSettings,
+	to: PruningMode,
+	mut progress: impl FnMut(PruningProgress),
+) -> ClientResult<()> {
+	let map_e = |e: state_db::Error<io::Error>| ClientError::from(format!("State database error: {:?}", e));
+
+	let db = utils::open_database(settings, columns::META, "full", columns::STATE)?;
+	let state_meta = StateMetaDb(&*db);
+	let meta = utils::read_meta::<Block>(&*db, columns::META, columns::HEADER)?;
+	if meta.genesis_hash == Default::default() {
+		return Err(ClientError::Backend(format!("No blocks in database at {}", settings.path.display())));
+	}
+	// A database with blocks but without pruning metadata is an `ArchiveAll` database.
+	let from = state_db::stored_pruning_mode(&state_meta).map_err(map_e)?.unwrap_or(PruningMode::ArchiveAll);
+
+	let canonical = match state_db::last_canonicalized(&state_meta).map_err(map_e)? {
+		Some(canonical) => canonical,
+		None => (meta.finalized_hash, meta.finalized_number.saturated_into::<u64>()),
+	};
+	let unfinalized = match from {
+		PruningMode::ArchiveAll => unfinalized_blocks::<Block>(&*db, &canonical)?,
+		_ => Vec::new(),
+	};
+
+	let changes = state_db::change_pruning_mode::<_, Vec<u8>, _>(&state_meta, &to, canonical, &unfinalized)
+		.map_err(map_e)?;
+	let mut transaction = DBTransaction::new();
+	apply_state_commit(&mut transaction, state_db::CommitSet { data: Default::default(), meta: changes });
+	db.write(transaction).map_err(db_err)?;
+
+	if let PruningMode::Constrained(_) = to {
+		let kept = std::iter::once(canonical.0).chain(unfinalized.iter().map(|(hash, _, _)| *hash));
+		let marked = mark::<Block>(&db, kept, &mut progress)?;
+		sweep(&*db, &marked, &mut progress)?;
+	}
+
+	db.flush().map_err(db_err)?;
+	Ok(())
+}
+
+/// Blocks above `canonical` that descend from it, as `(hash, number, parent_hash)` sorted by number.
+fn unfinalized_blocks<Block: BlockT<Hash=H256>>(
+	db: &dyn KeyValueDB,
+	canonical: &(H256, u64),
+) -> ClientResult<Vec<(H256, u64, H256)>> {
+	let mut headers = Vec::new();
+	for (key, header) in db.iter(columns::HEADER) {
+		if utils::lookup_key_to_number::<u32>(&key)? as u64 <= canonical.1 {
+			continue;
+		}
+		let header = Block::Header::decode(&mut &header[..])
+			.map_err(|e| ClientError::Backend(format!("Error decoding header: {}", e.what())))?;
+		headers.push((header.hash(), (*header.number()).saturated_into::<u64>(), *header.parent_hash()));
+	}
+	headers.sort_by_key(|(_, number, _)| *number);
+
+	let mut descendants: HashSet<H256> = std::iter::once(canonical.0).collect();
+	headers.retain(|(hash, _, parent_hash)| {
+		let descends = descendants.contains(parent_hash);
+		if descends {
+			descendants.insert(*hash);
+		}
+		descends
+	});
+	Ok(headers)
+}
+
+/// Find the database keys of all trie nodes of the states of the given blocks.
+fn mark<Block: BlockT<Hash=H256>>(
+	db: &Arc<dyn KeyValueDB>,
+	blocks: impl Iterator<Item=H256>,
+	progress: &mut impl FnMut(PruningProgress),
+) -> ClientResult<HashSet<Vec<u8>>> {
+	let storage = Arc::new(MarkingStorage {
+		db: db.clone(),
+		marked: Mutex::new(HashSet::new()),
+		missing: Mutex::new(None),
+	});
+
+	for hash in blocks {
+		let header = utils::read_header::<Block>(&**db, columns::KEY_LOOKUP, columns::HEADER, BlockId::Hash(hash))?
+			.ok_or_else(|| ClientError::UnknownBlock(format!("Missing header of block {}", hash)))?;
+		let state = DbState::new(storage.clone() as Arc<dyn state_machine::Storage<Blake2Hasher>>, *header.state_root());
+
+		let mut visited = 0u64;
+		let mut visit = |_: &[u8]| {
+			visited += 1;
+			if visited % MARK_REPORT_INTERVAL == 0 {
+				progress(PruningProgress::Marking(storage.marked.lock().len() as u64));
+			}
+		};
+		state.for_keys_with_prefix(&[], &mut visit);
+		for child_key in state.keys(well_known_keys::CHILD_STORAGE_KEY_PREFIX) {
+			state.for_keys_in_child_storage(&child_key, &mut visit);
+		}
+
+		// trie iteration stops at a missing node, and the nodes below it would be removed.
+		if let Some(missing) = *storage.missing.lock() {
+			return Err(ClientError::Backend(
+				format!("State of block {} is incomplete, node {} is missing", hash, missing)
+			));
+		}
+		progress(PruningProgress::Marking(storage.marked.lock().len() as u64));
+	}
+
+	let marked = std::mem::replace(&mut *storage.marked.lock(), HashSet::new());
+	Ok(marked)
+}
+
+/// Remove all state entries that are not marked.
+fn sweep(
+	db: &dyn KeyValueDB,
+	marked: &HashSet<Vec<u8>>,
+	progress: &mut impl FnMut(PruningProgress),
+) -> ClientResult<()> {
+	let mut transaction = DBTransaction::new();
+	let mut checked = 0u64;
+	let mut removed = 0u64;
+	for (key, _) in db.iter(columns::STATE) {
+		checked += 1;
+		if !marked.contains(&*key) {
+			transaction.delete(columns::STATE, &key);
+			removed += 1;
+		}
+		if checked % SWEEP_BATCH == 0 {
+			db.write(std::mem::replace(&mut transaction, DBTransaction::new())).map_err(db_err)?;
+			progress(PruningProgress::Sweeping { checked, removed });
+		}
+	}
+	db.write(transaction).map_err(db_err)?;
+	progress(PruningProgress::Sweeping { checked, removed });
+	Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use client::backend::{Backend as BackendT, BlockImportOperation as OperationT, NewBlockState};
+	use client::blockchain::HeaderBackend;
+	use sr_primitives::testing::{Header, Block as RawBlock, ExtrinsicWrapper};
+	use crate::{Backend, DatabaseKind};
+
+	type Block = RawBlock<ExtrinsicWrapper<u64>>;
+
+	fn settings(path: &std::path::Path, pruning: PruningMode) -> DatabaseSettings {
+		DatabaseSettings {
+			cache_size: None,
+			state_cache_size: 16777216,
+			state_cache_child_ratio: None,
+			path: path.into(),
+			pruning,
+			kind: DatabaseKind::LogDb,
+		}
+	}
+
+	fn insert_block(
+		backend: &Backend<Block>,
+		number: u64,
+		parent_hash: H256,
+		state: NewBlockState,
+	) -> H256 {
+		let block_id = if number == 0 {
+			BlockId::Hash(Default::default())
+		} else {
+			BlockId::Hash(parent_hash)
+		};
+		let mut op = backend.begin_operation().unwrap();
+		backend.begin_state_operation(&mut op, block_id).unwrap();
+
+		let children = vec![
+			(b":child_storage:default:test".to_vec(), vec![(b"child".to_vec(), vec![number as u8])].into_iter().collect()),
+		].into_iter().collect();
+		let storage = vec![(b"key".to_vec(), vec![number as u8])].into_iter().collect();
+		let state_root = op.reset_storage(storage, children).unwrap();
+
+		let header = Header {
+			number,
+			parent_hash,
+			state_root,
+			digest: Default::default(),
+			extrinsics_root: Default::default(),
+		};
+		let hash = header.hash();
+		op.set_block_data(header, Some(vec![]), None, state).unwrap();
+		backend.commit_operation(op).unwrap();
+		hash
+	}
+
+	fn state_entries(backend: &Backend<Block>) -> usize {
+		backend.storage.db.iter(columns::STATE).count()
+	}
+
+	#[test]
+	fn archive_becomes_constrained() {
+		let dir = tempfile::tempdir().unwrap();
+		let entries = {
+			let backend = Backend::<Block>::new(settings(dir.path(), PruningMode::ArchiveAll), 0).unwrap();
+			let mut hash = insert_block(&backend, 0, Default::default(), NewBlockState::Final);
+			for number in 1..4 {
+				hash = insert_block(&backend, number, hash, NewBlockState::Final);
+			}
+			insert_block(&backend, 4, hash, NewBlockState::Best);
+			state_entries(&backend)
+		};
+
+		assert!(Backend::<Block>::new(settings(dir.path(), PruningMode::keep_blocks(2)), 0).is_err());
+
+		let mut reports = Vec::new();
+		change_pruning_mode::<Block>(
+			&settings(dir.path(), PruningMode::ArchiveAll),
+			PruningMode::keep_blocks(2),
+			|report| reports.push(report),
+		).unwrap();
+		assert!(reports.iter().any(|report| match report {
+			PruningProgress::Sweeping { removed, .. } => *removed > 0,
+			_ => false,
+		}));
+
+		let backend = Backend::<Block>::new(settings(dir.path(), PruningMode::keep_blocks(2)), 0).unwrap();
+		assert!(state_entries(&backend) < entries);
+		assert!(backend.state_at(BlockId::Number(1)).is_err());
+		let state = backend.state_at(BlockId::Number(3)).unwrap();
+		assert_eq!(state.storage(b"key").unwrap(), Some(vec![3]));
+		assert_eq!(state.child_storage(b":child_storage:default:test", b"child").unwrap(), Some(vec![3]));
+		let state = backend.state_at(BlockId::Number(4)).unwrap();
+		assert_eq!(state.storage(b"key").unwrap(), Some(vec![4]));
+
+		// the unfinalized block is finalized and built on as usual.
+		let block4 = backend.blockchain().hash(4).unwrap().unwrap();
+		backend.finalize_block(BlockId::Hash(block4), None).unwrap();
+		insert_block(&backend, 5, block4, NewBlockState::Final);
+		assert_eq!(backend.state_at(BlockId::Number(5)).unwrap().storage(b"key").unwrap(), Some(vec![5]));
+	}
+
+	#[test]
+	fn constrained_becomes_archive_canonical() {
+		let dir = tempfile::tempdir().unwrap();
+		{
+			let backend = Backend::<Block>::new(settings(dir.path(), PruningMode::keep_blocks(1)), 0).unwrap();
+			let mut hash = insert_block(&backend, 0, Default::default(), NewBlockState::Final);
+			for number in 1..3 {
+				hash = insert_block(&backend, number, hash, NewBlockState::Final);
+			}
+		}
+
+		change_pruning_mode::<Block>(
+			&settings(dir.path(), PruningMode::keep_blocks(1)),
+			PruningMode::ArchiveCanonical,
+			|_| {},
+		).unwrap();
+
+		let backend = Backend::<Block>::new(settings(dir.path(), PruningMode::ArchiveCanonical), 0).unwrap();
+		let mut hash = backend.blockchain().hash(2).unwrap().unwrap();
+		for number in 3..6 {
+			hash = insert_block(&backend, number, hash, NewBlockState::Final);
+		}
+		// the state that was in the pruning window and all later ones are kept.
+		for number in 2..6 {
+			let state = backend.state_at(BlockId::Number(number)).unwrap();
+			assert_eq!(state.storage(b"key").unwrap(), Some(vec![number as u8]));
+		}
+		// the state pruned before stays pruned.
+		for number in 0..2 {
+			assert!(backend.state_at(BlockId::Number(number)).is_err());
+			let hash = backend.blockchain().hash(number).unwrap().unwrap();
+			assert!(!backend.have_state_at(&hash, number));
+		}
+	}
+
+	#[test]
+	fn archive_without_recorded_mode_is_recognized() {
+		let dir = tempfile::tempdir().unwrap();
+		{
+			let backend = Backend::<Block>::new(settings(dir.path(), PruningMode::ArchiveAll), 0).unwrap();
+			let mut hash = insert_block(&backend, 0, Default::default(), NewBlockState::Final);
+			for number in 1..3 {
+				hash = insert_block(&backend, number, hash, NewBlockState::Final);
+			}
+			// databases created before the pruning mode was recorded.
+			let mut transaction = DBTransaction::new();
+			transaction.delete(columns::STATE_META, b"mode");
+			backend.storage.db.write(transaction).unwrap();
+		}
+
+		assert!(Backend::<Block>::new(settings(dir.path(), PruningMode::keep_blocks(2)), 0).is_err());
+		assert!(Backend::<Block>::new(settings(dir.path(), PruningMode::ArchiveCanonical), 0).is_err());
+
+		change_pruning_mode::<Block>(
+			&settings(dir.path(), PruningMode::ArchiveAll),
+			PruningMode::ArchiveCanonical,
+			|_| {},
+		).unwrap();
+		let backend = Backend::<Block>::new(settings(dir.path(), PruningMode::ArchiveCanonical), 0).unwrap();
+		assert!(backend.state_at(BlockId::Number(0)).is_ok());
+	}
+}
@@ -0,0 +1,369 @@
+// Copyright 2019 Parity Technologies (UK) Ltd.
+// This file is part of Substrate.
+
+// Substrate is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Substrate is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Substrate.  If not, see <http://www.gnu.org/licenses/>.
+
+//! State snapshots.
+//!
+//! A snapshot holds the full state of a single finalized block, its header and justification,
+//! the genesis header of the chain and the auxiliary data kept by consensus engines. Only
+//! blocks with a stored justification can be exported, so that the importing node can check
+//! the snapshot block against the authorities found in the auxiliary data. An empty
+//! database can be bootstrapped from a snapshot; the node then syncs forward from the snapshot
+//! block without ever importing the blocks before it.
+
+use std::io;
+
+use client::backend::{Backend as BackendT, BlockImportOperation as OperationT};
+use client::blockchain::{Backend as BlockchainBackendT, HeaderBackend};
+use client::error::{Result as ClientResult, Error as ClientError};
+use codec::{Decode, Encode};
+use kvdb::DBTransaction;
+use primitives::H256;
+use primitives::storage::well_known_keys;
+use sr_primitives::Justification;
+use sr_primitives::generic::BlockId;
+use sr_primitives::traits::{Block as BlockT, Header as HeaderT, NumberFor, One, Zero, SaturatedConversion};
+use state_machine::backend::Backend as StateBackend;
+use log::info;
+
+use crate::utils::{self, db_err, meta_keys};
+use crate::{Backend, apply_state_commit, columns};
+
+/// The state of a finalized block along with everything needed to start a node at it.
+#[derive(Debug, Clone, PartialEq, Encode, Decode)]
+pub struct StateSnapshot<Block: BlockT> {
+	/// Header of the genesis block of the chain the snapshot was taken from.
+	pub genesis: Block::Header,
+	/// Header of the snapshot block.
+	pub header: Block::Header,
+	/// Justification of the snapshot block.
+	pub justification: Justification,
+	/// Top-level storage at the snapshot block, without child trie roots.
+	pub top: Vec<(Vec<u8>, Vec<u8>)>,
+	/// Child tries at the snapshot block, keyed by their storage key.
+	pub children: Vec<(Vec<u8>, Vec<(Vec<u8>, Vec<u8>)>)>,
+	/// Auxiliary data of the exporting node.
+	pub aux: Vec<(Vec<u8>, Vec<u8>)>,
+}
+
+impl<Block: BlockT<Hash=H256>> Backend<Block> {
+	/// The latest finalized block which has a justification stored, if any.
+	///
+	/// Only such blocks can be exported, and consensus engines store justifications for few
+	/// blocks, so this is usually behind the last finalized block.
+	pub fn latest_justified_block(&self) -> ClientResult<Option<NumberFor<Block>>> {
+		let mut number = self.blockchain.info().finalized_number;
+		while !number.is_zero() {
+			if self.blockchain.justification(BlockId::Number(number))?.is_some() {
+				return Ok(Some(number));
+			}
+			number -= One::one();
+		}
+
+		Ok(None)
+	}
+
+	/// Take a snapshot of the state at the given block.
+	///
+	/// The block must be finalized, so that the importing node never has to revert past it,
+	/// and have a justification.
+	pub fn export_state(&self, at: BlockId<Block>) -> ClientResult<StateSnapshot<Block>> {
+		let header = self.blockchain.expect_header(at)?;
+		let hash = header.hash();
+		let number = *header.number();
+
+		let info = self.blockchain.info();
+		if number > info.finalized_number || self.blockchain.hash(number)? != Some(hash) {
+			return Err(ClientError::Backend(
+				format!("Cannot export state of block #{} ({}): it is not finalized", number, hash)
+			));
+		}
+
+		let genesis = self.blockchain.expect_header(BlockId::Number(Zero::zero()))?;
+		let justification = self.blockchain.justification(BlockId::Hash(hash))?
+			.ok_or_else(|| ClientError::Backend(
+				format!("Cannot export state of block #{} ({}): it has no justification", number, hash)
+			))?;
+
+		let state = self.state_at(BlockId::Hash(hash))?;
+		let mut top = Vec::new();
+		let mut children = Vec::new();
+		for (key, value) in state.pairs() {
+			if !well_known_keys::is_child_storage_key(&key) {
+				top.push((key, value));
+				continue;
+			}
+
+			let mut child = Vec::new();
+			for child_key in state.child_keys(&key, &[]) {
+				let child_value = state.child_storage(&key, &child_key)
+					.map_err(|e| ClientError::from_state(Box::new(e)))?
+					.ok_or_else(|| ClientError::Backend(
+						format!("Missing child storage value in trie of block {}", hash)
+					))?;
+				child.push((child_key, child_value));
+			}
+			children.push((key, child));
+		}
+
+		let aux = self.storage.db.iter(columns::AUX)
+			.map(|(key, value)| (key.into_vec(), value.into_vec()))
+			.collect();
+
+		Ok(StateSnapshot { genesis, header, justification, top, children, aux })
+	}
+
+	/// Bootstrap an empty database from a snapshot.
+	///
+	/// The snapshot block becomes both the best and the finalized block. The genesis header is
+	/// stored without its state, and no other block is known until it is synced.
+	pub fn import_state(&self, snapshot: StateSnapshot<Block>) -> ClientResult<()> {
+		if self.blockchain.header(BlockId::Number(Zero::zero()))?.is_some() {
+			return Err(ClientError::Backend(
+				"Cannot import a state snapshot into a database that already has a chain".into()
+			));
+		}
+
+		let StateSnapshot { genesis, header, justification, top, children, aux } = snapshot;
+		if !genesis.number().is_zero() || header.number().is_zero() {
+			return Err(ClientError::Backend("State snapshot headers are out of order".into()));
+		}
+
+		let hash = header.hash();
+		let number = *header.number();
+		let genesis_hash = genesis.hash();
+
+		let mut operation = self.begin_operation()?;
+		let root = operation.reset_storage(
+			top.into_iter().collect(),
+			children.into_iter()
+				.map(|(storage_key, pairs)| (storage_key, pairs.into_iter().collect()))
+				.collect(),
+		)?;
+		if root != *header.state_root() {
+			return Err(ClientError::InvalidStateRoot);
+		}
+
+		let mut transaction = DBTransaction::new();
+		write_header::<Block>(&mut transaction, &genesis)?;
+		transaction.put(columns::META, meta_keys::GENESIS_HASH, genesis_hash.as_ref());
+
+		let lookup_key = write_header::<Block>(&mut transaction, &header)?;
+		transaction.put(columns::JUSTIFICATION, &lookup_key, &justification.encode());
+		transaction.put(columns::META, meta_keys::BEST_BLOCK, &lookup_key);
+		transaction.put(columns::META, meta_keys::FINALIZED_BLOCK, &lookup_key);
+
+		for (key, value) in aux {
+			transaction.put_vec(columns::AUX, &key, value);
+		}
+
+		let mut changeset: state_db::ChangeSet<Vec<u8>> = state_db::ChangeSet::default();
+		for (key, (value, rc)) in operation.db_updates.drain() {
+			if rc > 0 {
+				changeset.inserted.push((key, value.to_vec()));
+			}
+		}
+
+		let map_e = |e: state_db::Error<io::Error>| ClientError::from(format!("State database error: {:?}", e));
+		let number_u64 = number.saturated_into::<u64>();
+		let commit = self.storage.state_db.insert_block(&hash, number_u64, header.parent_hash(), changeset)
+			.map_err(map_e)?;
+		apply_state_commit(&mut transaction, commit);
+		match self.storage.state_db.canonicalize_block(&hash) {
+			Ok(commit) => apply_state_commit(&mut transaction, commit),
+			Err(e) => {
+				self.storage.state_db.revert_pending();
+				return Err(map_e(e));
+			},
+		}
+
+		{
+			let mut leaves = self.blockchain.leaves.write();
+			leaves.import(hash, number, *header.parent_hash());
+			leaves.prepare_transaction(&mut transaction, columns::META, meta_keys::LEAF_PREFIX);
+		}
+
+		if let Err(e) = self.storage.db.write(transaction).map_err(db_err) {
+			self.storage.state_db.revert_pending();
+			return Err(e);
+		}
+		self.storage.state_db.apply_pending();
+
+		self.blockchain.update_meta(genesis_hash, Zero::zero(), false, false);
+		self.blockchain.update_meta(hash, number, true, true);
+
+		info!("Imported state of block #{} ({})", number, hash);
+		Ok(())
+	}
+}
+
+/// Write a header and its lookup mappings, returning its lookup key.
+fn write_header<Block: BlockT>(
+	transaction: &mut DBTransaction,
+	header: &Block::Header,
+) -> ClientResult<Vec<u8>> {
+	let number = *header.number();
+	let hash = header.hash();
+	let lookup_key = utils::number_and_hash_to_lookup_key(number, hash)?;
+
+	utils::insert_number_to_key_mapping(transaction, columns::KEY_LOOKUP, number, hash)?;
+	utils::insert_hash_to_key_mapping(transaction, columns::KEY_LOOKUP, number, hash)?;
+	transaction.put(columns::HEADER, &lookup_key, &header.encode());
+
+	Ok(lookup_key)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use client::backend::{AuxStore, NewBlockState};
+	use sr_primitives::testing::{Header, Block as RawBlock, ExtrinsicWrapper};
+	use sr_primitives::traits::{Hash, BlakeTwo256};
+
+	type Block = RawBlock<ExtrinsicWrapper<u64>>;
+
+	fn child_key() -> Vec<u8> {
+		b":child_storage:default:snapshot".to_vec()
+	}
+
+	fn insert_block(
+		backend: &Backend<Block>,
+		number: u64,
+		parent_hash: H256,
+		storage: Vec<(Vec<u8>, Vec<u8>)>,
+	) -> H256 {
+		let block_id = if number == 0 {
+			BlockId::Hash(Default::default())
+		} else {
+			BlockId::Number(number - 1)
+		};
+		let mut op = backend.begin_operation().unwrap();
+		backend.begin_state_operation(&mut op, block_id).unwrap();
+
+		let children = vec![
+			(child_key(), vec![(b"child".to_vec(), vec![number as u8])].into_iter().collect()),
+		].into_iter().collect();
+		let state_root = op.reset_storage(storage.into_iter().collect(), children).unwrap();
+
+		let header = Header {
+			number,
+			parent_hash,
+			state_root,
+			digest: Default::default(),
+			extrinsics_root: Default::default(),
+		};
+		let hash = header.hash();
+		op.set_block_data(header, Some(vec![]), Some(vec![number as u8]), NewBlockState::Final).unwrap();
+		backend.commit_operation(op).unwrap();
+		hash
+	}
+
+	fn exporting_backend() -> (Backend<Block>, H256) {
+		let backend = Backend::<Block>::new_test(2, 0);
+		let genesis = insert_block(&backend, 0, Default::default(), vec![(b"key".to_vec(), vec![0])]);
+		let block1 = insert_block(&backend, 1, genesis, vec![(b"key".to_vec(), vec![1])]);
+		backend.insert_aux(&[(&b"aux"[..], &b"value"[..])], &[]).unwrap();
+		(backend, block1)
+	}
+
+	#[test]
+	fn snapshot_roundtrip() {
+		let (exporting, block1) = exporting_backend();
+		let snapshot = exporting.export_state(BlockId::Number(1)).unwrap();
+		assert_eq!(snapshot.header.hash(), block1);
+		assert_eq!(snapshot.justification, vec![1]);
+		assert_eq!(snapshot.top, vec![(b"key".to_vec(), vec![1])]);
+		assert_eq!(snapshot.children, vec![(child_key(), vec![(b"child".to_vec(), vec![1])])]);
+
+		let encoded = snapshot.encode();
+		let snapshot = StateSnapshot::<Block>::decode(&mut &encoded[..]).unwrap();
+
+		let importing = Backend::<Block>::new_test(2, 0);
+		importing.import_state(snapshot).unwrap();
+
+		let info = importing.blockchain().info();
+		assert_eq!(info.genesis_hash, exporting.blockchain().info().genesis_hash);
+		assert_eq!(info.best_hash, block1);
+		assert_eq!(info.finalized_hash, block1);
+		assert_eq!(importing.blockchain().justification(BlockId::Number(1)).unwrap(), Some(vec![1]));
+		assert_eq!(importing.get_aux(b"aux").unwrap(), Some(b"value".to_vec()));
+
+		let state = importing.state_at(BlockId::Number(1)).unwrap();
+		assert_eq!(state.storage(b"key").unwrap(), Some(vec![1]));
+		assert_eq!(state.child_storage(&child_key(), b"child").unwrap(), Some(vec![1]));
+
+		// the imported node builds on the snapshot block.
+		let block2 = insert_block(&importing, 2, block1, vec![(b"key".to_vec(), vec![2])]);
+		assert_eq!(importing.blockchain().info().finalized_hash, block2);
+	}
+
+	#[test]
+	fn exporting_unfinalized_block_fails() {
+		let (exporting, block1) = exporting_backend();
+		let mut op = exporting.begin_operation().unwrap();
+		exporting.begin_state_operation(&mut op, BlockId::Hash(block1)).unwrap();
+		let header = Header {
+			number: 2,
+			parent_hash: block1,
+			state_root: BlakeTwo256::trie_root(Vec::new()),
+			digest: Default::default(),
+			extrinsics_root: Default::default(),
+		};
+		op.set_block_data(header, None, None, NewBlockState::Best).unwrap();
+		exporting.commit_operation(op).unwrap();
+
+		assert!(exporting.export_state(BlockId::Number(2)).is_err());
+	}
+
+	#[test]
+	fn only_justified_blocks_are_exported() {
+		let (exporting, block1) = exporting_backend();
+		let mut op = exporting.begin_operation().unwrap();
+		exporting.begin_state_operation(&mut op, BlockId::Hash(block1)).unwrap();
+		let header = Header {
+			number: 2,
+			parent_hash: block1,
+			state_root: BlakeTwo256::trie_root(Vec::new()),
+			digest: Default::default(),
+			extrinsics_root: Default::default(),
+		};
+		op.set_block_data(header, None, None, NewBlockState::Final).unwrap();
+		exporting.commit_operation(op).unwrap();
+
+		assert_eq!(exporting.blockchain().info().finalized_number, 2);
+		assert_eq!(exporting.latest_justified_block().unwrap(), Some(1));
+		assert!(exporting.export_state(BlockId::Number(2)).is_err());
+	}
+
+	#[test]
+	fn import_checks_state_root() {
+		let (exporting, _) = exporting_backend();
+		let mut snapshot = exporting.export_state(BlockId::Number(1)).unwrap();
+		snapshot.top[0].1 = vec![42];
+
+		let importing = Backend::<Block>::new_test(2, 0);
+		match importing.import_state(snapshot) {
+			Err(ClientError::InvalidStateRoot) => {},
+			other => panic!("unexpected result: {:?}", other.map(|_| ())),
+		}
+	}
+
+	#[test]
+	fn import_into_existing_chain_fails() {
+		let (exporting, _) = exporting_backend();
+		let snapshot = exporting.export_state(BlockId::Number(1)).unwrap();
+		assert!(exporting.import_state(snapshot).is_err());
+	}
+}
@@ -17,13 +17,12 @@
 //! Db-based backend utility structures and functions, used by both
 //! full and light storages.
 
-#[cfg(feature = "kvdb-rocksdb")]
 use std::sync::Arc;
 use std::{io, convert::TryInto};
 
 use kvdb::{KeyValueDB, DBTransaction};
 #[cfg(feature = "kvdb-rocksdb")]
-use kvdb_rocksdb::{Database, DatabaseConfig};
+use kvdb_rocksdb::DatabaseConfig;
 use log::debug;
 
 use client;
@@ -34,8 +33,9 @@ use sr_primitives::traits::{
 	Block as BlockT, Header as HeaderT, Zero,
 	UniqueSaturatedFrom, UniqueSaturatedInto,
 };
-#[cfg(feature = "kvdb-rocksdb")]
-use crate::DatabaseSettings;
+use crate::{DatabaseSettings, DatabaseKind};
+use crate::database::{Database, KvdbDatabase, AsKeyValueDB};
+use crate::log_db::{LogDb, LogDbConfig, LOG_FILE};
 
 /// Number of columns in the db. Must be the same for both full && light dbs.
 /// Otherwise RocksDb will fail to open database && check its type.
@@ -205,17 +205,32 @@ pub fn db_err(err: io::Error) -> client::error::Error {
 	client::error::Error::Backend(format!("{}", err))
 }
 
-/// Open RocksDB database.
-#[cfg(feature = "kvdb-rocksdb")]
+/// Open the key-value store selected by the database settings.
+///
+/// `trie_column` is the column holding trie nodes keyed by the hash of their content, if any.
+pub fn open_key_value_database(
+	config: &DatabaseSettings,
+	trie_column: Option<u32>,
+) -> client::error::Result<Arc<dyn Database>> {
+	check_database_kind(config)?;
+	match config.kind {
+		DatabaseKind::RocksDb => Ok(Arc::new(KvdbDatabase(open_rocksdb(config)?))),
+		DatabaseKind::LogDb => Ok(Arc::new(open_log_db(config, trie_column)?)),
+	}
+}
+
+/// Open the database and check that it holds a storage of the given type.
 pub fn open_database(
 	config: &DatabaseSettings,
 	col_meta: Option<u32>,
-	db_type: &str
+	db_type: &str,
+	trie_column: Option<u32>,
 ) -> client::error::Result<Arc<dyn KeyValueDB>> {
-	let mut db_config = DatabaseConfig::with_columns(Some(NUM_COLUMNS));
-	db_config.memory_budget = config.cache_size;
-	let path = config.path.to_str().ok_or_else(|| client::error::Error::Backend("Invalid database path".into()))?;
-	let db = Database::open(&db_config, &path).map_err(db_err)?;
+	check_database_kind(config)?;
+	let db: Arc<dyn KeyValueDB> = match config.kind {
+		DatabaseKind::RocksDb => open_rocksdb(config)?,
+		DatabaseKind::LogDb => Arc::new(AsKeyValueDB(Arc::new(open_log_db(config, trie_column)?))),
+	};
 
 	// check database type
 	match db.get(col_meta, meta_keys::TYPE).map_err(db_err)? {
@@ -232,9 +247,58 @@ pub fn open_database(
 		},
 	}
 
+	Ok(db)
+}
+
+/// Open RocksDB database.
+#[cfg(feature = "kvdb-rocksdb")]
+fn open_rocksdb(config: &DatabaseSettings) -> client::error::Result<Arc<dyn KeyValueDB>> {
+	let mut db_config = DatabaseConfig::with_columns(Some(NUM_COLUMNS));
+	db_config.memory_budget = config.cache_size;
+	let path = config.path.to_str().ok_or_else(|| client::error::Error::Backend("Invalid database path".into()))?;
+	let db = kvdb_rocksdb::Database::open(&db_config, &path).map_err(db_err)?;
 	Ok(Arc::new(db))
 }
 
+#[cfg(not(feature = "kvdb-rocksdb"))]
+fn open_rocksdb(_config: &DatabaseSettings) -> client::error::Result<Arc<dyn KeyValueDB>> {
+	log::warn!("Running without the RocksDB feature. The database will NOT be saved.");
+	Ok(Arc::new(kvdb_memorydb::create(NUM_COLUMNS)))
+}
+
+fn open_log_db(config: &DatabaseSettings, trie_column: Option<u32>) -> client::error::Result<LogDb> {
+	let log_config = LogDbConfig {
+		columns: NUM_COLUMNS,
+		content_addressed: trie_column.into_iter().collect(),
+	};
+	LogDb::open(&config.path, log_config).map_err(db_err)
+}
+
+/// Refuse to open a database directory that was created by another kind of database.
+fn check_database_kind(config: &DatabaseSettings) -> client::error::Result<()> {
+	let found = if config.path.join(LOG_FILE).exists() {
+		Some(DatabaseKind::LogDb)
+	} else if config.path.join("CURRENT").exists() {
+		Some(DatabaseKind::RocksDb)
+	} else {
+		None
+	};
+
+	match found {
+		Some(found) if found != config.kind => Err(client::error::Error::Backend(format!(
+			"Database at {} is a {} database, but {} was requested",
+			config.path.display(),
+			found,
+			config.kind,
+		))),
+		None if crate::migration_path(&config.path).exists() => Err(client::error::Error::Backend(format!(
+			"A database migration to {} was interrupted; run `migrate-db` again to complete it",
+			config.path.display(),
+		))),
+		_ => Ok(()),
+	}
+}
+
 /// Read database column entry for the given block.
 pub fn read_db<Block>(
 	db: &dyn KeyValueDB,
@@ -2769,6 +2769,7 @@ pub(crate) mod tests {
 				state_cache_child_ratio: None,
 				path: tmp.path().into(),
 				pruning: PruningMode::ArchiveAll,
+				kind: Default::default(),
 			},
 			u64::max_value(),
 		).unwrap());
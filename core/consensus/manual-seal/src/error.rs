@@ -0,0 +1,107 @@
+// Copyright 2017-2019 Parity Technologies (UK) Ltd.
+// This file is part of Substrate.
+
+// Substrate is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Substrate is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Substrate.  If not, see <http://www.gnu.org/licenses/>.
+//! Errors of the manual seal engine.
+
+use consensus_common::ImportResult;
+use futures::channel::{mpsc::SendError, oneshot};
+
+/// Base code for all manual seal errors.
+const BASE_ERROR: i64 = 15000;
+/// The transaction pool has no ready transactions.
+const EMPTY_TRANSACTION_POOL: i64 = BASE_ERROR + 1;
+/// The block could not be imported.
+const BLOCK_IMPORT_FAILED: i64 = BASE_ERROR + 2;
+/// The block to build on or to finalize is unknown.
+const BLOCK_NOT_FOUND: i64 = BASE_ERROR + 3;
+/// The block could not be finalized.
+const BLOCK_FINALIZATION_FAILED: i64 = BASE_ERROR + 4;
+/// Inherent data could not be created.
+const INHERENTS_ERROR: i64 = BASE_ERROR + 5;
+/// The consensus engine failed.
+const CONSENSUS_ERROR: i64 = BASE_ERROR + 6;
+/// The authorship task is gone.
+const ENGINE_UNAVAILABLE: i64 = BASE_ERROR + 7;
+/// Any other error.
+const OTHER_ERROR: i64 = BASE_ERROR + 8;
+
+/// Manual seal errors.
+#[derive(Debug, derive_more::Display, derive_more::From)]
+pub enum Error {
+	/// An attempt to create a block without transactions and without `create_empty`.
+	#[display(fmt="Transaction pool is empty, set create_empty to true to create an empty block")]
+	EmptyTransactionPool,
+	/// The block import did not import the block.
+	#[display(fmt="Failed to import block: {:?}", _0)]
+	BlockImportError(ImportResult),
+	/// The block to build on or to finalize is unknown.
+	#[display(fmt="Block {} not found", _0)]
+	#[from(ignore)]
+	BlockNotFound(String),
+	/// Client error.
+	#[display(fmt="Client error: {}", _0)]
+	Client(client::error::Error),
+	/// Inherent data could not be created.
+	#[display(fmt="Failed to create inherent data: {}", _0)]
+	Inherents(inherents::RuntimeString),
+	/// Consensus error.
+	#[display(fmt="Consensus error: {}", _0)]
+	Consensus(consensus_common::Error),
+	/// The command could not be sent to the authorship task.
+	#[display(fmt="Failed to send command to the authorship task: {}", _0)]
+	SendError(SendError),
+	/// The authorship task dropped the command without answering.
+	#[display(fmt="The authorship task dropped the command")]
+	Canceled(oneshot::Canceled),
+	/// Any other error.
+	#[display(fmt="{}", _0)]
+	#[from(ignore)]
+	Other(String),
+}
+
+impl std::error::Error for Error {
+	fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+		match self {
+			Error::Client(ref err) => Some(err),
+			Error::Consensus(ref err) => Some(err),
+			_ => None,
+		}
+	}
+}
+
+impl Error {
+	fn code(&self) -> i64 {
+		match self {
+			Error::EmptyTransactionPool => EMPTY_TRANSACTION_POOL,
+			Error::BlockImportError(_) => BLOCK_IMPORT_FAILED,
+			Error::BlockNotFound(_) => BLOCK_NOT_FOUND,
+			Error::Client(_) => BLOCK_FINALIZATION_FAILED,
+			Error::Inherents(_) => INHERENTS_ERROR,
+			Error::Consensus(_) => CONSENSUS_ERROR,
+			Error::SendError(_) | Error::Canceled(_) => ENGINE_UNAVAILABLE,
+			Error::Other(_) => OTHER_ERROR,
+		}
+	}
+}
+
+impl From<Error> for jsonrpc_core::Error {
+	fn from(error: Error) -> Self {
+		jsonrpc_core::Error {
+			code: jsonrpc_core::ErrorCode::ServerError(error.code()),
+			message: format!("{}", error),
+			data: None,
+		}
+	}
+}
@@ -0,0 +1,39 @@
+// Copyright 2017-2019 Parity Technologies (UK) Ltd.
+// This file is part of Substrate.
+
+// Substrate is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Substrate is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Substrate.  If not, see <http://www.gnu.org/licenses/>.
+//! Finalization of a block on a `FinalizeBlock` command.
+
+use client::backend::{Backend, Finalizer};
+use primitives::{Blake2Hasher, H256};
+use sr_primitives::{Justification, generic::BlockId, traits::Block as BlockT};
+use crate::{Error, rpc};
+
+/// Finalize the block with hash `hash` and all of its ancestors, and answer `sender`.
+pub fn finalize_block<B, CB, BE>(
+	client: &CB,
+	hash: B::Hash,
+	justification: Option<Justification>,
+	sender: rpc::Sender<bool>,
+) where
+	B: BlockT<Hash = H256>,
+	CB: Finalizer<B, Blake2Hasher, BE>,
+	BE: Backend<B, Blake2Hasher>,
+{
+	let result = client.finalize_block(BlockId::Hash(hash), justification, true)
+		.map(|()| true)
+		.map_err(Error::from);
+
+	rpc::send_result(sender, result)
+}
@@ -0,0 +1,261 @@
+// Copyright 2017-2019 Parity Technologies (UK) Ltd.
+// This file is part of Substrate.
+
+// Substrate is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Substrate is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Substrate.  If not, see <http://www.gnu.org/licenses/>.
+//! Manual and instant seal consensus engine, for development and testing.
+//!
+//! Instead of authoring blocks on slots or by mining, the engine authors blocks on command:
+//!
+//! - Instant seal (`run_instant_seal`) authors and finalizes a block as soon as the
+//!   transaction pool gets a new transaction.
+//! - Manual seal (`run_manual_seal`) authors and finalizes blocks on the `EngineCommand`s
+//!   it receives, usually from the `engine_createBlock` and `engine_finalizeBlock` RPC
+//!   methods of `rpc::ManualSeal`.
+//!
+//! Blocks carry no seal, so the import queue accepts any block. Never run this engine on a
+//! network of nodes which do not trust each other.
+
+use std::sync::Arc;
+use client::{backend::{Backend, Finalizer}, blockchain::HeaderBackend};
+use consensus_common::{
+	BlockImportParams, BlockOrigin, Environment, ForkChoiceStrategy, SelectChain,
+	import_queue::{BasicQueue, BoxBlockImport, CacheKeyId, Verifier},
+};
+use futures::prelude::*;
+use inherents::InherentDataProviders;
+use primitives::{Blake2Hasher, H256};
+use sr_primitives::{Justification, generic::BlockId, traits::Block as BlockT};
+
+mod error;
+mod finalize_block;
+mod seal_new_block;
+pub mod rpc;
+
+pub use self::{
+	error::Error,
+	finalize_block::finalize_block,
+	rpc::{CreatedBlock, EngineCommand},
+	seal_new_block::seal_new_block,
+};
+
+/// Verifier of the manual seal engine, which accepts every block.
+struct ManualSealVerifier;
+
+impl<B: BlockT> Verifier<B> for ManualSealVerifier {
+	fn verify(
+		&mut self,
+		origin: BlockOrigin,
+		header: B::Header,
+		justification: Option<Justification>,
+		body: Option<Vec<B::Extrinsic>>,
+	) -> Result<(BlockImportParams<B>, Option<Vec<(CacheKeyId, Vec<u8>)>>), String> {
+		let import_params = BlockImportParams {
+			origin,
+			header,
+			justification,
+			post_digests: Vec::new(),
+			body,
+			finalized: false,
+			auxiliary: Vec::new(),
+			fork_choice: ForkChoiceStrategy::LongestChain,
+		};
+
+		Ok((import_params, None))
+	}
+}
+
+/// The import queue of the manual seal engine.
+pub fn import_queue<B: BlockT>(block_import: BoxBlockImport<B>) -> BasicQueue<B> {
+	BasicQueue::new(ManualSealVerifier, block_import, None, None)
+}
+
+/// Author and finalize blocks on the commands of `commands_stream`, until it ends.
+pub async fn run_manual_seal<B, CB, BE, E, A, SC, S>(
+	mut block_import: BoxBlockImport<B>,
+	mut env: E,
+	client: Arc<CB>,
+	pool: Arc<txpool::Pool<A>>,
+	mut commands_stream: S,
+	select_chain: SC,
+	inherent_data_providers: InherentDataProviders,
+) where
+	B: BlockT<Hash = H256>,
+	CB: HeaderBackend<B> + Finalizer<B, Blake2Hasher, BE>,
+	BE: Backend<B, Blake2Hasher>,
+	E: Environment<B>,
+	E::Error: std::fmt::Debug,
+	A: txpool::ChainApi<Block = B>,
+	SC: SelectChain<B>,
+	S: Stream<Item = EngineCommand<H256>> + Unpin,
+{
+	while let Some(command) = commands_stream.next().await {
+		match command {
+			EngineCommand::SealNewBlock { create_empty, finalize, parent_hash, sender } => {
+				seal_new_block(
+					create_empty,
+					finalize,
+					parent_hash,
+					sender,
+					&*client,
+					&mut env,
+					&mut block_import,
+					&*pool,
+					&select_chain,
+					&inherent_data_providers,
+				).await
+			}
+			EngineCommand::FinalizeBlock { hash, justification, sender } => {
+				finalize_block(&*client, hash, justification, sender)
+			}
+		}
+	}
+}
+
+/// Author and finalize a block whenever the transaction pool gets a new transaction.
+///
+/// Notifications which leave no ready transaction in the pool author nothing.
+pub async fn run_instant_seal<B, CB, BE, E, A, SC>(
+	block_import: BoxBlockImport<B>,
+	env: E,
+	client: Arc<CB>,
+	pool: Arc<txpool::Pool<A>>,
+	select_chain: SC,
+	inherent_data_providers: InherentDataProviders,
+) where
+	B: BlockT<Hash = H256>,
+	CB: HeaderBackend<B> + Finalizer<B, Blake2Hasher, BE>,
+	BE: Backend<B, Blake2Hasher>,
+	E: Environment<B>,
+	E::Error: std::fmt::Debug,
+	A: txpool::ChainApi<Block = B>,
+	SC: SelectChain<B>,
+{
+	let commands_stream = pool.import_notification_stream()
+		.map(|_| EngineCommand::SealNewBlock {
+			create_empty: false,
+			finalize: true,
+			parent_hash: None,
+			sender: None,
+		});
+
+	run_manual_seal(
+		block_import,
+		env,
+		client,
+		pool,
+		commands_stream,
+		select_chain,
+		inherent_data_providers,
+	).await
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	use basic_authorship::ProposerFactory;
+	use client::BlockchainEvents;
+	use sr_primitives::traits::Header as HeaderT;
+	use futures::{channel::{mpsc, oneshot}, executor::LocalPool, task::LocalSpawnExt};
+	use test_client::{
+		AccountKeyring, DefaultTestClientBuilderExt, TestClientBuilder, TestClientBuilderExt,
+		runtime::{Extrinsic, Transfer},
+	};
+	use transaction_pool::FullChainApi;
+
+	fn extrinsic(nonce: u64) -> Extrinsic {
+		Transfer {
+			amount: Default::default(),
+			nonce,
+			from: AccountKeyring::Alice.into(),
+			to: Default::default(),
+		}.into_signed_tx()
+	}
+
+	#[test]
+	fn instant_seal_authors_a_block_per_transaction() {
+		let (client, select_chain) = TestClientBuilder::new().build_with_longest_chain();
+		let client = Arc::new(client);
+		let pool = Arc::new(txpool::Pool::new(Default::default(), FullChainApi::new(client.clone())));
+		let env = ProposerFactory { client: client.clone(), transaction_pool: pool.clone() };
+		let mut imported = client.import_notification_stream();
+
+		let mut executor = LocalPool::new();
+		executor.spawner().spawn_local(run_instant_seal(
+			Box::new(client.clone()),
+			env,
+			client.clone(),
+			pool.clone(),
+			select_chain,
+			InherentDataProviders::new(),
+		)).unwrap();
+
+		executor.run_until(pool.submit_one(&BlockId::number(0), extrinsic(0))).unwrap();
+		let notification = executor.run_until(imported.next()).unwrap();
+
+		assert_eq!(*notification.header.number(), 1);
+		assert!(notification.is_new_best);
+		assert_eq!(client.info().chain.finalized_hash, notification.hash);
+	}
+
+	#[test]
+	fn manual_seal_authors_and_finalizes_on_command() {
+		let (client, select_chain) = TestClientBuilder::new().build_with_longest_chain();
+		let client = Arc::new(client);
+		let pool = Arc::new(txpool::Pool::new(Default::default(), FullChainApi::new(client.clone())));
+		let env = ProposerFactory { client: client.clone(), transaction_pool: pool.clone() };
+		let (mut commands, commands_stream) = mpsc::channel(16);
+
+		let mut executor = LocalPool::new();
+		executor.spawner().spawn_local(run_manual_seal(
+			Box::new(client.clone()),
+			env,
+			client.clone(),
+			pool,
+			commands_stream,
+			select_chain,
+			InherentDataProviders::new(),
+		)).unwrap();
+
+		let mut seal = |create_empty, parent_hash| {
+			let (sender, receiver) = oneshot::channel();
+			commands.try_send(EngineCommand::SealNewBlock {
+				create_empty,
+				finalize: false,
+				parent_hash,
+				sender: Some(sender),
+			}).unwrap();
+			executor.run_until(receiver).unwrap()
+		};
+
+		match seal(false, None) {
+			Err(Error::EmptyTransactionPool) => {},
+			other => panic!("Expected an empty transaction pool, got {:?}", other),
+		}
+		let first = seal(true, None).unwrap().hash;
+		let second = seal(true, None).unwrap().hash;
+		let fork = seal(true, Some(first)).unwrap().hash;
+
+		assert_eq!(client.header(&BlockId::Hash(second)).unwrap().unwrap().parent_hash(), &first);
+		assert_eq!(client.header(&BlockId::Hash(fork)).unwrap().unwrap().parent_hash(), &first);
+		assert_eq!(client.info().chain.best_hash, second);
+		assert_eq!(client.info().chain.finalized_number, 0);
+
+		let (sender, receiver) = oneshot::channel();
+		commands.try_send(EngineCommand::FinalizeBlock { hash: fork, justification: None, sender: Some(sender) })
+			.unwrap();
+		assert!(executor.run_until(receiver).unwrap().unwrap());
+		assert_eq!(client.info().chain.finalized_hash, fork);
+	}
+}
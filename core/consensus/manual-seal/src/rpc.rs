@@ -0,0 +1,155 @@
+// Copyright 2017-2019 Parity Technologies (UK) Ltd.
+// This file is part of Substrate.
+
+// Substrate is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Substrate is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Substrate.  If not, see <http://www.gnu.org/licenses/>.
+//! RPC interface of the manual seal engine.
+
+use futures::{
+	channel::{mpsc, oneshot},
+	FutureExt, SinkExt, TryFutureExt,
+};
+use jsonrpc_derive::rpc;
+use serde::{Deserialize, Serialize};
+use sr_primitives::Justification;
+
+pub use self::gen_client::Client as ManualSealClient;
+
+/// Future's type for jsonrpc.
+type FutureResult<T> = Box<dyn jsonrpc_core::futures::Future<Item = T, Error = jsonrpc_core::Error> + Send>;
+
+/// Sender the authorship task answers a command with, if the command expects an answer.
+pub type Sender<T> = Option<oneshot::Sender<Result<T, crate::Error>>>;
+
+/// Command of the authorship task, usually sent by RPC.
+pub enum EngineCommand<Hash> {
+	/// Author a new block on top of `parent_hash`, or of the best block.
+	SealNewBlock {
+		/// Author the block even if the transaction pool has no ready transactions.
+		create_empty: bool,
+		/// Finalize the block once it is imported.
+		finalize: bool,
+		/// Hash of the block to build on.
+		parent_hash: Option<Hash>,
+		/// Sender for the hash of the new block.
+		sender: Sender<CreatedBlock<Hash>>,
+	},
+	/// Finalize the block with the given hash and all of its ancestors.
+	FinalizeBlock {
+		/// Hash of the block to finalize.
+		hash: Hash,
+		/// Justification to store with the block.
+		justification: Option<Justification>,
+		/// Sender for the outcome.
+		sender: Sender<bool>,
+	},
+}
+
+/// A block authored by the engine.
+#[derive(Debug, PartialEq, Eq, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CreatedBlock<Hash> {
+	/// Hash of the block.
+	pub hash: Hash,
+}
+
+/// Manual seal RPC methods.
+#[rpc]
+pub trait ManualSealApi<Hash> {
+	/// Author a block, built on `parent_hash` or on the best block, and finalize it
+	/// if `finalize` is set. Fails on an empty transaction pool unless `create_empty` is set.
+	#[rpc(name = "engine_createBlock")]
+	fn create_block(
+		&self,
+		create_empty: bool,
+		finalize: bool,
+		parent_hash: Option<Hash>,
+	) -> FutureResult<CreatedBlock<Hash>>;
+
+	/// Finalize the block with the given hash, and store `justification` with it.
+	#[rpc(name = "engine_finalizeBlock")]
+	fn finalize_block(
+		&self,
+		hash: Hash,
+		justification: Option<Justification>,
+	) -> FutureResult<bool>;
+}
+
+/// Implementation of `ManualSealApi`, which forwards the calls to the authorship task.
+pub struct ManualSeal<Hash> {
+	commands: mpsc::Sender<EngineCommand<Hash>>,
+}
+
+impl<Hash> ManualSeal<Hash> {
+	/// Create a new `ManualSeal` sending its commands to `commands`.
+	pub fn new(commands: mpsc::Sender<EngineCommand<Hash>>) -> Self {
+		ManualSeal { commands }
+	}
+}
+
+impl<Hash: Send + 'static> ManualSealApi<Hash> for ManualSeal<Hash> {
+	fn create_block(
+		&self,
+		create_empty: bool,
+		finalize: bool,
+		parent_hash: Option<Hash>,
+	) -> FutureResult<CreatedBlock<Hash>> {
+		let mut commands = self.commands.clone();
+		let future = async move {
+			let (sender, receiver) = oneshot::channel();
+			commands.send(EngineCommand::SealNewBlock {
+				create_empty,
+				finalize,
+				parent_hash,
+				sender: Some(sender),
+			}).await?;
+			receiver.await?
+		}.boxed();
+
+		Box::new(future.map_err(Into::into).compat())
+	}
+
+	fn finalize_block(
+		&self,
+		hash: Hash,
+		justification: Option<Justification>,
+	) -> FutureResult<bool> {
+		let mut commands = self.commands.clone();
+		let future = async move {
+			let (sender, receiver) = oneshot::channel();
+			commands.send(EngineCommand::FinalizeBlock {
+				hash,
+				justification,
+				sender: Some(sender),
+			}).await?;
+			receiver.await?
+		}.boxed();
+
+		Box::new(future.map_err(Into::into).compat())
+	}
+}
+
+/// Answer a command with `result`, or log it if nobody waits for the answer.
+pub fn send_result<T: std::fmt::Debug>(sender: Sender<T>, result: Result<T, crate::Error>) {
+	match sender {
+		Some(sender) => {
+			if let Err(result) = sender.send(result) {
+				log::warn!(target: "manual-seal", "Nobody waits for the result of the command: {:?}", result);
+			}
+		}
+		None => match result {
+			Ok(value) => log::info!(target: "manual-seal", "Command done: {:?}", value),
+			Err(e) => log::debug!(target: "manual-seal", "Command failed: {}", e),
+		},
+	}
+}
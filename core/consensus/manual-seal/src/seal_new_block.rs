@@ -0,0 +1,90 @@
+// Copyright 2017-2019 Parity Technologies (UK) Ltd.
+// This file is part of Substrate.
+
+// Substrate is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Substrate is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Substrate.  If not, see <http://www.gnu.org/licenses/>.
+//! Authoring of a new block on a `SealNewBlock` command.
+
+use std::{collections::HashMap, time::Duration};
+use client::blockchain::HeaderBackend;
+use consensus_common::{
+	BlockImportParams, BlockOrigin, Environment, ForkChoiceStrategy, ImportResult, Proposer,
+	SelectChain, import_queue::BoxBlockImport,
+};
+use inherents::InherentDataProviders;
+use sr_primitives::{generic::BlockId, traits::{Block as BlockT, Header as HeaderT}};
+use crate::{Error, rpc::{self, CreatedBlock}};
+
+/// The maximum time the proposer takes to fill a block.
+const MAX_PROPOSAL_DURATION: Duration = Duration::from_secs(10);
+
+/// Author a block on `parent_hash`, or on the best block, import it and answer `sender`
+/// with its hash.
+pub async fn seal_new_block<B, CB, E, A, SC>(
+	create_empty: bool,
+	finalize: bool,
+	parent_hash: Option<B::Hash>,
+	sender: rpc::Sender<CreatedBlock<B::Hash>>,
+	client: &CB,
+	env: &mut E,
+	block_import: &mut BoxBlockImport<B>,
+	pool: &txpool::Pool<A>,
+	select_chain: &SC,
+	inherent_data_providers: &InherentDataProviders,
+) where
+	B: BlockT,
+	CB: HeaderBackend<B>,
+	E: Environment<B>,
+	E::Error: std::fmt::Debug,
+	A: txpool::ChainApi<Block = B>,
+	SC: SelectChain<B>,
+{
+	let result = async {
+		if pool.status().ready == 0 && !create_empty {
+			return Err(Error::EmptyTransactionPool);
+		}
+
+		let parent = match parent_hash {
+			Some(hash) => client.header(BlockId::Hash(hash))?
+				.ok_or_else(|| Error::BlockNotFound(format!("{}", hash)))?,
+			None => select_chain.best_chain()?,
+		};
+
+		let mut proposer = env.init(&parent)
+			.map_err(|e| Error::Other(format!("Unable to create the proposer: {:?}", e)))?;
+		let inherent_data = inherent_data_providers.create_inherent_data()?;
+		let block = proposer.propose(inherent_data, Default::default(), MAX_PROPOSAL_DURATION)
+			.await
+			.map_err(|e| Error::Other(format!("Unable to propose a block: {:?}", e)))?;
+
+		let (header, body) = block.deconstruct();
+		let hash = header.hash();
+		let params = BlockImportParams {
+			origin: BlockOrigin::Own,
+			header,
+			justification: None,
+			post_digests: Vec::new(),
+			body: Some(body),
+			finalized: finalize,
+			auxiliary: Vec::new(),
+			fork_choice: ForkChoiceStrategy::LongestChain,
+		};
+
+		match block_import.import_block(params, HashMap::new())? {
+			ImportResult::Imported(_) => Ok(CreatedBlock { hash }),
+			other => Err(Error::BlockImportError(other)),
+		}
+	}.await;
+
+	rpc::send_result(sender, result)
+}
@@ -0,0 +1,229 @@
+// Copyright 2017-2019 Parity Technologies (UK) Ltd.
+// This file is part of Substrate.
+
+// Substrate is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Substrate is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Substrate.  If not, see <http://www.gnu.org/licenses/>.
+//! Sha3 and Blake2 proof of work algorithms.
+//!
+//! The work of a block is the hash of its pre-seal hash and a nonce. A work meets a
+//! difficulty when the work, read as a 256-bit integer, multiplied by the difficulty
+//! does not overflow, so a difficulty of `d` takes `d` hashes on average. The seal of
+//! a block is the encoded nonce and work.
+//!
+//! `HashAlgorithm` reads the difficulty of the next block from the `DifficultyApi` of
+//! the runtime, which leaves the difficulty adjustment to the runtime.
+
+use std::{marker::PhantomData, sync::Arc, time::{SystemTime, UNIX_EPOCH}};
+use codec::{Encode, Decode};
+use inherents::{InherentData, InherentIdentifier, ProvideInherentData, RuntimeString};
+use pow::{Error, PowAlgorithm};
+use pow_primitives::DifficultyApi;
+use primitives::{H256, U256};
+use rand::Rng;
+use sha3::{Digest, Sha3_256};
+use sr_primitives::generic::BlockId;
+use sr_primitives::traits::{Block as BlockT, ProvideRuntimeApi};
+use srml_timestamp::{INHERENT_IDENTIFIER, InherentError, InherentType};
+
+/// A hash function to do the work with.
+pub trait PowHash: Send + Sync {
+	/// Hash `data`.
+	fn hash(data: &[u8]) -> H256;
+}
+
+/// Sha3-256.
+pub struct Sha3;
+
+impl PowHash for Sha3 {
+	fn hash(data: &[u8]) -> H256 {
+		H256::from_slice(&Sha3_256::digest(data))
+	}
+}
+
+/// Blake2b-256.
+pub struct Blake2;
+
+impl PowHash for Blake2 {
+	fn hash(data: &[u8]) -> H256 {
+		H256::from(primitives::blake2_256(data))
+	}
+}
+
+/// The seal of a mined block.
+#[derive(Clone, PartialEq, Eq, Encode, Decode, Debug)]
+pub struct Seal {
+	/// The nonce the work was done with.
+	pub nonce: H256,
+	/// The hash of the pre-seal hash and the nonce.
+	pub work: H256,
+}
+
+/// The work of `nonce` on the block with pre-seal hash `pre_hash`.
+pub fn work<H: PowHash>(pre_hash: &H256, nonce: &H256) -> H256 {
+	H::hash(&(pre_hash, nonce).encode())
+}
+
+/// Whether `work` meets `difficulty`.
+pub fn meets_difficulty(work: &H256, difficulty: U256) -> bool {
+	let (_, overflowed) = U256::from(&work[..]).overflowing_mul(difficulty);
+	!overflowed
+}
+
+/// Try `round` random nonces on the block with pre-seal hash `pre_hash`, and return the seal
+/// of the first which meets `difficulty`.
+pub fn mine<H: PowHash>(pre_hash: &H256, difficulty: U256, round: u32) -> Option<Seal> {
+	let mut rng = rand::thread_rng();
+
+	(0..round).find_map(|_| {
+		let nonce = H256::from(rng.gen::<[u8; 32]>());
+		let work = work::<H>(pre_hash, &nonce);
+
+		if meets_difficulty(&work, difficulty) {
+			Some(Seal { nonce, work })
+		} else {
+			None
+		}
+	})
+}
+
+/// Whether `seal` is a valid seal of the block with pre-seal hash `pre_hash` at `difficulty`.
+pub fn verify<H: PowHash>(pre_hash: &H256, seal: &Seal, difficulty: U256) -> bool {
+	meets_difficulty(&seal.work, difficulty) && work::<H>(pre_hash, &seal.nonce) == seal.work
+}
+
+/// Proof of work with hash function `H`, at the difficulty the runtime of `client` asks for.
+pub struct HashAlgorithm<H, C> {
+	client: Arc<C>,
+	_marker: PhantomData<H>,
+}
+
+impl<H, C> HashAlgorithm<H, C> {
+	/// Create a new algorithm reading the difficulty from the runtime of `client`.
+	pub fn new(client: Arc<C>) -> Self {
+		HashAlgorithm { client, _marker: PhantomData }
+	}
+}
+
+impl<H, C> Clone for HashAlgorithm<H, C> {
+	fn clone(&self) -> Self {
+		HashAlgorithm::new(self.client.clone())
+	}
+}
+
+impl<B, H, C> PowAlgorithm<B> for HashAlgorithm<H, C> where
+	B: BlockT<Hash=H256>,
+	H: PowHash,
+	C: ProvideRuntimeApi,
+	C::Api: DifficultyApi<B, U256>,
+{
+	type Difficulty = U256;
+
+	fn difficulty(&self, parent: &BlockId<B>) -> Result<U256, Error<B>> {
+		self.client.runtime_api().difficulty(parent)
+			.map_err(|e| Error::Environment(format!("Fetching difficulty from runtime failed: {:?}", e)))
+	}
+
+	fn verify(
+		&self,
+		_parent: &BlockId<B>,
+		pre_hash: &H256,
+		seal: &pow_primitives::Seal,
+		difficulty: U256,
+	) -> Result<bool, Error<B>> {
+		match Seal::decode(&mut &seal[..]) {
+			Ok(seal) => Ok(verify::<H>(pre_hash, &seal, difficulty)),
+			Err(_) => Ok(false),
+		}
+	}
+
+	fn mine(
+		&self,
+		_parent: &BlockId<B>,
+		pre_hash: &H256,
+		difficulty: U256,
+		round: u32,
+	) -> Result<Option<pow_primitives::Seal>, Error<B>> {
+		Ok(mine::<H>(pre_hash, difficulty, round).map(|seal| seal.encode()))
+	}
+}
+
+/// Timestamp inherent provider for checking the timestamps of imported blocks.
+///
+/// The `timestamp` module refuses a block whose timestamp is ahead of the provided one by
+/// more than a few milliseconds, while miners move their timestamps past their parents and
+/// clocks are never quite in sync. This provider gives the system clock plus `max_drift`
+/// milliseconds, which bounds how far into the future a miner can set its timestamps, and so
+/// how far it can pull down a difficulty adjusted on them.
+pub struct DriftTimestampProvider {
+	max_drift: InherentType,
+}
+
+impl DriftTimestampProvider {
+	/// Create a provider accepting timestamps up to `max_drift` milliseconds in the future.
+	pub fn new(max_drift: InherentType) -> Self {
+		DriftTimestampProvider { max_drift }
+	}
+}
+
+impl ProvideInherentData for DriftTimestampProvider {
+	fn inherent_identifier(&self) -> &'static InherentIdentifier {
+		&INHERENT_IDENTIFIER
+	}
+
+	fn provide_inherent_data(&self, inherent_data: &mut InherentData) -> Result<(), RuntimeString> {
+		let now = SystemTime::now().duration_since(UNIX_EPOCH)
+			.map_err(|_| RuntimeString::from("Current time is before unix epoch"))?
+			.as_millis() as InherentType;
+
+		inherent_data.put_data(INHERENT_IDENTIFIER, &now.saturating_add(self.max_drift))
+	}
+
+	fn error_to_string(&self, error: &[u8]) -> Option<String> {
+		InherentError::try_from(&INHERENT_IDENTIFIER, error).map(|e| format!("{:?}", e))
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn every_work_meets_difficulty_one() {
+		assert!(meets_difficulty(&H256::repeat_byte(0xff), U256::one()));
+		assert!(!meets_difficulty(&H256::repeat_byte(0xff), U256::from(2)));
+		assert!(meets_difficulty(&H256::zero(), U256::max_value()));
+	}
+
+	#[test]
+	fn mined_seals_verify() {
+		let pre_hash = H256::repeat_byte(1);
+		let difficulty = U256::from(16);
+
+		let seal = mine::<Sha3>(&pre_hash, difficulty, 10_000).expect("16 is an easy difficulty; qed");
+		assert!(verify::<Sha3>(&pre_hash, &seal, difficulty));
+		assert!(!verify::<Blake2>(&pre_hash, &seal, difficulty));
+		assert!(!verify::<Sha3>(&H256::repeat_byte(2), &seal, difficulty));
+
+		let seal = mine::<Blake2>(&pre_hash, difficulty, 10_000).expect("16 is an easy difficulty; qed");
+		assert!(verify::<Blake2>(&pre_hash, &seal, difficulty));
+	}
+
+	#[test]
+	fn seal_with_forged_work_does_not_verify() {
+		let pre_hash = H256::repeat_byte(1);
+		let seal = Seal { nonce: H256::repeat_byte(3), work: H256::zero() };
+
+		assert!(meets_difficulty(&seal.work, U256::max_value()));
+		assert!(!verify::<Blake2>(&pre_hash, &seal, U256::max_value()));
+	}
+}
@@ -32,6 +32,8 @@
 use std::sync::Arc;
 use std::thread;
 use std::collections::HashMap;
+use std::marker::PhantomData;
+use std::time::{SystemTime, UNIX_EPOCH};
 use client::{
 	BlockOf, blockchain::{HeaderBackend, ProvideCache},
 	block_builder::api::BlockBuilder as BlockBuilderApi, backend::AuxStore,
@@ -40,10 +42,13 @@ use client::{
 use sr_primitives::{Justification, RuntimeString};
 use sr_primitives::generic::{BlockId, Digest, DigestItem};
 use sr_primitives::traits::{Block as BlockT, Header as HeaderT, ProvideRuntimeApi};
-use srml_timestamp::{TimestampInherentData, InherentError as TIError};
-use pow_primitives::{Seal, TotalDifficulty, POW_ENGINE_ID};
+use srml_timestamp::{
+	TimestampInherentData, InherentError as TIError, InherentType as TimestampInherentType,
+	INHERENT_IDENTIFIER as TIMESTAMP_INHERENT_IDENTIFIER,
+};
+use pow_primitives::{Seal, TimestampApi, TotalDifficulty, POW_ENGINE_ID};
 use primitives::H256;
-use inherents::{InherentDataProviders, InherentData};
+use inherents::{InherentDataProviders, InherentData, InherentIdentifier, ProvideInherentData};
 use consensus_common::{
 	BlockImportParams, BlockOrigin, ForkChoiceStrategy, SyncOracle, Environment, Proposer,
 	SelectChain, Error as ConsensusError
@@ -148,11 +153,17 @@ pub trait PowAlgorithm<B: BlockT> {
 	) -> Result<Option<Seal>, Error<B>>;
 }
 
+/// Adds to the inherent data of an imported block what the inherent data providers can not
+/// know, because it can only be taken from the block itself.
+pub type BlockInherentData<B> =
+	Box<dyn Fn(&B, &mut InherentData) -> Result<(), String> + Send + Sync>;
+
 /// A verifier for PoW blocks.
 pub struct PowVerifier<B: BlockT<Hash=H256>, C, S, Algorithm> {
 	client: Arc<C>,
 	algorithm: Algorithm,
 	inherent_data_providers: inherents::InherentDataProviders,
+	block_inherent_data: Option<BlockInherentData<B>>,
 	select_chain: Option<S>,
 	check_inherents_after: <<B as BlockT>::Header as HeaderT>::Number,
 }
@@ -164,8 +175,16 @@ impl<B: BlockT<Hash=H256>, C, S, Algorithm> PowVerifier<B, C, S, Algorithm> {
 		check_inherents_after: <<B as BlockT>::Header as HeaderT>::Number,
 		select_chain: Option<S>,
 		inherent_data_providers: inherents::InherentDataProviders,
+		block_inherent_data: Option<BlockInherentData<B>>,
 	) -> Self {
-		Self { client, algorithm, inherent_data_providers, select_chain, check_inherents_after }
+		Self {
+			client,
+			algorithm,
+			inherent_data_providers,
+			block_inherent_data,
+			select_chain,
+			check_inherents_after,
+		}
 	}
 
 	fn check_header(
@@ -259,7 +278,7 @@ impl<B: BlockT<Hash=H256>, C, S, Algorithm> Verifier<B> for PowVerifier<B, C, S,
 		justification: Option<Justification>,
 		mut body: Option<Vec<B::Extrinsic>>,
 	) -> Result<(BlockImportParams<B>, Option<Vec<(CacheKeyId, Vec<u8>)>>), String> {
-		let inherent_data = self.inherent_data_providers
+		let mut inherent_data = self.inherent_data_providers
 			.create_inherent_data().map_err(String::from)?;
 		let timestamp_now = inherent_data.timestamp_inherent_data().map_err(String::from)?;
 
@@ -284,6 +303,10 @@ impl<B: BlockT<Hash=H256>, C, S, Algorithm> Verifier<B> for PowVerifier<B, C, S,
 		if let Some(inner_body) = body.take() {
 			let block = B::new(checked_header.clone(), inner_body);
 
+			if let Some(block_inherent_data) = self.block_inherent_data.as_ref() {
+				block_inherent_data(&block, &mut inherent_data)?;
+			}
+
 			self.check_inherents(
 				block.clone(),
 				BlockId::Hash(parent_hash),
@@ -324,6 +347,62 @@ pub fn register_pow_inherent_data_provider(
 	}
 }
 
+/// Timestamp inherent provider for blocks authored faster than the runtime expects.
+///
+/// The `timestamp` module refuses a block closer to its parent than its minimum period, and
+/// `aura` or `babe` refuse two blocks in one slot, while mined or manually sealed blocks come
+/// at any interval. This provider gives the latest timestamp of the leaves of `select_chain`
+/// plus `min_interval` milliseconds, read through the `TimestampApi` of the runtime, when that
+/// is later than the system clock. As timestamps only grow along a chain, the timestamp is
+/// past the one of any block the author builds on.
+pub struct TimestampProvider<B, C, SC> {
+	client: Arc<C>,
+	select_chain: SC,
+	min_interval: TimestampInherentType,
+	_marker: PhantomData<B>,
+}
+
+impl<B, C, SC> TimestampProvider<B, C, SC> {
+	/// Create a provider keeping blocks at least `min_interval` milliseconds past the leaves of
+	/// `select_chain`.
+	pub fn new(client: Arc<C>, select_chain: SC, min_interval: TimestampInherentType) -> Self {
+		TimestampProvider { client, select_chain, min_interval, _marker: PhantomData }
+	}
+}
+
+impl<B, C, SC> ProvideInherentData for TimestampProvider<B, C, SC> where
+	B: BlockT,
+	C: ProvideRuntimeApi + Send + Sync,
+	C::Api: TimestampApi<B, TimestampInherentType>,
+	SC: SelectChain<B>,
+{
+	fn inherent_identifier(&self) -> &'static InherentIdentifier {
+		&TIMESTAMP_INHERENT_IDENTIFIER
+	}
+
+	fn provide_inherent_data(&self, inherent_data: &mut InherentData) -> Result<(), RuntimeString> {
+		let now = SystemTime::now().duration_since(UNIX_EPOCH)
+			.map_err(|_| RuntimeString::from("Current time is before unix epoch"))?
+			.as_millis() as TimestampInherentType;
+
+		let leaves = self.select_chain.leaves()
+			.map_err(|e| RuntimeString::from(format!("Fetching the leaves failed: {:?}", e)))?;
+		let mut latest: TimestampInherentType = 0;
+		for leaf in leaves {
+			let timestamp = self.client.runtime_api().timestamp(&BlockId::Hash(leaf))
+				.map_err(|e| RuntimeString::from(format!("Fetching timestamp from runtime failed: {:?}", e)))?;
+			latest = latest.max(timestamp);
+		}
+
+		let timestamp = now.max(latest.saturating_add(self.min_interval));
+		inherent_data.put_data(TIMESTAMP_INHERENT_IDENTIFIER, &timestamp)
+	}
+
+	fn error_to_string(&self, error: &[u8]) -> Option<String> {
+		TIError::try_from(&TIMESTAMP_INHERENT_IDENTIFIER, error).map(|e| format!("{:?}", e))
+	}
+}
+
 /// The PoW import queue type.
 pub type PowImportQueue<B> = BasicQueue<B>;
 
@@ -335,6 +414,7 @@ pub fn import_queue<B, C, S, Algorithm>(
 	check_inherents_after: <<B as BlockT>::Header as HeaderT>::Number,
 	select_chain: Option<S>,
 	inherent_data_providers: InherentDataProviders,
+	block_inherent_data: Option<BlockInherentData<B>>,
 ) -> Result<PowImportQueue<B>, consensus_common::Error> where
 	B: BlockT<Hash=H256>,
 	C: ProvideRuntimeApi + HeaderBackend<B> + BlockOf + ProvideCache<B> + AuxStore,
@@ -351,6 +431,7 @@ pub fn import_queue<B, C, S, Algorithm>(
 		check_inherents_after,
 		select_chain,
 		inherent_data_providers,
+		block_inherent_data,
 	);
 
 	Ok(BasicQueue::new(
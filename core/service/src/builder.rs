@@ -165,6 +165,7 @@ where TGen: RuntimeGenesis, TCSExt: Extension {
 				config.state_cache_child_ratio.map(|v| (v, 100)),
 			path: config.database_path.clone(),
 			pruning: config.pruning.clone(),
+			kind: config.database,
 		};
 
 		let executor = NativeExecutor::<TExecDisp>::new(
@@ -240,6 +241,7 @@ where TGen: RuntimeGenesis, TCSExt: Extension {
 				config.state_cache_child_ratio.map(|v| (v, 100)),
 			path: config.database_path.clone(),
 			pruning: config.pruning.clone(),
+			kind: config.database,
 		};
 
 		let executor = NativeExecutor::<TExecDisp>::new(
@@ -719,6 +721,21 @@ pub trait ServiceBuilderExport {
 	) -> Result<(), Error>;
 }
 
+/// Implemented on `ServiceBuilder`. Allows exporting the state of a block once you have given
+/// all the required components to the builder.
+pub trait ServiceBuilderExportState {
+	/// Type of block of the builder.
+	type Block: BlockT;
+
+	/// Writes a snapshot of the state at block `at`, or at the last finalized block with a
+	/// justification.
+	fn export_state(
+		&self,
+		output: impl Write,
+		at: Option<NumberFor<Self::Block>>,
+	) -> Result<(), Error>;
+}
+
 /// Implemented on `ServiceBuilder`. Allows reverting the chain once you have given all the
 /// required components to the builder.
 pub trait ServiceBuilderRevert {
@@ -781,6 +798,26 @@ where
 	}
 }
 
+impl<TBl, TRtApi, TCfg, TGen, TCSExt, TExec, TFchr, TSc, TImpQu, TFprb, TFpp, TNetP, TExPool, TRpc, TRpcB>
+	ServiceBuilderExportState for ServiceBuilder<TBl, TRtApi, TCfg, TGen, TCSExt,
+		Client<TFullBackend<TBl>, TExec, TBl, TRtApi>, TFchr, TSc, TImpQu, TFprb, TFpp, TNetP, TExPool, TRpc, TRpcB,
+		TFullBackend<TBl>>
+where
+	TBl: BlockT<Hash = <Blake2Hasher as Hasher>::Out>,
+	TExec: 'static + client::CallExecutor<TBl, Blake2Hasher> + Send + Sync + Clone
+{
+	type Block = TBl;
+
+	fn export_state(
+		&self,
+		mut output: impl Write,
+		at: Option<NumberFor<TBl>>,
+	) -> Result<(), Error> {
+		let backend = &self.backend;
+		export_state!(backend, output, at)
+	}
+}
+
 impl<TBl, TRtApi, TCfg, TGen, TCSExt, TBackend, TExec, TFchr, TSc, TImpQu, TFprb, TFpp, TNetP, TExPool, TRpc, TRpcB>
 	ServiceBuilderRevert for ServiceBuilder<TBl, TRtApi, TCfg, TGen, TCSExt, Client<TBackend, TExec, TBl, TRtApi>,
 		TFchr, TSc, TImpQu, TFprb, TFpp, TNetP, TExPool, TRpc, TRpcB, TBackend>
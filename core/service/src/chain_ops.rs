@@ -17,7 +17,15 @@
 //! Chain utilities.
 
 use crate::error;
+use crate::config::{Configuration, DatabaseKind, PruningMode};
 use chain_spec::{ChainSpec, RuntimeGenesis, Extension};
+use codec::{Decode, IoReader};
+use log::info;
+use primitives::H256;
+use sr_primitives::traits::{Block as BlockT, Header as HeaderT};
+use std::fs;
+use std::io::Read;
+use std::path::{Path, PathBuf};
 
 /// Defines the logic for an operation exporting blocks within a range.
 #[macro_export]
@@ -222,6 +230,170 @@ macro_rules! revert_chain {
 }}
 }
 
+/// Export a snapshot of the state at a finalized block with a justification.
+#[macro_export]
+macro_rules! export_state {
+($backend:ident, $output:ident, $at:ident) => {{
+	let at = match $at {
+		Some(v) => v,
+		None => $backend.latest_justified_block()?
+			.ok_or("No finalized block has a justification")?,
+	};
+
+	info!("Exporting state of block #{}", at);
+	let snapshot = $backend.export_state(BlockId::number(at))?;
+	$output.write_all(&snapshot.encode())?;
+	info!(
+		"Exported {} storage entries and {} child tries of block #{} ({})",
+		snapshot.top.len(),
+		snapshot.children.len(),
+		at,
+		snapshot.header.hash(),
+	);
+	Ok(())
+}}
+}
+
+/// Bootstrap an empty database from a state snapshot.
+///
+/// The snapshot is only imported if its block has the trusted `hash`, and its state is checked
+/// against the state root of that block. Once imported, the snapshot block is the best and
+/// finalized block of the database and a node started on it syncs forward from there.
+pub fn import_state<TBl, C, G, E>(
+	config: &Configuration<C, G, E>,
+	input: impl Read,
+	hash: TBl::Hash,
+) -> error::Result<()> where
+	TBl: BlockT<Hash=H256>,
+	G: RuntimeGenesis,
+	E: Extension,
+{
+	let snapshot = client_db::StateSnapshot::<TBl>::decode(&mut IoReader(input))
+		.map_err(|e| format!("Error reading state snapshot: {}", e))?;
+	info!(
+		"Importing state of block #{} ({})",
+		snapshot.header.number(),
+		snapshot.header.hash(),
+	);
+
+	if snapshot.header.hash() != hash {
+		return Err(format!(
+			"State snapshot is of block {}, but block {} was expected",
+			snapshot.header.hash(),
+			hash,
+		).into());
+	}
+
+	client_db::import_state(database_settings(config), &config.chain_spec, snapshot)?;
+	Ok(())
+}
+
+/// Move the node database to another kind of database.
+///
+/// The entries are copied into a new database next to the current one. Once the copy is
+/// complete, the new database takes the place of the current one, which is kept as a backup.
+/// Running it again after an interruption starts over, or completes the move if the copy was
+/// complete.
+pub fn migrate_database<C, G, E>(
+	config: &Configuration<C, G, E>,
+	to: DatabaseKind,
+) -> error::Result<()> {
+	let from = database_settings(config);
+	if from.kind == to {
+		return Err(format!("Database is already a {} database", to).into());
+	}
+
+	let backup_path = with_suffix(&from.path, &format!("{}-backup", from.kind));
+	let migration_path = client_db::migration_path(&from.path);
+	if !from.path.exists() && backup_path.exists() && migration_path.exists() {
+		// The source is only moved to the backup once the copy is complete, so a migration
+		// that stopped between the two renames just has to move the new database into place.
+		fs::rename(&migration_path, &from.path)?;
+		info!(
+			"Completed an interrupted migration. The {} database was moved to {}",
+			from.kind,
+			backup_path.display(),
+		);
+		return Ok(());
+	}
+	if !from.path.exists() {
+		return Err(format!("No database found at {}", from.path.display()).into());
+	}
+	if backup_path.exists() {
+		return Err(format!("Backup location {} already exists", backup_path.display()).into());
+	}
+
+	if migration_path.exists() {
+		// Left behind by a migration that did not complete.
+		fs::remove_dir_all(&migration_path)?;
+	}
+	let destination = client_db::DatabaseSettings {
+		path: migration_path.clone(),
+		kind: to,
+		..database_settings(config)
+	};
+
+	info!("Migrating {} database at {} to {}", from.kind, from.path.display(), to);
+	let copied = client_db::migrate_database(&from, &destination, |col, copied| {
+		info!("Copied {} entries (column {})", copied, col);
+	})?;
+
+	fs::rename(&from.path, &backup_path)?;
+	fs::rename(&migration_path, &from.path)?;
+	info!(
+		"Migrated {} entries. The {} database was moved to {}",
+		copied,
+		from.kind,
+		backup_path.display(),
+	);
+	Ok(())
+}
+
+/// Change the pruning mode of the node database.
+///
+/// Turning an archive into a pruned database removes the historic state, logging progress as
+/// the live state is found and the rest is removed.
+pub fn change_pruning_mode<TBl, C, G, E>(
+	config: &Configuration<C, G, E>,
+	to: PruningMode,
+) -> error::Result<()> where
+	TBl: BlockT<Hash=H256>,
+{
+	let settings = database_settings(config);
+	if !settings.path.exists() {
+		return Err(format!("No database found at {}", settings.path.display()).into());
+	}
+
+	info!("Changing pruning mode to {}", to.id());
+	client_db::change_pruning_mode::<TBl>(&settings, to, |report| match report {
+		client_db::PruningProgress::Marking(nodes) =>
+			info!("Found {} live state nodes", nodes),
+		client_db::PruningProgress::Sweeping { checked, removed } =>
+			info!("Checked {} state nodes, removed {}", checked, removed),
+	})?;
+	info!("Pruning mode changed");
+	Ok(())
+}
+
+fn database_settings<C, G, E>(config: &Configuration<C, G, E>) -> client_db::DatabaseSettings {
+	client_db::DatabaseSettings {
+		cache_size: None,
+		state_cache_size: config.state_cache_size,
+		state_cache_child_ratio:
+			config.state_cache_child_ratio.map(|v| (v, 100)),
+		path: config.database_path.clone(),
+		pruning: config.pruning.clone(),
+		kind: config.database,
+	}
+}
+
+fn with_suffix(path: &Path, suffix: &str) -> PathBuf {
+	let mut name = path.file_name().map(|name| name.to_os_string()).unwrap_or_default();
+	name.push(".");
+	name.push(suffix);
+	path.with_file_name(name)
+}
+
 /// Build a chain spec json
 pub fn build_spec<G, E>(spec: ChainSpec<G, E>, raw: bool) -> error::Result<String> where
 	G: RuntimeGenesis,
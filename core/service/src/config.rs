@@ -17,7 +17,7 @@
 //! Service configuration.
 
 pub use client::ExecutionStrategies;
-pub use client_db::PruningMode;
+pub use client_db::{PruningMode, DatabaseKind};
 pub use network::config::{ExtTransport, NetworkConfiguration, Roles};
 pub use substrate_executor::WasmExecutionMethod;
 
@@ -47,6 +47,8 @@ pub struct Configuration<C, G, E = NoExtension> {
 	pub keystore_path: PathBuf,
 	/// Path to the database.
 	pub database_path: PathBuf,
+	/// Kind of database the node storage is kept in.
+	pub database: DatabaseKind,
 	/// Cache Size for internal database in MiB
 	pub database_cache_size: Option<u32>,
 	/// Size of internal state cache in Bytes
@@ -114,6 +116,7 @@ impl<C, G, E> Configuration<C, G, E> where
 			network: Default::default(),
 			keystore_path: Default::default(),
 			database_path: Default::default(),
+			database: Default::default(),
 			database_cache_size: Default::default(),
 			state_cache_size: Default::default(),
 			state_cache_child_ratio: Default::default(),
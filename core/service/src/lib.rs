@@ -51,8 +51,11 @@ use sr_primitives::generic::BlockId;
 use sr_primitives::traits::NumberFor;
 
 pub use self::error::Error;
-pub use self::builder::{ServiceBuilder, ServiceBuilderExport, ServiceBuilderImport, ServiceBuilderRevert};
-pub use config::{Configuration, Roles, PruningMode};
+pub use self::builder::{
+	ServiceBuilder, ServiceBuilderExport, ServiceBuilderExportState, ServiceBuilderImport,
+	ServiceBuilderRevert,
+};
+pub use config::{Configuration, Roles, PruningMode, DatabaseKind};
 pub use chain_spec::{ChainSpec, Properties, RuntimeGenesis, Extension as ChainSpecExtension};
 pub use transaction_pool::txpool::{
 	self, Pool as TransactionPool, Options as TransactionPoolOptions, ChainApi, IntoPoolError
@@ -171,6 +171,7 @@ fn node_config<G, E: Clone> (
 		keystore_path: root.join("key"),
 		keystore_password: None,
 		database_path: root.join("db"),
+		database: Default::default(),
 		database_cache_size: None,
 		state_cache_size: 16777216,
 		state_cache_child_ratio: None,
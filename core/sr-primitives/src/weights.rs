@@ -29,6 +29,15 @@ use arithmetic::traits::Bounded;
 /// Numeric range of a transaction weight.
 pub type Weight = u32;
 
+/// Weight of a balance transfer, which the weights of other dispatches are estimated against.
+pub const TRANSFER_WEIGHT: Weight = 1_000_000;
+
+/// Weight of a storage read.
+pub const READ_WEIGHT: Weight = 25_000;
+
+/// Weight of a storage write.
+pub const WRITE_WEIGHT: Weight = 100_000;
+
 /// A generalized group of dispatch types. This is only distinguishing normal, user-triggered transactions
 /// (`Normal`) and anything beyond which serves a higher purpose to the system (`Operational`).
 #[cfg_attr(feature = "std", derive(Debug))]
@@ -28,7 +28,12 @@
 //! # Pruning.
 //! See `RefWindow` for pruning algorithm details. `StateDb` prunes on each canonicalization until pruning
 //! constraints are satisfied.
+//!
+//! # Pruning mode.
+//! The pruning mode is recorded in the database and opening it with a different mode fails. See
+//! `change_pruning_mode` for converting an existing database.
 
+mod migration;
 mod noncanonical;
 mod pruning;
 #[cfg(test)] mod test;
@@ -41,6 +46,12 @@ use noncanonical::NonCanonicalOverlay;
 use pruning::RefWindow;
 use log::trace;
 
+pub use migration::{change_pruning_mode, stored_pruning_mode};
+pub use noncanonical::last_canonicalized;
+
+const PRUNING_MODE: &[u8] = b"mode";
+const FIRST_KEPT: &[u8] = b"first_kept";
+
 /// Database value type.
 pub type DBValue = Vec<u8>;
 
@@ -77,6 +88,8 @@ pub enum Error<E: fmt::Debug> {
 	InvalidBlockNumber,
 	/// Trying to insert block with unknown parent.
 	InvalidParent,
+	/// Pruning mode does not match the database, or can't be changed as requested.
+	InvalidPruningMode(String),
 }
 
 /// Pinning error type.
@@ -99,6 +112,7 @@ impl<E: fmt::Debug> fmt::Debug for Error<E> {
 			Error::InvalidBlock => write!(f, "Trying to canonicalize invalid block"),
 			Error::InvalidBlockNumber => write!(f, "Trying to insert block with invalid number"),
 			Error::InvalidParent => write!(f, "Trying to insert block with unknown parent"),
+			Error::InvalidPruningMode(e) => write!(f, "Invalid pruning mode: {}", e),
 		}
 	}
 }
@@ -159,6 +173,15 @@ impl PruningMode {
 		}
 	}
 
+	/// Name of the mode as recorded in the database. Constrained modes share a name, since the
+	/// constraints may change between runs.
+	pub fn id(&self) -> &'static str {
+		match *self {
+			PruningMode::ArchiveAll => "archive",
+			PruningMode::ArchiveCanonical => "archive-canonical",
+			PruningMode::Constrained(_) => "constrained",
+		}
+	}
 }
 
 impl Default for PruningMode {
@@ -178,11 +201,29 @@ struct StateDbSync<BlockHash: Hash, Key: Hash> {
 	non_canonical: NonCanonicalOverlay<BlockHash, Key>,
 	pruning: Option<RefWindow<BlockHash, Key>>,
 	pinned: HashMap<BlockHash, u32>,
+	/// First canonical block with state, for `ArchiveCanonical` databases that used to be pruned.
+	first_kept: u64,
+	/// Whether the pruning mode is recorded in the database.
+	mode_stored: bool,
+	/// Whether pending changes record the pruning mode.
+	mode_pending: bool,
 }
 
 impl<BlockHash: Hash, Key: Hash> StateDbSync<BlockHash, Key> {
 	pub fn new<D: MetaDb>(mode: PruningMode, db: &D) -> Result<StateDbSync<BlockHash, Key>, Error<D::Error>> {
 		trace!(target: "state-db", "StateDb settings: {:?}", mode);
+		// Databases created before the mode was recorded have it inferred, so that it is only
+		// recorded if it matches. An `ArchiveAll` database can't be told apart from a new one.
+		if let Some(stored_mode) = stored_pruning_mode(db)? {
+			if stored_mode.id() != mode.id() {
+				return Err(Error::InvalidPruningMode(format!(
+					"database uses {} pruning, {} was requested",
+					stored_mode.id(),
+					mode.id(),
+				)));
+			}
+		}
+		let mode_stored = db.get_meta(&to_meta_key(PRUNING_MODE, &())).map_err(|e| Error::Db(e))?.is_some();
 		let non_canonical: NonCanonicalOverlay<BlockHash, Key> = NonCanonicalOverlay::new(db)?;
 		let pruning: Option<RefWindow<BlockHash, Key>> = match mode {
 			PruningMode::Constrained(Constraints {
@@ -197,28 +238,42 @@ impl<BlockHash: Hash, Key: Hash> StateDbSync<BlockHash, Key> {
 			non_canonical,
 			pruning,
 			pinned: Default::default(),
+			first_kept: migration::read_first_kept(db)?,
+			mode_stored,
+			mode_pending: false,
 		})
 	}
 
+	/// Record the pruning mode with the first changes written to the database.
+	fn store_mode(&mut self, commit: &mut CommitSet<Key>) {
+		if !self.mode_stored {
+			commit.meta.inserted.push((to_meta_key(PRUNING_MODE, &()), self.mode.id().as_bytes().to_vec()));
+			self.mode_pending = true;
+		}
+	}
+
 	pub fn insert_block<E: fmt::Debug>(&mut self, hash: &BlockHash, number: u64, parent_hash: &BlockHash, mut changeset: ChangeSet<Key>) -> Result<CommitSet<Key>, Error<E>> {
-		match self.mode {
+		let mut commit = match self.mode {
 			PruningMode::ArchiveAll => {
 				changeset.deleted.clear();
 				// write changes immediately
-				Ok(CommitSet {
+				CommitSet {
 					data: changeset,
 					meta: Default::default(),
-				})
+				}
 			},
 			PruningMode::Constrained(_) | PruningMode::ArchiveCanonical => {
-				self.non_canonical.insert(hash, number, parent_hash, changeset)
+				self.non_canonical.insert(hash, number, parent_hash, changeset)?
 			}
-		}
+		};
+		self.store_mode(&mut commit);
+		Ok(commit)
 	}
 
 	pub fn canonicalize_block<E: fmt::Debug>(&mut self, hash: &BlockHash) -> Result<CommitSet<Key>, Error<E>> {
 		let mut commit = CommitSet::default();
 		if self.mode == PruningMode::ArchiveAll {
+			self.store_mode(&mut commit);
 			return Ok(commit)
 		}
 		match self.non_canonical.canonicalize(&hash, &mut commit) {
@@ -233,6 +288,7 @@ impl<BlockHash: Hash, Key: Hash> StateDbSync<BlockHash, Key> {
 			pruning.note_canonical(&hash, &mut commit);
 		}
 		self.prune(&mut commit);
+		self.store_mode(&mut commit);
 		Ok(commit)
 	}
 
@@ -247,7 +303,10 @@ impl<BlockHash: Hash, Key: Hash> StateDbSync<BlockHash, Key> {
 				if self.best_canonical().map(|c| number > c).unwrap_or(true) {
 					!self.non_canonical.have_block(hash)
 				} else {
-					self.pruning.as_ref().map_or(false, |pruning| number < pruning.pending() || !pruning.have_block(hash))
+					match self.pruning {
+						Some(ref pruning) => number < pruning.pending() || !pruning.have_block(hash),
+						None => number < self.first_kept,
+					}
 				}
 			}
 		}
@@ -287,7 +346,7 @@ impl<BlockHash: Hash, Key: Hash> StateDbSync<BlockHash, Key> {
 		}
 	}
 
-	pub fn pin(&mut self, hash: &BlockHash) -> Result<(), PinError> {
+	pub fn pin(&mut self, hash: &BlockHash, number: u64) -> Result<(), PinError> {
 		match self.mode {
 			PruningMode::ArchiveAll => Ok(()),
 			PruningMode::ArchiveCanonical | PruningMode::Constrained(_) => {
@@ -301,6 +360,9 @@ impl<BlockHash: Hash, Key: Hash> StateDbSync<BlockHash, Key> {
 					}
 					*refs += 1;
 					Ok(())
+				} else if self.mode == PruningMode::ArchiveCanonical && !self.is_pruned(hash, number) {
+					// canonical state is not pruned, unless it was before the database became an archive.
+					Ok(())
 				} else {
 					Err(PinError::InvalidBlock)
 				}
@@ -334,6 +396,8 @@ impl<BlockHash: Hash, Key: Hash> StateDbSync<BlockHash, Key> {
 	}
 
 	pub fn apply_pending(&mut self) {
+		self.mode_stored |= self.mode_pending;
+		self.mode_pending = false;
 		self.non_canonical.apply_pending();
 		if let Some(pruning) = &mut self.pruning {
 			pruning.apply_pending();
@@ -348,6 +412,7 @@ impl<BlockHash: Hash, Key: Hash> StateDbSync<BlockHash, Key> {
 	}
 
 	pub fn revert_pending(&mut self) {
+		self.mode_pending = false;
 		if let Some(pruning) = &mut self.pruning {
 			pruning.revert_pending();
 		}
@@ -380,8 +445,8 @@ impl<BlockHash: Hash, Key: Hash> StateDb<BlockHash, Key> {
 	}
 
 	/// Prevents pruning of specified block and its descendants.
-	pub fn pin(&self, hash: &BlockHash) -> Result<(), PinError> {
-		self.db.write().pin(hash)
+	pub fn pin(&self, hash: &BlockHash, number: u64) -> Result<(), PinError> {
+		self.db.write().pin(hash, number)
 	}
 
 	/// Allows pruning of specified block.
@@ -0,0 +1,270 @@
+// Copyright 2019 Parity Technologies (UK) Ltd.
+// This file is part of Substrate.
+
+// Substrate is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Substrate is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Substrate.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Pruning mode changes.
+//!
+//! Only the metadata is converted here. Removing the historic state nodes when an archive
+//! becomes constrained is left to the caller, which knows how to find the nodes that are live.
+
+use codec::{Decode, Encode};
+
+use crate::{ChangeSet, Constraints, Error, Hash, MetaDb, PruningMode, FIRST_KEPT, PRUNING_MODE, to_meta_key};
+use crate::{noncanonical, pruning};
+
+/// Read the pruning mode recorded in the database.
+///
+/// Databases created before the mode was recorded have it inferred from the metadata each
+/// mode keeps: a `Constrained` database has a pruning window, and an `ArchiveCanonical` one
+/// only records the last canonical block. `ArchiveAll` keeps no metadata, so `None` is returned
+/// for it just as for a database without blocks.
+///
+/// Constraints are not recorded, so a constrained mode is returned with default constraints.
+pub fn stored_pruning_mode<D: MetaDb>(db: &D) -> Result<Option<PruningMode>, Error<D::Error>> {
+	let stored = db.get_meta(&to_meta_key(PRUNING_MODE, &())).map_err(|e| Error::Db(e))?;
+	let stored = match stored {
+		Some(stored) => stored,
+		None => return legacy_pruning_mode(db),
+	};
+	[PruningMode::ArchiveAll, PruningMode::ArchiveCanonical, PruningMode::Constrained(Constraints::default())]
+		.iter()
+		.find(|mode| mode.id().as_bytes() == stored.as_slice())
+		.cloned()
+		.map(Some)
+		.ok_or_else(|| Error::InvalidPruningMode(format!("unknown mode {}", String::from_utf8_lossy(&stored))))
+}
+
+fn legacy_pruning_mode<D: MetaDb>(db: &D) -> Result<Option<PruningMode>, Error<D::Error>> {
+	if pruning::has_window(db)? {
+		return Ok(Some(PruningMode::Constrained(Constraints::default())));
+	}
+	let last_canonical = db.get_meta(&to_meta_key(noncanonical::LAST_CANONICAL, &())).map_err(|e| Error::Db(e))?;
+	Ok(last_canonical.map(|_| PruningMode::ArchiveCanonical))
+}
+
+/// First canonical block whose state is kept by an `ArchiveCanonical` database that used to be
+/// pruned. The state of all canonical blocks is kept otherwise.
+pub(crate) fn read_first_kept<D: MetaDb>(db: &D) -> Result<u64, Error<D::Error>> {
+	match db.get_meta(&to_meta_key(FIRST_KEPT, &())).map_err(|e| Error::Db(e))? {
+		Some(buffer) => Ok(u64::decode(&mut buffer.as_slice())?),
+		None => Ok(0),
+	}
+}
+
+/// Metadata changes that switch the database to another pruning mode.
+///
+/// `canonical` is the last canonicalized block, or the last finalized block of an `ArchiveAll`
+/// database, which does not track canonicalization.
+///
+/// - From an archive to `Constrained`: the pruning window starts with `canonical` only. The
+///   caller has to delete the state nodes that are not reachable from `canonical` or from the
+///   non-canonical blocks.
+/// - From `Constrained` to `ArchiveCanonical`: the pruning window is dropped, so the state of
+///   the blocks in it and of all blocks canonicalized later is kept. Blocks below the window
+///   stay pruned.
+/// - From `ArchiveAll` to `ArchiveCanonical`: canonicalization starts at `canonical`.
+///
+/// `ArchiveAll` keeps the state of non-canonical blocks in the database instead of a journal.
+/// When converting from it, `unfinalized` lists those blocks as `(hash, number, parent_hash)`,
+/// sorted by number and descending from `canonical`. They are journaled without changes, so
+/// their nodes are not removed if they are discarded.
+///
+/// A database without pruning metadata is taken to be an `ArchiveAll` database, see
+/// `stored_pruning_mode`. The caller has to check that it has blocks.
+pub fn change_pruning_mode<BlockHash: Hash, Key: Hash, D: MetaDb>(
+	db: &D,
+	to: &PruningMode,
+	canonical: (BlockHash, u64),
+	unfinalized: &[(BlockHash, u64, BlockHash)],
+) -> Result<ChangeSet<Vec<u8>>, Error<D::Error>> {
+	let from = stored_pruning_mode(db)?.unwrap_or(PruningMode::ArchiveAll);
+
+	let mut changes = match (&from, to) {
+		(PruningMode::ArchiveAll, PruningMode::Constrained(_)) => {
+			let mut changes = noncanonical::journal_blocks::<BlockHash, Key>(&canonical, unfinalized);
+			extend(&mut changes, pruning::start_window::<BlockHash, Key>(&canonical.0, canonical.1));
+			changes
+		},
+		(PruningMode::ArchiveAll, PruningMode::ArchiveCanonical) =>
+			noncanonical::journal_blocks::<BlockHash, Key>(&canonical, unfinalized),
+		(PruningMode::ArchiveCanonical, PruningMode::Constrained(_)) => {
+			let mut changes = pruning::start_window::<BlockHash, Key>(&canonical.0, canonical.1);
+			changes.deleted.push(to_meta_key(FIRST_KEPT, &()));
+			changes
+		},
+		(PruningMode::Constrained(_), PruningMode::ArchiveCanonical) => {
+			let first_kept = pruning::read_pending_number(db)?;
+			let mut changes = pruning::remove_window(db)?;
+			changes.inserted.push((to_meta_key(FIRST_KEPT, &()), first_kept.encode()));
+			changes
+		},
+		(from, to) if from.id() == to.id() => return Err(Error::InvalidPruningMode(
+			format!("the database already uses {} pruning", to.id())
+		)),
+		(from, to) => return Err(Error::InvalidPruningMode(
+			format!("can't change {} pruning to {}", from.id(), to.id())
+		)),
+	};
+	changes.inserted.push((to_meta_key(PRUNING_MODE, &()), to.id().as_bytes().to_vec()));
+	Ok(changes)
+}
+
+fn extend(changes: &mut ChangeSet<Vec<u8>>, other: ChangeSet<Vec<u8>>) {
+	changes.inserted.extend(other.inserted);
+	changes.deleted.extend(other.deleted);
+}
+
+#[cfg(test)]
+mod tests {
+	use std::io;
+	use primitives::H256;
+	use crate::{StateDb, CommitSet};
+	use crate::test::{make_db, make_changeset, TestDb};
+	use super::*;
+
+	fn h(n: u64) -> H256 {
+		H256::from_low_u64_be(n)
+	}
+
+	// blocks 1 and 2, with 2 and its sibling 22 left non-canonical.
+	fn make_test_db(mode: PruningMode) -> (TestDb, StateDb<H256, H256>) {
+		let mut db = make_db(&[91]);
+		let state_db = StateDb::new(mode, &db).unwrap();
+		db.commit(&state_db.insert_block::<io::Error>(&h(1), 1, &h(0), make_changeset(&[1], &[91])).unwrap());
+		db.commit(&state_db.insert_block::<io::Error>(&h(2), 2, &h(1), make_changeset(&[2], &[1])).unwrap());
+		db.commit(&state_db.insert_block::<io::Error>(&h(22), 2, &h(1), make_changeset(&[22], &[1])).unwrap());
+		state_db.apply_pending();
+		db.commit(&state_db.canonicalize_block::<io::Error>(&h(1)).unwrap());
+		state_db.apply_pending();
+		(db, state_db)
+	}
+
+	fn commit_meta(db: &mut TestDb, meta: ChangeSet<Vec<u8>>) {
+		db.commit(&CommitSet { data: Default::default(), meta });
+	}
+
+	#[test]
+	fn opening_with_another_mode_fails() {
+		let (db, _) = make_test_db(PruningMode::ArchiveAll);
+		assert_eq!(stored_pruning_mode(&db).unwrap(), Some(PruningMode::ArchiveAll));
+		assert!(StateDb::<H256, H256>::new(PruningMode::keep_blocks(10), &db).is_err());
+		assert!(StateDb::<H256, H256>::new(PruningMode::ArchiveAll, &db).is_ok());
+	}
+
+	#[test]
+	fn archive_all_to_constrained() {
+		let (mut db, _) = make_test_db(PruningMode::ArchiveAll);
+		let unfinalized = vec![(h(2), 2, h(1)), (h(22), 2, h(1))];
+		let changes = change_pruning_mode::<H256, H256, _>(&db, &PruningMode::keep_blocks(0), (h(1), 1), &unfinalized)
+			.unwrap();
+		commit_meta(&mut db, changes);
+		// the caller removes the unreachable nodes.
+		db.data.remove(&h(91));
+
+		let state_db = StateDb::<H256, H256>::new(PruningMode::keep_blocks(0), &db).unwrap();
+		assert!(state_db.pin(&h(1), 1).is_ok());
+		assert!(state_db.pin(&h(22), 2).is_ok());
+		assert!(state_db.is_pruned(&h(0), 0));
+		state_db.unpin(&h(1));
+		state_db.unpin(&h(22));
+
+		db.commit(&state_db.insert_block::<io::Error>(&h(3), 3, &h(2), make_changeset(&[3], &[2])).unwrap());
+		state_db.apply_pending();
+		db.commit(&state_db.canonicalize_block::<io::Error>(&h(2)).unwrap());
+		state_db.apply_pending();
+		db.commit(&state_db.canonicalize_block::<io::Error>(&h(3)).unwrap());
+		state_db.apply_pending();
+		assert_eq!(state_db.best_canonical(), Some(3));
+		// nodes replaced or discarded by the blocks journaled without changes are kept.
+		assert!(db.data_eq(&make_db(&[1, 22, 3])));
+	}
+
+	#[test]
+	fn constrained_to_archive_canonical() {
+		let (mut db, _) = make_test_db(PruningMode::keep_blocks(10));
+		let changes = change_pruning_mode::<H256, H256, _>(&db, &PruningMode::ArchiveCanonical, (h(1), 1), &[])
+			.unwrap();
+		commit_meta(&mut db, changes);
+
+		let state_db = StateDb::<H256, H256>::new(PruningMode::ArchiveCanonical, &db).unwrap();
+		db.commit(&state_db.canonicalize_block::<io::Error>(&h(2)).unwrap());
+		state_db.apply_pending();
+		assert!(db.data_eq(&make_db(&[1, 2, 91])));
+		assert!(!db.meta.keys().any(|key| key.ends_with(b"pruning_journal") || key.ends_with(b"last_pruned")));
+		assert!(state_db.pin(&h(1), 1).is_ok());
+	}
+
+	#[test]
+	fn pruned_state_stays_pruned_in_archive_canonical() {
+		// blocks 0 to 2 with hashes 10 to 12, all canonical, with the state of block 2 kept.
+		let mut db = make_db(&[]);
+		let state_db = StateDb::<H256, H256>::new(PruningMode::keep_blocks(1), &db).unwrap();
+		let mut parent = H256::default();
+		for n in 0..3u64 {
+			let changeset = make_changeset(&[n + 1], if n > 0 { &[n][..] } else { &[] });
+			db.commit(&state_db.insert_block::<io::Error>(&h(10 + n), n, &parent, changeset).unwrap());
+			state_db.apply_pending();
+			db.commit(&state_db.canonicalize_block::<io::Error>(&h(10 + n)).unwrap());
+			state_db.apply_pending();
+			parent = h(10 + n);
+		}
+		assert!(state_db.is_pruned(&h(11), 1));
+
+		let changes = change_pruning_mode::<H256, H256, _>(&db, &PruningMode::ArchiveCanonical, (h(12), 2), &[])
+			.unwrap();
+		commit_meta(&mut db, changes);
+
+		let state_db = StateDb::<H256, H256>::new(PruningMode::ArchiveCanonical, &db).unwrap();
+		assert!(state_db.is_pruned(&h(11), 1));
+		assert!(state_db.pin(&h(11), 1).is_err());
+		assert!(!state_db.is_pruned(&h(12), 2));
+		assert!(state_db.pin(&h(12), 2).is_ok());
+	}
+
+	#[test]
+	fn legacy_databases_have_their_mode_inferred() {
+		let forget_mode = |db: &mut TestDb| { db.meta.remove(&to_meta_key(PRUNING_MODE, &())); };
+
+		let (mut db, _) = make_test_db(PruningMode::keep_blocks(10));
+		forget_mode(&mut db);
+		assert_eq!(stored_pruning_mode(&db).unwrap(), Some(PruningMode::Constrained(Constraints::default())));
+		assert!(StateDb::<H256, H256>::new(PruningMode::ArchiveCanonical, &db).is_err());
+		let changes = change_pruning_mode::<H256, H256, _>(&db, &PruningMode::ArchiveCanonical, (h(1), 1), &[])
+			.unwrap();
+		commit_meta(&mut db, changes);
+		assert_eq!(stored_pruning_mode(&db).unwrap(), Some(PruningMode::ArchiveCanonical));
+
+		let (mut db, _) = make_test_db(PruningMode::ArchiveCanonical);
+		forget_mode(&mut db);
+		assert_eq!(stored_pruning_mode(&db).unwrap(), Some(PruningMode::ArchiveCanonical));
+		assert!(StateDb::<H256, H256>::new(PruningMode::keep_blocks(10), &db).is_err());
+
+		let (mut db, _) = make_test_db(PruningMode::ArchiveAll);
+		forget_mode(&mut db);
+		assert_eq!(stored_pruning_mode(&db).unwrap(), None);
+		let changes = change_pruning_mode::<H256, H256, _>(&db, &PruningMode::ArchiveCanonical, (h(1), 1), &[])
+			.unwrap();
+		commit_meta(&mut db, changes);
+		assert_eq!(stored_pruning_mode(&db).unwrap(), Some(PruningMode::ArchiveCanonical));
+	}
+
+	#[test]
+	fn unsupported_changes_fail() {
+		let (db, _) = make_test_db(PruningMode::keep_blocks(10));
+		assert!(change_pruning_mode::<H256, H256, _>(&db, &PruningMode::ArchiveAll, (h(1), 1), &[]).is_err());
+		assert!(change_pruning_mode::<H256, H256, _>(&db, &PruningMode::keep_blocks(5), (h(1), 1), &[]).is_err());
+		assert!(change_pruning_mode::<H256, H256, _>(&make_db(&[]), &PruningMode::ArchiveAll, (h(1), 1), &[]).is_err());
+	}
+}
@@ -27,7 +27,7 @@ use codec::{Encode, Decode};
 use log::trace;
 
 const NON_CANONICAL_JOURNAL: &[u8] = b"noncanonical_journal";
-const LAST_CANONICAL: &[u8] = b"last_canonical";
+pub(crate) const LAST_CANONICAL: &[u8] = b"last_canonical";
 
 /// See module documentation.
 pub struct NonCanonicalOverlay<BlockHash: Hash, Key: Hash> {
@@ -52,6 +52,39 @@ fn to_journal_key(block: u64, index: u64) -> Vec<u8> {
 	to_meta_key(NON_CANONICAL_JOURNAL, &(block, index))
 }
 
+/// Read the last canonicalized block from the database.
+pub fn last_canonicalized<BlockHash: Hash, D: MetaDb>(db: &D) -> Result<Option<(BlockHash, u64)>, Error<D::Error>> {
+	match db.get_meta(&to_meta_key(LAST_CANONICAL, &())).map_err(|e| Error::Db(e))? {
+		Some(buffer) => Ok(Some(<(BlockHash, u64)>::decode(&mut buffer.as_slice())?)),
+		None => Ok(None),
+	}
+}
+
+/// Journal the given blocks as non-canonical blocks on top of `canonical`, without any changes.
+/// Used when the state of the blocks is already in the database.
+///
+/// `blocks` are `(hash, number, parent_hash)` sorted by number, each a descendant of `canonical`.
+pub(crate) fn journal_blocks<BlockHash: Hash, Key: Hash>(
+	canonical: &(BlockHash, u64),
+	blocks: &[(BlockHash, u64, BlockHash)],
+) -> ChangeSet<Vec<u8>> {
+	let mut changes = ChangeSet::default();
+	changes.inserted.push((to_meta_key(LAST_CANONICAL, &()), canonical.encode()));
+	let mut level_sizes: HashMap<u64, u64> = HashMap::new();
+	for (hash, number, parent_hash) in blocks {
+		let index = level_sizes.entry(*number).or_default();
+		let journal_record = JournalRecord::<BlockHash, Key> {
+			hash: hash.clone(),
+			parent_hash: parent_hash.clone(),
+			inserted: Vec::new(),
+			deleted: Vec::new(),
+		};
+		changes.inserted.push((to_journal_key(*number, *index), journal_record.encode()));
+		*index += 1;
+	}
+	changes
+}
+
 #[cfg_attr(test, derive(PartialEq, Debug))]
 struct BlockOverlay<BlockHash: Hash, Key: Hash> {
 	hash: BlockHash,
@@ -122,12 +155,7 @@ fn discard_descendants<BlockHash: Hash, Key: Hash>(
 impl<BlockHash: Hash, Key: Hash> NonCanonicalOverlay<BlockHash, Key> {
 	/// Creates a new instance. Does not expect any metadata to be present in the DB.
 	pub fn new<D: MetaDb>(db: &D) -> Result<NonCanonicalOverlay<BlockHash, Key>, Error<D::Error>> {
-		let last_canonicalized = db.get_meta(&to_meta_key(LAST_CANONICAL, &()))
-			.map_err(|e| Error::Db(e))?;
-		let last_canonicalized = match last_canonicalized {
-			Some(buffer) => Some(<(BlockHash, u64)>::decode(&mut buffer.as_slice())?),
-			None => None,
-		};
+		let last_canonicalized = last_canonicalized(db)?;
 		let mut levels = VecDeque::new();
 		let mut parents = HashMap::new();
 		let mut values = HashMap::new();
@@ -24,7 +24,7 @@
 
 use std::collections::{HashMap, HashSet, VecDeque};
 use codec::{Encode, Decode};
-use crate::{CommitSet, Error, MetaDb, to_meta_key, Hash};
+use crate::{ChangeSet, CommitSet, Error, MetaDb, to_meta_key, Hash};
 use log::{trace, warn};
 
 const LAST_PRUNED: &[u8] = b"last_pruned";
@@ -64,14 +64,57 @@ fn to_journal_key(block: u64) -> Vec<u8> {
 	to_meta_key(PRUNING_JOURNAL, &block)
 }
 
+/// Number of the first block in the pruning window, which is the oldest block with state.
+pub(crate) fn read_pending_number<D: MetaDb>(db: &D) -> Result<u64, Error<D::Error>> {
+	let last_pruned = db.get_meta(&to_meta_key(LAST_PRUNED, &()))
+		.map_err(|e| Error::Db(e))?;
+	Ok(match last_pruned {
+		Some(buffer) => u64::decode(&mut buffer.as_slice())? + 1,
+		None => 0,
+	})
+}
+
+/// Whether the database has a pruning window, which only `Constrained` databases keep.
+pub(crate) fn has_window<D: MetaDb>(db: &D) -> Result<bool, Error<D::Error>> {
+	let last_pruned = db.get_meta(&to_meta_key(LAST_PRUNED, &())).map_err(|e| Error::Db(e))?;
+	let journal = db.get_meta(&to_journal_key(read_pending_number(db)?)).map_err(|e| Error::Db(e))?;
+	Ok(last_pruned.is_some() || journal.is_some())
+}
+
+/// Start a pruning window that holds only the given block, without any changes to prune.
+pub(crate) fn start_window<BlockHash: Hash, Key: Hash>(hash: &BlockHash, number: u64) -> ChangeSet<Vec<u8>> {
+	let mut changes = ChangeSet::default();
+	if number > 0 {
+		changes.inserted.push((to_meta_key(LAST_PRUNED, &()), (number - 1).encode()));
+	}
+	let journal_record = JournalRecord::<BlockHash, Key> {
+		hash: hash.clone(),
+		inserted: Vec::new(),
+		deleted: Vec::new(),
+	};
+	changes.inserted.push((to_journal_key(number), journal_record.encode()));
+	changes
+}
+
+/// Remove the pruning window from the database. Nodes it would have pruned are kept.
+pub(crate) fn remove_window<D: MetaDb>(db: &D) -> Result<ChangeSet<Vec<u8>>, Error<D::Error>> {
+	let mut changes = ChangeSet::default();
+	changes.deleted.push(to_meta_key(LAST_PRUNED, &()));
+	let mut block = read_pending_number(db)?;
+	loop {
+		let journal_key = to_journal_key(block);
+		if db.get_meta(&journal_key).map_err(|e| Error::Db(e))?.is_none() {
+			break;
+		}
+		changes.deleted.push(journal_key);
+		block += 1;
+	}
+	Ok(changes)
+}
+
 impl<BlockHash: Hash, Key: Hash> RefWindow<BlockHash, Key> {
 	pub fn new<D: MetaDb>(db: &D) -> Result<RefWindow<BlockHash, Key>, Error<D::Error>> {
-		let last_pruned = db.get_meta(&to_meta_key(LAST_PRUNED, &()))
-			.map_err(|e| Error::Db(e))?;
-		let pending_number: u64 = match last_pruned {
-			Some(buffer) => u64::decode(&mut buffer.as_slice())? + 1,
-			None => 0,
-		};
+		let pending_number = read_pending_number(db)?;
 		let mut block = pending_number;
 		let mut pruning = RefWindow {
 			death_rows: Default::default(),
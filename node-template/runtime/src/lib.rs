@@ -9,13 +9,14 @@
 include!(concat!(env!("OUT_DIR"), "/wasm_binary.rs"));
 
 use rstd::prelude::*;
-use primitives::{OpaqueMetadata, crypto::key_types};
+use primitives::{OpaqueMetadata, U256, crypto::key_types};
 use sr_primitives::{
 	ApplyResult, transaction_validity::TransactionValidity, generic, create_runtime_str,
 	impl_opaque_keys, AnySignature
 };
 use sr_primitives::traits::{NumberFor, BlakeTwo256, Block as BlockT, StaticLookup, Verify, ConvertInto};
 use sr_primitives::weights::Weight;
+use sr_primitives::Fixed64;
 use client::{
 	block_builder::api::{CheckInherentsResult, InherentData, self as block_builder_api},
 	runtime_api as client_api, impl_runtime_apis
@@ -31,6 +32,7 @@ use version::NativeVersion;
 #[cfg(any(feature = "std", test))]
 pub use sr_primitives::BuildStorage;
 pub use timestamp::Call as TimestampCall;
+pub use aura::INHERENT_IDENTIFIER as AURA_INHERENT_IDENTIFIER;
 pub use balances::Call as BalancesCall;
 pub use sr_primitives::{Permill, Perbill};
 pub use support::{StorageValue, construct_runtime, parameter_types, traits::Randomness};
@@ -95,8 +97,8 @@ pub const VERSION: RuntimeVersion = RuntimeVersion {
 	spec_name: create_runtime_str!("node-template"),
 	impl_name: create_runtime_str!("node-template"),
 	authoring_version: 3,
-	spec_version: 4,
-	impl_version: 4,
+	spec_version: 8,
+	impl_version: 8,
 	apis: RUNTIME_API_VERSIONS,
 };
 
@@ -191,10 +193,22 @@ parameter_types! {
 impl timestamp::Trait for Runtime {
 	/// A timestamp: milliseconds since the unix epoch.
 	type Moment = u64;
-	type OnTimestampSet = Aura;
+	type OnTimestampSet = (Aura, Difficulty);
 	type MinimumPeriod = MinimumPeriod;
 }
 
+parameter_types! {
+	/// Mined blocks are at least a slot apart, so aim at two.
+	pub const TargetBlockTime: u64 = 2 * MILLISECS_PER_BLOCK;
+	pub const DifficultyWindow: u32 = 60;
+}
+
+/// Adjusts the difficulty of the `pow` sealing of the node.
+impl difficulty::Trait for Runtime {
+	type TargetBlockTime = TargetBlockTime;
+	type Window = DifficultyWindow;
+}
+
 parameter_types! {
 	pub const ExistentialDeposit: u128 = 500;
 	pub const TransferFee: u128 = 0;
@@ -236,6 +250,50 @@ impl sudo::Trait for Runtime {
 	type Proposal = Call;
 }
 
+parameter_types! {
+	pub const DidByteDeposit: Balance = 1;
+	pub const LoanByteDeposit: Balance = 1;
+	pub const BlocksPerYear: BlockNumber = 365 * DAYS;
+	pub const InitialCollateralRatio: Fixed64 = Fixed64::from_rational(3, 2);
+	pub const MaintenanceRatio: Fixed64 = Fixed64::from_rational(6, 5);
+	pub const LiquidationBonus: Perbill = Perbill::from_percent(5);
+	pub const CollateralChecks: u32 = 32;
+	/// Room for hundreds of loan accruals (150_000 each) and default checks in a block.
+	pub const MaximumSchedulerWeight: Weight = 100_000_000;
+}
+
+impl scheduler::Trait for Runtime {
+	type Event = Event;
+	type Call = Call;
+	type MaximumWeight = MaximumSchedulerWeight;
+}
+
+impl did::Trait for Runtime {
+	type Event = Event;
+	type Origin = Origin;
+	type Proposal = Call;
+	type Currency = Balances;
+	type ByteDeposit = DidByteDeposit;
+}
+
+impl loan::Trait for Runtime {
+	type Event = Event;
+	type Currency = Balances;
+	type ByteDeposit = LoanByteDeposit;
+	type BlocksPerYear = BlocksPerYear;
+	/// The template chain has no other assets, so loans are collateralised with balances.
+	type AssetId = u32;
+	type Assets = loan::NativeCollateral<Balances>;
+	type PriceFeed = loan::NativeCollateral<Balances>;
+	type InitialCollateralRatio = InitialCollateralRatio;
+	type MaintenanceRatio = MaintenanceRatio;
+	type LiquidationBonus = LiquidationBonus;
+	type CollateralChecks = CollateralChecks;
+	type Identity = DID;
+	type Call = Call;
+	type Scheduler = Scheduler;
+}
+
 /// Used for the module template in `./template.rs`
 impl template::Trait for Runtime {
 	type Event = Event;
@@ -250,6 +308,7 @@ construct_runtime!(
 		System: system::{Module, Call, Storage, Config, Event},
 		Timestamp: timestamp::{Module, Call, Storage, Inherent},
 		Aura: aura::{Module, Config<T>, Inherent(Timestamp)},
+		Difficulty: difficulty::{Module, Storage, Config},
 		Grandpa: grandpa::{Module, Call, Storage, Config, Event},
 		Indices: indices::{default, Config<T>},
 		Balances: balances::{default, Error},
@@ -257,6 +316,9 @@ construct_runtime!(
 		Sudo: sudo,
 		// Used for the module template in `./template.rs`
 		TemplateModule: template::{Module, Call, Storage, Event<T>},
+		DID: did::{Module, Call, Storage, Event<T>, Origin, Config<T>},
+		Scheduler: scheduler::{Module, Call, Storage, Event<T>},
+		Loan: loan::{Module, Call, Storage, Event<T>, Config<T>},
 		RandomnessCollectiveFlip: randomness_collective_flip::{Module, Call, Storage},
 	}
 );
@@ -352,6 +414,18 @@ impl_runtime_apis! {
 		}
 	}
 
+	impl pow_primitives::DifficultyApi<Block, U256> for Runtime {
+		fn difficulty() -> U256 {
+			Difficulty::difficulty()
+		}
+	}
+
+	impl pow_primitives::TimestampApi<Block, u64> for Runtime {
+		fn timestamp() -> u64 {
+			Timestamp::now()
+		}
+	}
+
 	impl substrate_session::SessionKeys<Block> for Runtime {
 		fn generate_session_keys(seed: Option<Vec<u8>>) -> Vec<u8> {
 			let seed = seed.as_ref().map(|s| rstd::str::from_utf8(&s).expect("Seed is an utf8 string"));
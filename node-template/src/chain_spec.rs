@@ -1,7 +1,7 @@
 use primitives::{Pair, Public};
 use node_template_runtime::{
-	AccountId, AuraConfig, BalancesConfig, GenesisConfig, GrandpaConfig,
-	SudoConfig, IndicesConfig, SystemConfig, WASM_BINARY, 
+	AccountId, AuraConfig, BalancesConfig, DIDConfig, DifficultyConfig, GenesisConfig, GrandpaConfig,
+	LoanConfig, SudoConfig, IndicesConfig, SystemConfig, WASM_BINARY, 
 };
 use aura_primitives::sr25519::{AuthorityId as AuraId};
 use grandpa_primitives::{AuthorityId as GrandpaId};
@@ -126,8 +126,19 @@ fn testnet_genesis(initial_authorities: Vec<(AuraId, GrandpaId)>,
 		aura: Some(AuraConfig {
 			authorities: initial_authorities.iter().map(|x| (x.0.clone())).collect(),
 		}),
+		difficulty: Some(DifficultyConfig {
+			// about a second of mining on one CPU thread.
+			initial_difficulty: 1_000_000.into(),
+		}),
 		grandpa: Some(GrandpaConfig {
 			authorities: initial_authorities.iter().map(|x| (x.1.clone(), 1)).collect(),
 		}),
+		did: Some(DIDConfig {
+			ids: vec![],
+		}),
+		loan: Some(LoanConfig {
+			requests: vec![],
+			credit_history: vec![],
+		}),
 	}
 }
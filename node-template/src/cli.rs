@@ -3,11 +3,40 @@ use futures::{future, Future, sync::oneshot};
 use std::cell::RefCell;
 use tokio::runtime::Runtime;
 pub use substrate_cli::{VersionInfo, IntoExit, error};
-use substrate_cli::{informant, parse_and_prepare, ParseAndPrepare, NoCustom};
+use substrate_cli::{informant, parse_and_prepare, impl_augment_clap, ParseAndPrepare, NoCustom};
 use substrate_service::{AbstractService, Roles as ServiceRoles, Configuration};
 use aura_primitives::sr25519::{AuthorityPair as AuraPair};
 use crate::chain_spec;
+use crate::service::DevSealing;
+use node_template_runtime::opaque::Block;
 use log::info;
+use structopt::StructOpt;
+
+/// Extra parameters of the command which runs the node.
+#[derive(Debug, StructOpt, Clone)]
+pub struct RunParams {
+	/// Author blocks with instant or manual seal instead of aura and grandpa.
+	///
+	/// Instant seal authors a block for every new transaction, manual seal authors blocks
+	/// on the `engine_createBlock` RPC call. Only meant for a single development node.
+	#[structopt(
+		long = "sealing",
+		value_name = "SEALING",
+		possible_values = &["instant", "manual"],
+		case_insensitive = true
+	)]
+	pub sealing: Option<DevSealing>,
+
+	/// Mine blocks with proof of work instead of running aura and grandpa.
+	#[structopt(long = "pow", conflicts_with = "sealing")]
+	pub pow: bool,
+
+	/// The number of CPU threads to mine with.
+	#[structopt(long = "mining-threads", value_name = "COUNT", default_value = "1")]
+	pub mining_threads: usize,
+}
+
+impl_augment_clap!(RunParams);
 
 /// Parse command line arguments into service configuration.
 pub fn run<I, T, E>(args: I, exit: E, version: VersionInfo) -> error::Result<()> where
@@ -16,9 +45,9 @@ pub fn run<I, T, E>(args: I, exit: E, version: VersionInfo) -> error::Result<()>
 	E: IntoExit,
 {
 	type Config<T> = Configuration<(), T>;
-	match parse_and_prepare::<NoCustom, NoCustom, _>(&version, "substrate-node", args) {
+	match parse_and_prepare::<NoCustom, RunParams, _>(&version, "substrate-node", args) {
 		ParseAndPrepare::Run(cmd) => cmd.run(load_spec, exit,
-		|exit, _cli_args, _custom_args, config: Config<_>| {
+		|exit, _cli_args, custom_args, config: Config<_>| {
 			info!("{}", version.name);
 			info!("  version {}", config.full_version());
 			info!("  by {}, 2017, 2018", version.author);
@@ -26,13 +55,29 @@ pub fn run<I, T, E>(args: I, exit: E, version: VersionInfo) -> error::Result<()>
 			info!("Node name: {}", config.name);
 			info!("Roles: {:?}", config.roles);
 			let runtime = Runtime::new().map_err(|e| format!("{:?}", e))?;
-			match config.roles {
-				ServiceRoles::LIGHT => run_until_exit(
+			match (config.roles, custom_args.sealing) {
+				(ServiceRoles::LIGHT, Some(_)) => Err(error::Error::Input(
+					"Instant and manual seal need a full node".into()
+				)),
+				(ServiceRoles::LIGHT, None) if custom_args.pow => Err(error::Error::Input(
+					"Proof of work needs a full node".into()
+				)),
+				(ServiceRoles::LIGHT, None) => run_until_exit(
 					runtime,
 				 	service::new_light(config).map_err(|e| format!("{:?}", e))?,
 					exit
 				),
-				_ => run_until_exit(
+				(_, Some(sealing)) => run_until_exit(
+					runtime,
+					service::new_dev(config, sealing).map_err(|e| format!("{:?}", e))?,
+					exit
+				),
+				(_, None) if custom_args.pow => run_until_exit(
+					runtime,
+					service::new_pow(config, custom_args.mining_threads).map_err(|e| format!("{:?}", e))?,
+					exit
+				),
+				(_, None) => run_until_exit(
 					runtime,
 					service::new_full(config).map_err(|e| format!("{:?}", e))?,
 					exit
@@ -44,6 +89,11 @@ pub fn run<I, T, E>(args: I, exit: E, version: VersionInfo) -> error::Result<()>
 			Ok(new_full_start!(config).0), load_spec, exit),
 		ParseAndPrepare::ImportBlocks(cmd) => cmd.run_with_builder(|config: Config<_>|
 			Ok(new_full_start!(config).0), load_spec, exit),
+		ParseAndPrepare::ExportState(cmd) => cmd.run_with_builder(|config: Config<_>|
+			Ok(new_full_start!(config).0), load_spec),
+		ParseAndPrepare::ImportState(cmd) => cmd.run::<Block, _, _, _>(load_spec),
+		ParseAndPrepare::MigrateDb(cmd) => cmd.run(load_spec),
+		ParseAndPrepare::MigratePruning(cmd) => cmd.run::<Block, _, _, _>(load_spec),
 		ParseAndPrepare::PurgeChain(cmd) => cmd.run(load_spec),
 		ParseAndPrepare::RevertChain(cmd) => cmd.run_with_builder(|config: Config<_>|
 			Ok(new_full_start!(config).0), load_spec),
@@ -4,7 +4,11 @@ use std::sync::Arc;
 use std::time::Duration;
 use substrate_client::LongestChain;
 use futures::prelude::*;
-use node_template_runtime::{self, GenesisConfig, opaque::Block, RuntimeApi};
+use codec::Decode;
+use node_template_runtime::{
+	self, AURA_INHERENT_IDENTIFIER, Call, GenesisConfig, opaque::Block, RuntimeApi, TimestampCall,
+	UncheckedExtrinsic,
+};
 use substrate_service::{error::{Error as ServiceError}, AbstractService, Configuration, ServiceBuilder};
 use transaction_pool::{self, txpool::{Pool as TransactionPool}};
 use inherents::InherentDataProviders;
@@ -13,6 +17,9 @@ use substrate_executor::native_executor_instance;
 pub use substrate_executor::NativeExecutor;
 use aura_primitives::sr25519::{AuthorityPair as AuraPair};
 use grandpa::{self, FinalityProofProvider as GrandpaFinalityProofProvider};
+use futures03::{FutureExt, TryFutureExt, channel::mpsc};
+use manual_seal::{self, rpc::{ManualSeal, ManualSealApi}};
+use pow_hash::{Blake2, DriftTimestampProvider, HashAlgorithm};
 
 // Our native executor instance.
 native_executor_instance!(
@@ -168,6 +175,205 @@ pub fn new_full<C: Send + Default + 'static>(config: Configuration<C, GenesisCon
 	Ok(service)
 }
 
+/// The way a development node authors blocks instead of aura and grandpa.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DevSealing {
+	/// Author and finalize a block for every new transaction.
+	Instant,
+	/// Author and finalize blocks on the `engine_createBlock` and `engine_finalizeBlock` RPC calls.
+	Manual,
+}
+
+impl std::str::FromStr for DevSealing {
+	type Err = String;
+
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		match s.to_lowercase().as_str() {
+			"instant" => Ok(DevSealing::Instant),
+			"manual" => Ok(DevSealing::Manual),
+			other => Err(format!("Unknown sealing `{}`, expected `instant` or `manual`", other)),
+		}
+	}
+}
+
+/// Builds a new service for a full client of a single development node, which authors its
+/// blocks with manual or instant seal.
+pub fn new_dev<C: Send + Default + 'static>(config: Configuration<C, GenesisConfig>, sealing: DevSealing)
+	-> Result<impl AbstractService, ServiceError>
+{
+	let inherent_data_providers = InherentDataProviders::new();
+	let (commands_sink, commands_stream) = mpsc::channel(1024);
+
+	let service = ServiceBuilder::new_full::<Block, RuntimeApi, Executor>(config)?
+		.with_select_chain(|_config, backend| {
+			Ok(LongestChain::new(backend.clone()))
+		})?
+		.with_transaction_pool(|config, client|
+			Ok(TransactionPool::new(config, transaction_pool::FullChainApi::new(client)))
+		)?
+		.with_import_queue(|_config, client, _select_chain, _transaction_pool| {
+			Ok(manual_seal::import_queue::<Block>(Box::new(client)))
+		})?
+		.with_rpc_extensions(|_client, _pool| {
+			let mut io = jsonrpc_core::IoHandler::<substrate_service::RpcMetadata>::default();
+			// instant seal reads no commands, so it takes no `engine_*` calls.
+			if sealing == DevSealing::Manual {
+				io.extend_with(ManualSealApi::to_delegate(ManualSeal::new(commands_sink)));
+			}
+			io
+		})?
+		.with_network_protocol(|_| Ok(NodeProtocol::new()))?
+		.build()?;
+
+	let client = service.client();
+	let select_chain = service.select_chain()
+		.ok_or(ServiceError::SelectChainRequired)?;
+
+	// the runtime refuses two blocks in one aura slot, so step the timestamp a slot per block.
+	let slot_duration = aura::SlotDuration::get_or_compute(&*client)?;
+	inherent_data_providers
+		.register_provider(pow::TimestampProvider::new(
+			client.clone(),
+			select_chain.clone(),
+			slot_duration.get(),
+		))
+		.map_err(|e| format!("{:?}", e))?;
+
+	let proposer = basic_authorship::ProposerFactory {
+		client: service.client(),
+		transaction_pool: service.transaction_pool(),
+	};
+
+	let authorship = match sealing {
+		DevSealing::Instant => manual_seal::run_instant_seal(
+			Box::new(client.clone()),
+			proposer,
+			client,
+			service.transaction_pool(),
+			select_chain,
+			inherent_data_providers,
+		).boxed(),
+		DevSealing::Manual => manual_seal::run_manual_seal(
+			Box::new(client.clone()),
+			proposer,
+			client,
+			service.transaction_pool(),
+			commands_stream,
+			select_chain,
+			inherent_data_providers,
+		).boxed(),
+	};
+
+	let select = authorship.unit_error().compat().select(service.on_exit()).then(|_| Ok(()));
+
+	// the authorship task is considered essential, i.e. if it
+	// fails we take down the service with it.
+	service.spawn_essential_task(select);
+
+	Ok(service)
+}
+
+/// The number of nonces a mining thread tries before it checks for a new best block.
+const MINING_ROUNDS: u32 = 10_000;
+
+/// How far ahead of the system clock, in milliseconds, the timestamp of an imported mined
+/// block may be.
+const MAX_TIMESTAMP_DRIFT: u64 = 30 * 1000;
+
+/// Builds a new service for a full client which mines its blocks with Blake2 proof of work on
+/// `mining_threads` CPU threads, instead of running aura and grandpa.
+///
+/// The chain has no finality. The difficulty is adjusted by the `difficulty` module of the
+/// runtime.
+pub fn new_pow<C: Send + Default + 'static>(config: Configuration<C, GenesisConfig>, mining_threads: usize)
+	-> Result<impl AbstractService, ServiceError>
+{
+	let is_authority = config.roles.is_authority();
+
+	let service = ServiceBuilder::new_full::<Block, RuntimeApi, Executor>(config)?
+		.with_select_chain(|_config, backend| {
+			Ok(LongestChain::new(backend.clone()))
+		})?
+		.with_transaction_pool(|config, client|
+			Ok(TransactionPool::new(config, transaction_pool::FullChainApi::new(client)))
+		)?
+		.with_import_queue(|_config, client, select_chain, _transaction_pool| {
+			let inherent_data_providers = InherentDataProviders::new();
+			inherent_data_providers
+				.register_provider(DriftTimestampProvider::new(MAX_TIMESTAMP_DRIFT))
+				.map_err(|e| format!("{:?}", e))?;
+
+			// the runtime checks the timestamp of a block against its aura slot. Mined blocks
+			// have no aura seal, and are mined a slot apart, so their slot is the one of their
+			// timestamp.
+			let slot_duration = aura::SlotDuration::get_or_compute(&*client)?.get();
+			let aura_slot: pow::BlockInherentData<Block> = Box::new(move |block, inherent_data| {
+				let timestamp = block.extrinsics.iter()
+					.filter_map(|xt| UncheckedExtrinsic::decode(&mut &xt.0[..]).ok())
+					.find_map(|xt| match xt.function {
+						Call::Timestamp(TimestampCall::set(timestamp)) => Some(timestamp),
+						_ => None,
+					});
+
+				if let Some(timestamp) = timestamp {
+					inherent_data.replace_data(AURA_INHERENT_IDENTIFIER, &(timestamp / slot_duration));
+				}
+				Ok(())
+			});
+
+			pow::import_queue(
+				Box::new(client.clone()),
+				client.clone(),
+				HashAlgorithm::<Blake2, _>::new(client),
+				0,
+				select_chain,
+				inherent_data_providers,
+				Some(aura_slot),
+			).map_err(Into::into)
+		})?
+		.with_network_protocol(|_| Ok(NodeProtocol::new()))?
+		.build()?;
+
+	if is_authority {
+		let client = service.client();
+		let select_chain = service.select_chain()
+			.ok_or(ServiceError::SelectChainRequired)?;
+
+		// the runtime refuses two blocks in one aura slot, so mine a slot after the chain.
+		let slot_duration = aura::SlotDuration::get_or_compute(&*client)?;
+		let inherent_data_providers = InherentDataProviders::new();
+		inherent_data_providers
+			.register_provider(pow::TimestampProvider::new(
+				client.clone(),
+				select_chain.clone(),
+				slot_duration.get(),
+			))
+			.map_err(|e| format!("{:?}", e))?;
+
+		for _ in 0..mining_threads {
+			let proposer = basic_authorship::ProposerFactory {
+				client: client.clone(),
+				transaction_pool: service.transaction_pool(),
+			};
+
+			pow::start_mine(
+				Box::new(client.clone()),
+				client.clone(),
+				HashAlgorithm::<Blake2, _>::new(client.clone()),
+				proposer,
+				None,
+				MINING_ROUNDS,
+				service.network(),
+				Duration::from_secs(2),
+				Some(select_chain.clone()),
+				inherent_data_providers.clone(),
+			);
+		}
+	}
+
+	Ok(service)
+}
+
 /// Builds a new service for a light client.
 pub fn new_light<C: Send + Default + 'static>(config: Configuration<C, GenesisConfig>)
 	-> Result<impl AbstractService, ServiceError>
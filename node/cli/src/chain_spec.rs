@@ -21,24 +21,31 @@ use chain_spec::ChainSpecExtension;
 use grandpa_primitives::AuthorityId as GrandpaId;
 use hex_literal::hex;
 use im_online::sr25519::AuthorityId as ImOnlineId;
+use loan::Schedule;
 use node_runtime::constants::{currency::*, time::*};
 use node_runtime::Block;
 use node_runtime::{
 	AuthorityDiscoveryConfig, BabeConfig, BalancesConfig, ContractsConfig, CouncilConfig,
-	DemocracyConfig, ElectionsConfig, GrandpaConfig, ImOnlineConfig, IndicesConfig, SessionConfig,
-	SessionKeys, StakerStatus, StakingConfig, SudoConfig, SystemConfig, TechnicalCommitteeConfig,
-	WASM_BINARY,
+	DIDConfig, DemocracyConfig, ElectionsConfig, GenericAssetConfig, GrandpaConfig, ImOnlineConfig,
+	IndicesConfig, LoanConfig, SessionConfig, SessionKeys, StakerStatus, StakingConfig, SudoConfig,
+	SystemConfig, TechnicalCommitteeConfig, WASM_BINARY,
 };
 use primitives::{crypto::UncheckedInto, Pair, Public};
 use serde::{Deserialize, Serialize};
+use serde_json::json;
 use sr_primitives::Perbill;
-use substrate_service;
+use std::iter;
+use substrate_service::{self, Properties};
 use substrate_telemetry::TelemetryEndpoints;
 
-pub use node_primitives::{AccountId, Balance};
+pub use node_primitives::{AccountId, AssetId, Balance};
 pub use node_runtime::GenesisConfig;
 
 const STAGING_TELEMETRY_URL: &str = "wss://telemetry.polkadot.io/submit/";
+const STAGING_PROTOCOL_ID: &str = "vlt";
+
+/// Asset the endowed accounts hold for collateralising loans.
+const COLLATERAL_ASSET: AssetId = 1;
 
 /// Node `ChainSpec` extensions.
 ///
@@ -156,12 +163,78 @@ fn staging_testnet_config_genesis() -> GenesisConfig {
 
 	let endowed_accounts: Vec<AccountId> = vec![root_key.clone()];
 
-	testnet_genesis(initial_authorities, root_key, Some(endowed_accounts), false)
+	// the sudo key is the root issuer, and issues a DID to the controller of every validator
+	let root_did = b"did:vault:root".to_vec();
+	let ids = iter::once((root_did.clone(), vec![0], root_key.clone()))
+		.chain(initial_authorities.iter().enumerate().map(|(i, x)| {
+			(
+				format!("did:vault:validator:{}", i + 1).into_bytes(),
+				root_did.clone(),
+				x.1.clone(),
+			)
+		}))
+		.map(|(id, issuer_id, owner)| {
+			(
+				id,
+				issuer_id,
+				owner.as_ref().to_vec(),
+				root_key.clone(),
+				owner,
+			)
+		})
+		.collect();
+
+	// an open request of the sudo key to the first validator, so the market is not empty
+	let requests = vec![(
+		root_key.clone(),
+		initial_authorities[0].1.clone(),
+		1_000 * DOLLARS,
+		30 * DAYS,
+		Perbill::from_percent(10),
+		Schedule::Amortised,
+		COLLATERAL_ASSET,
+		2_000 * DOLLARS,
+		1 * DAYS,
+		7 * DAYS,
+		Perbill::from_percent(5),
+	)];
+
+	GenesisConfig {
+		did: Some(DIDConfig { ids }),
+		loan: Some(LoanConfig {
+			requests,
+			credit_history: vec![],
+		}),
+		..testnet_genesis(initial_authorities, root_key, Some(endowed_accounts), false)
+	}
+}
+
+fn staging_testnet_properties() -> Properties {
+	json!({
+		"ss58Format": 42,
+		"tokenDecimals": 18,
+		"tokenSymbol": "tVLT",
+	})
+	.as_object()
+	.expect("properties are a json object; qed")
+	.clone()
 }
 
-/// Staging testnet config.
+/// Vault staging testnet config.
+///
+/// `vaultRaw.json` is its raw form, regenerated with
+/// `substrate build-spec --chain vault-staging --raw > vaultRaw.json`.
 pub fn staging_testnet_config() -> ChainSpec {
-	let boot_nodes = vec![];
+	let boot_nodes = vec![
+		"/ip4/159.65.46.201/tcp/30333/p2p/QmPZQLf2p34S8FZ1wiQeeKDBcyWsDk2RBBFWRwhrRfEaZA"
+			.to_string(),
+		"/ip4/159.89.42.74/tcp/30333/p2p/QmXoPNoTHx5cpRTTUpj6GVxvKLU3x1DavNuMDrKB5C1cUk"
+			.to_string(),
+		"/ip4/178.128.74.185/tcp/30333/p2p/QmQuWVugCh8L4mXt6HjPPCiwfWszyF9dYKQuTDNa4sv3ED"
+			.to_string(),
+		"/ip4/178.128.74.213/tcp/30333/p2p/QmRpnUUnAMkUxKMUb3Zi5T487Ewf1LPMqTtJPPGUsMMXPw"
+			.to_string(),
+	];
 	ChainSpec::from_genesis(
 		"Vault Staging Testnet",
 		"staging_testnet",
@@ -171,8 +244,8 @@ pub fn staging_testnet_config() -> ChainSpec {
 			STAGING_TELEMETRY_URL.to_string(),
 			0,
 		)])),
-		None,
-		None,
+		Some(STAGING_PROTOCOL_ID),
+		Some(staging_testnet_properties()),
 		Default::default(),
 	)
 }
@@ -300,6 +373,19 @@ pub fn testnet_genesis(
 			authorities: vec![],
 		}),
 		membership_Instance1: Some(Default::default()),
+		generic_asset: Some(GenericAssetConfig {
+			assets: vec![COLLATERAL_ASSET],
+			initial_balance: ENDOWMENT,
+			endowed_accounts: endowed_accounts.clone(),
+			next_asset_id: COLLATERAL_ASSET + 1,
+			staking_asset_id: 0,
+			spending_asset_id: 0,
+		}),
+		did: Some(DIDConfig { ids: vec![] }),
+		loan: Some(LoanConfig {
+			requests: vec![],
+			credit_history: vec![],
+		}),
 	}
 }
 
@@ -357,6 +443,7 @@ pub(crate) mod tests {
 	use super::*;
 	use crate::service::new_full;
 	use service_test;
+	use sr_primitives::BuildStorage;
 	use substrate_service::Roles;
 
 	fn local_testnet_genesis_instant_single() -> GenesisConfig {
@@ -396,6 +483,11 @@ pub(crate) mod tests {
 		)
 	}
 
+	#[test]
+	fn test_staging_testnet_chain_spec() {
+		staging_testnet_config().build_storage().unwrap();
+	}
+
 	#[test]
 	#[ignore]
 	fn test_connectivity() {
@@ -45,7 +45,7 @@ pub enum ChainSpec {
 	LocalTestnet,
 	/// The Flaming Fir testnet.
 	FlamingFir,
-	/// Whatever the current runtime is with the "global testnet" defaults.
+	/// The Vault staging testnet, whose raw spec is `vaultRaw.json`.
 	StagingTestnet,
 }
 
@@ -137,7 +137,7 @@ impl ChainSpec {
 			"dev" => Some(ChainSpec::Development),
 			"local" => Some(ChainSpec::LocalTestnet),
 			"" | "fir" | "flaming-fir" => Some(ChainSpec::FlamingFir),
-			"staging" => Some(ChainSpec::StagingTestnet),
+			"staging" | "vault" | "vault-staging" => Some(ChainSpec::StagingTestnet),
 			_ => None,
 		}
 	}
@@ -187,6 +187,11 @@ pub fn run<I, T, E>(args: I, exit: E, version: cli::VersionInfo) -> error::Resul
 			Ok(new_full_start!(config).0), load_spec, exit),
 		ParseAndPrepare::ImportBlocks(cmd) => cmd.run_with_builder(|config: Config<_, _>|
 			Ok(new_full_start!(config).0), load_spec, exit),
+		ParseAndPrepare::ExportState(cmd) => cmd.run_with_builder(|config: Config<_, _>|
+			Ok(new_full_start!(config).0), load_spec),
+		ParseAndPrepare::ImportState(cmd) => cmd.run::<node_primitives::Block, _, _, _>(load_spec),
+		ParseAndPrepare::MigrateDb(cmd) => cmd.run(load_spec),
+		ParseAndPrepare::MigratePruning(cmd) => cmd.run::<node_primitives::Block, _, _, _>(load_spec),
 		ParseAndPrepare::PurgeChain(cmd) => cmd.run(load_spec),
 		ParseAndPrepare::RevertChain(cmd) => cmd.run_with_builder(|config: Config<_, _>|
 			Ok(new_full_start!(config).0), load_spec),
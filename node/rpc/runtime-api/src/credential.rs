@@ -0,0 +1,53 @@
+// Copyright 2019 Parity Technologies (UK) Ltd.
+// This file is part of Substrate.
+
+// Substrate is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Substrate is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Substrate.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Runtime API for checking credentials anchored in the `credential` module.
+
+use rstd::vec::Vec;
+use codec::{Encode, Decode, Codec};
+use primitives::H256;
+
+/// The status of a credential at a given block.
+#[derive(Eq, PartialEq, Copy, Clone, Encode, Decode)]
+#[cfg_attr(feature = "std", derive(Debug, serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "std", serde(rename_all = "camelCase"))]
+pub enum CredentialStatus {
+	/// The credential was never anchored.
+	Unknown,
+	/// The credential was anchored after the block.
+	NotIssued,
+	/// The credential is valid.
+	Valid,
+	/// The credential expired at or before the block.
+	Expired,
+	/// The credential was revoked at or before the block.
+	Revoked,
+}
+
+client::decl_runtime_apis! {
+	/// The API to check anchored credentials.
+	pub trait CredentialApi<BlockNumber> where
+		BlockNumber: Codec,
+	{
+		/// The status of `credential` at block `at`.
+		fn status(credential: H256, at: BlockNumber) -> CredentialStatus;
+
+		/// The revocation bitmap of the issuer DID `issuer`.
+		///
+		/// The bit of a credential is counted from the most significant bit of the first byte.
+		fn status_list(issuer: Vec<u8>) -> Vec<u8>;
+	}
+}
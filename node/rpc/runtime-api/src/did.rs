@@ -0,0 +1,47 @@
+// Copyright 2019 Parity Technologies (UK) Ltd.
+// This file is part of Substrate.
+
+// Substrate is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Substrate is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Substrate.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Runtime API for resolving identifiers of the `did` module.
+
+use rstd::vec::Vec;
+use codec::Codec;
+
+pub use did::{DidController, DidDocument, KeyPurpose, KeyType, VerificationMethod};
+
+client::decl_runtime_apis! {
+	/// The API to resolve DIDs.
+	pub trait DidApi<BlockNumber> where
+		BlockNumber: Codec,
+	{
+		/// Resolve the DID with the given method-specific identifier.
+		///
+		/// Returns `None` if the identifier was never registered.
+		fn resolve(id: Vec<u8>) -> Option<DidDocument>;
+
+		/// Check that `signature` over `message` was made at block `at` with a key
+		/// registered for `purpose` and valid at that block.
+		fn verify_signature(
+			id: Vec<u8>,
+			purpose: KeyPurpose,
+			message: Vec<u8>,
+			signature: Vec<u8>,
+			at: BlockNumber,
+		) -> bool;
+
+		/// The nonce which has to be signed to dispatch the next call as the DID.
+		fn nonce(id: Vec<u8>) -> u64;
+	}
+}
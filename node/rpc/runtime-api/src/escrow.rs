@@ -0,0 +1,78 @@
+// Copyright 2019 Parity Technologies (UK) Ltd.
+// This file is part of Substrate.
+
+// Substrate is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Substrate is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Substrate.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Runtime API for querying escrows of the `escrow` module.
+
+use rstd::vec::Vec;
+use codec::{Encode, Decode, Codec};
+use primitives::H256;
+
+/// Where an escrow stands.
+#[derive(Eq, PartialEq, Copy, Clone, Encode, Decode)]
+#[cfg_attr(feature = "std", derive(Debug, serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "std", serde(rename_all = "camelCase"))]
+pub enum EscrowStatus {
+	/// The payee has not accepted the escrow yet.
+	Proposed,
+	/// The payee accepted the escrow, and the funds go to it at the deadline.
+	Accepted,
+	/// A party disputed the escrow, and the funds wait for the arbiter.
+	Disputed,
+}
+
+impl Default for EscrowStatus {
+	fn default() -> Self {
+		EscrowStatus::Proposed
+	}
+}
+
+/// An escrow of the `escrow` module.
+#[derive(Eq, PartialEq, Clone, Encode, Decode)]
+#[cfg_attr(feature = "std", derive(Debug, serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "std", serde(rename_all = "camelCase"))]
+pub struct EscrowInfo<AccountId, Balance, BlockNumber> {
+	/// Identifier of the escrow.
+	pub id: H256,
+	/// The account whose funds are held.
+	pub payer: AccountId,
+	/// The account the funds are for.
+	pub payee: AccountId,
+	/// The account which resolves disputes, besides the arbiter origin of the runtime.
+	pub arbiter: Option<AccountId>,
+	/// Funds reserved in the account of the payer.
+	pub amount: Balance,
+	/// The block the funds go back to the payer at, if the payee has not accepted by then.
+	pub accept_by: BlockNumber,
+	/// The block the funds go to the payee at, if nobody disputes the escrow before.
+	pub deadline: BlockNumber,
+	/// Where the escrow stands.
+	pub status: EscrowStatus,
+}
+
+client::decl_runtime_apis! {
+	/// The API to query escrows of the `escrow` module.
+	pub trait EscrowApi<AccountId, Balance, BlockNumber> where
+		AccountId: Codec,
+		Balance: Codec,
+		BlockNumber: Codec,
+	{
+		/// The open escrows `who` pays, receives or arbitrates.
+		fn escrows_of(who: AccountId) -> Vec<EscrowInfo<AccountId, Balance, BlockNumber>>;
+
+		/// The escrow `escrow_id`, or `None` if it does not exist or is closed.
+		fn escrow(escrow_id: H256) -> Option<EscrowInfo<AccountId, Balance, BlockNumber>>;
+	}
+}
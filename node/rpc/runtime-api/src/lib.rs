@@ -0,0 +1,35 @@
+// Copyright 2019 Parity Technologies (UK) Ltd.
+// This file is part of Substrate.
+
+// Substrate is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Substrate is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Substrate.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Runtime API definitions required by node-specific RPC extensions.
+//!
+//! These APIs should be implemented by the node runtime so that the
+//! `node-rpc` crate can query the custom runtime modules without
+//! knowing their storage layout.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+pub mod credential;
+pub mod did;
+pub mod escrow;
+pub mod loan;
+pub mod pool;
+
+pub use credential::CredentialApi;
+pub use did::DidApi;
+pub use escrow::EscrowApi;
+pub use loan::LoanApi;
+pub use pool::PoolApi;
@@ -0,0 +1,52 @@
+// Copyright 2019 Parity Technologies (UK) Ltd.
+// This file is part of Substrate.
+
+// Substrate is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Substrate is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Substrate.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Runtime API for querying bonds of the `loan` module.
+
+use rstd::vec::Vec;
+use codec::Codec;
+use primitives::H256;
+
+pub use loan::{Ask, Bid, BondInfo, CreditRecord, CreditScore, OrderBook, ProjectedBond, Schedule};
+
+client::decl_runtime_apis! {
+	/// The API to query bonds of the `loan` module.
+	pub trait LoanApi<AccountId, Balance, BlockNumber, AssetId> where
+		AccountId: Codec,
+		Balance: Codec,
+		BlockNumber: Codec,
+		AssetId: Codec,
+	{
+		/// The open orders of `bond_id`, or `None` if the bond does not exist.
+		fn orders(bond_id: H256) -> Option<OrderBook<AccountId, Balance>>;
+
+		/// The bonds `borrower` borrows with, including requests which are not approved yet.
+		fn bonds_by_borrower(borrower: AccountId) -> Vec<BondInfo<AccountId, Balance, BlockNumber, AssetId>>;
+
+		/// The bonds `lender` holds or is asked to lend with.
+		fn bonds_by_lender(lender: AccountId) -> Vec<BondInfo<AccountId, Balance, BlockNumber, AssetId>>;
+
+		/// The bonds which are for sale.
+		fn bonds_for_sale() -> Vec<BondInfo<AccountId, Balance, BlockNumber, AssetId>>;
+
+		/// The bond `bond_id` with its outstanding debt at block `at`, or `None` if the bond
+		/// does not exist.
+		fn bond(bond_id: H256, at: BlockNumber) -> Option<ProjectedBond<AccountId, Balance, BlockNumber, AssetId>>;
+
+		/// The repayment history of the DID `id` with its credit score.
+		fn credit_score(id: Vec<u8>) -> CreditScore;
+	}
+}
@@ -0,0 +1,56 @@
+// Copyright 2019 Parity Technologies (UK) Ltd.
+// This file is part of Substrate.
+
+// Substrate is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Substrate is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Substrate.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Runtime API for querying lending pools of the `pool` module.
+
+use rstd::vec::Vec;
+use codec::{Encode, Decode, Codec};
+
+/// A lending pool with its current rates.
+///
+/// Rates and the utilisation are given in parts per billion, so `10_000_000` is 1%.
+#[derive(Eq, PartialEq, Clone, Encode, Decode)]
+#[cfg_attr(feature = "std", derive(Debug, serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "std", serde(rename_all = "camelCase"))]
+pub struct PoolInfo<Balance> {
+	/// Identifier of the pool.
+	pub id: u32,
+	/// Deposits which are not lent out.
+	pub cash: Balance,
+	/// Outstanding debt of the borrowers, with the interest accrued on it.
+	pub borrows: Balance,
+	/// Share of the deposits which is lent out.
+	pub utilisation: u32,
+	/// Annual rate borrowers pay at the current utilisation.
+	pub borrow_rate: u32,
+	/// Annual yield of borrowing, with interest compounded every accrual period.
+	pub borrow_apy: u64,
+	/// Annual yield of depositing, with interest compounded every accrual period.
+	pub supply_apy: u64,
+}
+
+client::decl_runtime_apis! {
+	/// The API to query lending pools.
+	pub trait PoolApi<Balance> where
+		Balance: Codec,
+	{
+		/// All lending pools.
+		fn pools() -> Vec<PoolInfo<Balance>>;
+
+		/// The pool `pool_id`, or `None` if it does not exist.
+		fn pool(pool_id: u32) -> Option<PoolInfo<Balance>>;
+	}
+}
@@ -0,0 +1,121 @@
+// Copyright 2019 Parity Technologies (UK) Ltd.
+// This file is part of Substrate.
+
+// Substrate is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Substrate is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Substrate.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Node-specific RPC methods for checking anchored verifiable credentials.
+
+use std::sync::Arc;
+
+use client::blockchain::HeaderBackend;
+use codec::Codec;
+use jsonrpc_core::{Error, ErrorCode, Result};
+use jsonrpc_derive::rpc;
+use primitives::{Bytes, H256};
+use sr_primitives::{
+	generic::BlockId,
+	traits::{Block as BlockT, ProvideRuntimeApi},
+};
+
+pub use node_rpc_runtime_api::credential::{CredentialStatus, CredentialApi as CredentialRuntimeApi};
+pub use self::gen_client::Client as CredentialClient;
+
+/// Credential RPC methods.
+#[rpc]
+pub trait CredentialApi<BlockHash, BlockNumber> {
+	/// The status of the credential with hash `credential` at block `block`.
+	#[rpc(name = "credential_status")]
+	fn status(&self, credential: H256, block: BlockNumber, at: Option<BlockHash>) -> Result<CredentialStatus>;
+
+	/// Whether the credential with hash `credential` is valid at block `block`.
+	#[rpc(name = "credential_isValid")]
+	fn is_valid(&self, credential: H256, block: BlockNumber, at: Option<BlockHash>) -> Result<bool>;
+
+	/// The revocation bitmap of the issuer DID `issuer`.
+	#[rpc(name = "credential_statusList")]
+	fn status_list(&self, issuer: String, at: Option<BlockHash>) -> Result<Bytes>;
+}
+
+/// An implementation of credential specific RPC methods.
+pub struct Credential<C, B> {
+	client: Arc<C>,
+	_marker: std::marker::PhantomData<B>,
+}
+
+impl<C, B> Credential<C, B> {
+	/// Create new `Credential` with the given reference to the client.
+	pub fn new(client: Arc<C>) -> Self {
+		Credential { client, _marker: Default::default() }
+	}
+}
+
+const RUNTIME_ERROR: i64 = 1;
+
+fn runtime_error(e: impl std::fmt::Debug) -> Error {
+	Error {
+		code: ErrorCode::ServerError(RUNTIME_ERROR),
+		message: "Unable to query the credential registry.".into(),
+		data: Some(format!("{:?}", e).into()),
+	}
+}
+
+impl<C, Block, BlockNumber> CredentialApi<<Block as BlockT>::Hash, BlockNumber> for Credential<C, Block>
+where
+	Block: BlockT,
+	C: Send + Sync + 'static,
+	C: ProvideRuntimeApi,
+	C: HeaderBackend<Block>,
+	C::Api: CredentialRuntimeApi<Block, BlockNumber>,
+	BlockNumber: Codec,
+{
+	fn status(
+		&self,
+		credential: H256,
+		block: BlockNumber,
+		at: Option<<Block as BlockT>::Hash>,
+	) -> Result<CredentialStatus> {
+		let api = self.client.runtime_api();
+		let at = BlockId::hash(at.unwrap_or_else(||
+			// If the block hash is not supplied assume the best block.
+			self.client.info().best_hash
+		));
+
+		api.status(&at, credential, block).map_err(runtime_error)
+	}
+
+	fn is_valid(
+		&self,
+		credential: H256,
+		block: BlockNumber,
+		at: Option<<Block as BlockT>::Hash>,
+	) -> Result<bool> {
+		self.status(credential, block, at).map(|status| status == CredentialStatus::Valid)
+	}
+
+	fn status_list(&self, issuer: String, at: Option<<Block as BlockT>::Hash>) -> Result<Bytes> {
+		let api = self.client.runtime_api();
+		let at = BlockId::hash(at.unwrap_or_else(||
+			// If the block hash is not supplied assume the best block.
+			self.client.info().best_hash
+		));
+
+		let issuer = crate::did::from_did(&issuer).map_err(|e| Error {
+			code: ErrorCode::InvalidParams,
+			message: e,
+			data: None,
+		})?;
+
+		api.status_list(&at, issuer).map(Into::into).map_err(runtime_error)
+	}
+}
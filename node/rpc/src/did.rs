@@ -0,0 +1,390 @@
+// Copyright 2019 Parity Technologies (UK) Ltd.
+// This file is part of Substrate.
+
+// Substrate is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Substrate is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Substrate.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Node-specific RPC methods for resolving DIDs into W3C DID documents.
+
+use std::sync::Arc;
+
+use serde::{Serialize, Deserialize};
+use client::blockchain::HeaderBackend;
+use jsonrpc_core::{Error, ErrorCode, Result};
+use jsonrpc_derive::rpc;
+use codec::Codec;
+use primitives::Bytes;
+use sr_primitives::{
+	generic::BlockId,
+	traits::{Block as BlockT, ProvideRuntimeApi},
+};
+
+pub use node_rpc_runtime_api::did::{
+	self as runtime_types, DidController, KeyPurpose, KeyType, DidApi as DidRuntimeApi,
+};
+pub use self::gen_client::Client as DidClient;
+
+/// The DID method name used by this chain.
+pub const DID_METHOD: &str = "did:vault:";
+
+const W3C_DID_CONTEXT: &str = "https://www.w3.org/ns/did/v1";
+
+/// The verification method type of a key in a DID document.
+pub fn verification_key_type(key_type: KeyType) -> &'static str {
+	match key_type {
+		KeyType::Ed25519 => "Ed25519VerificationKey2018",
+		KeyType::Sr25519 => "Sr25519VerificationKey2020",
+		KeyType::Ecdsa => "EcdsaSecp256k1VerificationKey2019",
+	}
+}
+
+/// A verification method entry of a DID document.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all="camelCase")]
+pub struct VerificationMethod {
+	/// Full identifier of the key, `<did>#<fragment>`.
+	pub id: String,
+	/// The verification key type.
+	#[serde(rename = "type")]
+	pub key_type: String,
+	/// The DID controlling the key.
+	pub controller: String,
+	/// Hex encoded public key.
+	pub public_key_hex: String,
+}
+
+/// A DID document as specified by W3C DID Core.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all="camelCase")]
+pub struct DidDocument {
+	/// The JSON-LD context.
+	#[serde(rename = "@context")]
+	pub context: String,
+	/// The DID.
+	pub id: String,
+	/// The DIDs controlling the DID.
+	pub controller: Vec<String>,
+	/// Hex encoded hashes of the accounts in the controller set of the DID.
+	#[serde(default, skip_serializing_if = "Vec::is_empty")]
+	pub controller_accounts: Vec<String>,
+	/// Keys of the DID which are currently valid.
+	pub verification_method: Vec<VerificationMethod>,
+	/// Keys which can authenticate as the DID subject.
+	pub authentication: Vec<String>,
+	/// Keys which can issue claims on behalf of the DID subject.
+	pub assertion_method: Vec<String>,
+	/// Keys which can establish encrypted channels with the DID subject.
+	pub key_agreement: Vec<String>,
+	/// Keys which can delegate capabilities of the DID subject.
+	pub capability_delegation: Vec<String>,
+	/// The DID of the direct issuer, if the DID is not a root issuer.
+	pub issuer: Option<String>,
+	/// DIDs of the issuers from the direct issuer up to the root issuer.
+	pub issuer_chain: Vec<String>,
+	/// Number of controllers needed to approve an update or the deactivation.
+	pub controller_threshold: u32,
+}
+
+/// Metadata about the resolved DID document.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all="camelCase")]
+pub struct DidDocumentMetadata {
+	/// Whether the DID is deactivated.
+	pub deactivated: bool,
+}
+
+/// The result of resolving a DID.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all="camelCase")]
+pub struct DidResolution {
+	/// The resolved document.
+	pub did_document: DidDocument,
+	/// Metadata about the document.
+	pub did_document_metadata: DidDocumentMetadata,
+}
+
+/// Render a method-specific identifier as a DID.
+///
+/// Identifiers that consist of DID-safe characters only are shown as they are,
+/// other identifiers are hex encoded with a `0x` prefix.
+pub fn to_did(id: &[u8]) -> String {
+	let is_safe = !id.is_empty() && !id.starts_with(b"0x") && id.iter()
+		.all(|c| c.is_ascii_alphanumeric() || *c == b'.' || *c == b'-' || *c == b'_');
+	if is_safe {
+		format!("{}{}", DID_METHOD, String::from_utf8_lossy(id))
+	} else {
+		format!("{}0x{}", DID_METHOD, hex::encode(id))
+	}
+}
+
+/// Parse a DID or a bare method-specific identifier into the identifier bytes.
+///
+/// This is the inverse of `to_did`.
+pub fn from_did(did: &str) -> std::result::Result<Vec<u8>, String> {
+	let id = if did.starts_with(DID_METHOD) {
+		&did[DID_METHOD.len()..]
+	} else if did.starts_with("did:") {
+		return Err(format!("Unsupported DID method: {}", did));
+	} else {
+		did
+	};
+	if id.starts_with("0x") {
+		hex::decode(&id[2..]).map_err(|e| format!("Invalid hex identifier: {}", e))
+	} else {
+		Ok(id.as_bytes().to_vec())
+	}
+}
+
+impl From<runtime_types::DidDocument> for DidResolution {
+	fn from(doc: runtime_types::DidDocument) -> Self {
+		let did = to_did(&doc.id);
+		// accounts are not DIDs, so they are listed apart from the controller DIDs.
+		let controller = doc.controllers.iter()
+			.filter_map(|controller| match controller {
+				DidController::Did(id) => Some(to_did(id)),
+				DidController::Account(_) => None,
+			})
+			.collect();
+		let controller_accounts = doc.controllers.iter()
+			.filter_map(|controller| match controller {
+				DidController::Account(account) => Some(format!("0x{}", hex::encode(account.as_bytes()))),
+				DidController::Did(_) => None,
+			})
+			.collect();
+		let key_id = |method: &runtime_types::VerificationMethod| format!("{}#key-{}", did, method.index + 1);
+		let verification_method: Vec<_> = doc.verification_methods.iter()
+			.map(|method| VerificationMethod {
+				id: key_id(method),
+				key_type: verification_key_type(method.key_type).into(),
+				controller: did.clone(),
+				public_key_hex: hex::encode(&method.public_key),
+			})
+			.collect();
+		let keys_for = |purpose: KeyPurpose| doc.verification_methods.iter()
+			.filter(|method| method.purpose == purpose)
+			.map(&key_id)
+			.collect::<Vec<_>>();
+		let authentication = keys_for(KeyPurpose::Authentication);
+		let assertion_method = keys_for(KeyPurpose::Assertion);
+		let key_agreement = keys_for(KeyPurpose::KeyAgreement);
+		let capability_delegation = keys_for(KeyPurpose::Delegation);
+		let issuer_chain: Vec<_> = doc.issuer_chain.iter().map(|id| to_did(id)).collect();
+
+		DidResolution {
+			did_document: DidDocument {
+				context: W3C_DID_CONTEXT.into(),
+				id: did,
+				controller,
+				controller_accounts,
+				verification_method,
+				authentication,
+				assertion_method,
+				key_agreement,
+				capability_delegation,
+				issuer: issuer_chain.first().cloned(),
+				issuer_chain,
+				controller_threshold: doc.threshold,
+			},
+			did_document_metadata: DidDocumentMetadata {
+				deactivated: doc.deactivated,
+			},
+		}
+	}
+}
+
+/// DID RPC methods.
+#[rpc]
+pub trait DidApi<BlockHash, BlockNumber> {
+	/// Resolve a `did:vault:<id>` identifier into a W3C DID document.
+	///
+	/// The identifier may also be given without the `did:vault:` prefix.
+	#[rpc(name = "did_resolve")]
+	fn resolve(&self, did: String, at: Option<BlockHash>) -> Result<DidResolution>;
+
+	/// Check that `signature` over `message` was made at block `signed_at` with a key
+	/// of `did` which was registered for `purpose` and valid at that block.
+	///
+	/// Keys which were rotated or revoked after `signed_at` are still accepted.
+	#[rpc(name = "did_verifySignature")]
+	fn verify_signature(
+		&self,
+		did: String,
+		purpose: KeyPurpose,
+		message: Bytes,
+		signature: Bytes,
+		signed_at: BlockNumber,
+		at: Option<BlockHash>,
+	) -> Result<bool>;
+
+	/// The nonce which has to be signed to dispatch the next call as `did`.
+	#[rpc(name = "did_nonce")]
+	fn nonce(&self, did: String, at: Option<BlockHash>) -> Result<u64>;
+}
+
+/// An implementation of DID specific RPC methods.
+pub struct Did<C, B> {
+	client: Arc<C>,
+	_marker: std::marker::PhantomData<B>,
+}
+
+impl<C, B> Did<C, B> {
+	/// Create new `Did` with the given reference to the client.
+	pub fn new(client: Arc<C>) -> Self {
+		Did { client, _marker: Default::default() }
+	}
+}
+
+const RUNTIME_ERROR: i64 = 1;
+const NOT_FOUND: i64 = 2;
+
+fn invalid_did(e: String) -> Error {
+	Error {
+		code: ErrorCode::InvalidParams,
+		message: e,
+		data: None,
+	}
+}
+
+impl<C, Block, BlockNumber> DidApi<<Block as BlockT>::Hash, BlockNumber> for Did<C, Block>
+where
+	Block: BlockT,
+	C: Send + Sync + 'static,
+	C: ProvideRuntimeApi,
+	C: HeaderBackend<Block>,
+	C::Api: DidRuntimeApi<Block, BlockNumber>,
+	BlockNumber: Codec,
+{
+	fn resolve(&self, did: String, at: Option<<Block as BlockT>::Hash>) -> Result<DidResolution> {
+		let api = self.client.runtime_api();
+		let at = BlockId::hash(at.unwrap_or_else(||
+			// If the block hash is not supplied assume the best block.
+			self.client.info().best_hash
+		));
+
+		let id = from_did(&did).map_err(invalid_did)?;
+
+		let document = api.resolve(&at, id).map_err(|e| Error {
+			code: ErrorCode::ServerError(RUNTIME_ERROR),
+			message: "Unable to resolve the DID.".into(),
+			data: Some(format!("{:?}", e).into()),
+		})?;
+
+		document.map(Into::into).ok_or_else(|| Error {
+			code: ErrorCode::ServerError(NOT_FOUND),
+			message: format!("{} is not registered.", did),
+			data: None,
+		})
+	}
+	fn verify_signature(
+		&self,
+		did: String,
+		purpose: KeyPurpose,
+		message: Bytes,
+		signature: Bytes,
+		signed_at: BlockNumber,
+		at: Option<<Block as BlockT>::Hash>,
+	) -> Result<bool> {
+		let api = self.client.runtime_api();
+		let at = BlockId::hash(at.unwrap_or_else(||
+			// If the block hash is not supplied assume the best block.
+			self.client.info().best_hash
+		));
+
+		let id = from_did(&did).map_err(invalid_did)?;
+
+		api.verify_signature(&at, id, purpose, message.to_vec(), signature.to_vec(), signed_at)
+			.map_err(|e| Error {
+				code: ErrorCode::ServerError(RUNTIME_ERROR),
+				message: "Unable to verify the signature.".into(),
+				data: Some(format!("{:?}", e).into()),
+			})
+	}
+
+	fn nonce(&self, did: String, at: Option<<Block as BlockT>::Hash>) -> Result<u64> {
+		let api = self.client.runtime_api();
+		let at = BlockId::hash(at.unwrap_or_else(||
+			// If the block hash is not supplied assume the best block.
+			self.client.info().best_hash
+		));
+
+		let id = from_did(&did).map_err(invalid_did)?;
+
+		api.nonce(&at, id).map_err(|e| Error {
+			code: ErrorCode::ServerError(RUNTIME_ERROR),
+			message: "Unable to query the DID nonce.".into(),
+			data: Some(format!("{:?}", e).into()),
+		})
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use primitives::H256;
+
+	#[test]
+	fn did_round_trips() {
+		assert_eq!(to_did(b"alice"), "did:vault:alice");
+		assert_eq!(from_did("did:vault:alice").unwrap(), b"alice".to_vec());
+		assert_eq!(to_did(&[0, 255]), "did:vault:0x00ff");
+		assert_eq!(from_did("did:vault:0x00ff").unwrap(), vec![0, 255]);
+		assert_eq!(from_did("bob").unwrap(), b"bob".to_vec());
+		assert!(from_did("did:web:example.com").is_err());
+	}
+
+	#[test]
+	fn document_is_serialized_as_w3c_did_document() {
+		let resolution: DidResolution = runtime_types::DidDocument {
+			id: b"alice".to_vec(),
+			verification_methods: vec![
+				runtime_types::VerificationMethod {
+					index: 0,
+					purpose: KeyPurpose::Authentication,
+					key_type: KeyType::Sr25519,
+					public_key: vec![1, 2],
+				},
+				runtime_types::VerificationMethod {
+					index: 2,
+					purpose: KeyPurpose::Assertion,
+					key_type: KeyType::Ed25519,
+					public_key: vec![3],
+				},
+			],
+			controllers: vec![
+				DidController::Account(H256::repeat_byte(1)),
+				DidController::Did(b"bob".to_vec()),
+			],
+			threshold: 2,
+			issuer: H256::repeat_byte(2),
+			issuer_chain: vec![b"issuer".to_vec(), b"root".to_vec()],
+			deactivated: false,
+		}.into();
+
+		let json = serde_json::to_value(&resolution).unwrap();
+		assert_eq!(json["didDocument"]["@context"], "https://www.w3.org/ns/did/v1");
+		assert_eq!(json["didDocument"]["id"], "did:vault:alice");
+		assert_eq!(json["didDocument"]["controller"], serde_json::json!(["did:vault:bob"]));
+		assert_eq!(json["didDocument"]["controllerAccounts"][0], format!("0x{}", "01".repeat(32)));
+		assert_eq!(json["didDocument"]["controllerThreshold"], 2);
+		assert_eq!(json["didDocument"]["verificationMethod"][0]["id"], "did:vault:alice#key-1");
+		assert_eq!(json["didDocument"]["verificationMethod"][0]["type"], "Sr25519VerificationKey2020");
+		assert_eq!(json["didDocument"]["verificationMethod"][0]["publicKeyHex"], "0102");
+		assert_eq!(json["didDocument"]["authentication"][0], "did:vault:alice#key-1");
+		assert_eq!(json["didDocument"]["verificationMethod"][1]["type"], "Ed25519VerificationKey2018");
+		assert_eq!(json["didDocument"]["assertionMethod"][0], "did:vault:alice#key-3");
+		assert_eq!(json["didDocument"]["keyAgreement"].as_array().unwrap().len(), 0);
+		assert_eq!(json["didDocument"]["issuer"], "did:vault:issuer");
+		assert_eq!(json["didDocument"]["issuerChain"][1], "did:vault:root");
+		assert_eq!(json["didDocumentMetadata"]["deactivated"], false);
+	}
+}
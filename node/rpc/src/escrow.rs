@@ -0,0 +1,123 @@
+// Copyright 2019 Parity Technologies (UK) Ltd.
+// This file is part of Substrate.
+
+// Substrate is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Substrate is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Substrate.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Node-specific RPC methods for querying escrows of the `escrow` module.
+
+use std::sync::Arc;
+
+use client::blockchain::HeaderBackend;
+use codec::Codec;
+use jsonrpc_core::{Error, ErrorCode, Result};
+use jsonrpc_derive::rpc;
+use primitives::H256;
+use sr_primitives::{
+	generic::BlockId,
+	traits::{Block as BlockT, ProvideRuntimeApi},
+};
+
+pub use node_rpc_runtime_api::escrow::{EscrowApi as EscrowRuntimeApi, EscrowInfo, EscrowStatus};
+pub use self::gen_client::Client as EscrowClient;
+
+/// Escrow RPC methods.
+#[rpc]
+pub trait EscrowApi<BlockHash, AccountId, Balance, BlockNumber> {
+	/// The open escrows `who` pays, receives or arbitrates.
+	#[rpc(name = "escrow_listByParty")]
+	fn escrows_of(
+		&self,
+		who: AccountId,
+		at: Option<BlockHash>,
+	) -> Result<Vec<EscrowInfo<AccountId, Balance, BlockNumber>>>;
+
+	/// The open escrow `escrow_id`.
+	#[rpc(name = "escrow_info")]
+	fn escrow(
+		&self,
+		escrow_id: H256,
+		at: Option<BlockHash>,
+	) -> Result<EscrowInfo<AccountId, Balance, BlockNumber>>;
+}
+
+/// An implementation of escrow specific RPC methods.
+pub struct Escrow<C, B> {
+	client: Arc<C>,
+	_marker: std::marker::PhantomData<B>,
+}
+
+impl<C, B> Escrow<C, B> {
+	/// Create new `Escrow` with the given reference to the client.
+	pub fn new(client: Arc<C>) -> Self {
+		Escrow { client, _marker: Default::default() }
+	}
+}
+
+const RUNTIME_ERROR: i64 = 1;
+const NOT_FOUND: i64 = 2;
+
+fn runtime_error(e: impl std::fmt::Debug) -> Error {
+	Error {
+		code: ErrorCode::ServerError(RUNTIME_ERROR),
+		message: "Unable to query the escrow module.".into(),
+		data: Some(format!("{:?}", e).into()),
+	}
+}
+
+impl<C, Block, AccountId, Balance, BlockNumber>
+	EscrowApi<<Block as BlockT>::Hash, AccountId, Balance, BlockNumber> for Escrow<C, Block>
+where
+	Block: BlockT,
+	C: Send + Sync + 'static,
+	C: ProvideRuntimeApi,
+	C: HeaderBackend<Block>,
+	C::Api: EscrowRuntimeApi<Block, AccountId, Balance, BlockNumber>,
+	AccountId: Codec,
+	Balance: Codec,
+	BlockNumber: Codec,
+{
+	fn escrows_of(
+		&self,
+		who: AccountId,
+		at: Option<<Block as BlockT>::Hash>,
+	) -> Result<Vec<EscrowInfo<AccountId, Balance, BlockNumber>>> {
+		let api = self.client.runtime_api();
+		let at = BlockId::hash(at.unwrap_or_else(||
+			// If the block hash is not supplied assume the best block.
+			self.client.info().best_hash
+		));
+
+		api.escrows_of(&at, who).map_err(runtime_error)
+	}
+
+	fn escrow(
+		&self,
+		escrow_id: H256,
+		at: Option<<Block as BlockT>::Hash>,
+	) -> Result<EscrowInfo<AccountId, Balance, BlockNumber>> {
+		let api = self.client.runtime_api();
+		let at = BlockId::hash(at.unwrap_or_else(||
+			// If the block hash is not supplied assume the best block.
+			self.client.info().best_hash
+		));
+
+		api.escrow(&at, escrow_id)
+			.map_err(runtime_error)?
+			.ok_or_else(|| Error {
+				code: ErrorCode::ServerError(NOT_FOUND),
+				message: format!("Escrow {:?} does not exist.", escrow_id),
+				data: None,
+			})
+	}
+}
@@ -31,10 +31,21 @@
 
 use std::sync::Arc;
 
-use node_primitives::{Block, AccountId, Index, Balance};
+use node_primitives::{Block, BlockNumber, AccountId, AssetId, Index, Balance};
 use sr_primitives::traits::ProvideRuntimeApi;
 use transaction_pool::txpool::{ChainApi, Pool};
 
+/// Status queries for credentials anchored by DIDs.
+pub mod credential;
+/// Resolution of `did` module identifiers into W3C DID documents.
+pub mod did;
+/// Escrows of the `escrow` module by party.
+pub mod escrow;
+/// Queries of bonds and their order book in the `loan` module.
+pub mod loan;
+/// Rates and yields of the lending pools of the `pool` module.
+pub mod pool;
+
 /// Instantiate all RPC extensions.
 pub fn create<C, P, M>(client: Arc<C>, pool: Arc<Pool<P>>) -> jsonrpc_core::IoHandler<M> where
 	C: ProvideRuntimeApi,
@@ -42,18 +53,43 @@ pub fn create<C, P, M>(client: Arc<C>, pool: Arc<Pool<P>>) -> jsonrpc_core::IoHa
 	C: Send + Sync + 'static,
 	C::Api: srml_system_rpc::AccountNonceApi<Block, AccountId, Index>,
 	C::Api: srml_contracts_rpc::ContractsRuntimeApi<Block, AccountId, Balance>,
+	C::Api: did::DidRuntimeApi<Block, BlockNumber>,
+	C::Api: credential::CredentialRuntimeApi<Block, BlockNumber>,
+	C::Api: escrow::EscrowRuntimeApi<Block, AccountId, Balance, BlockNumber>,
+	C::Api: loan::LoanRuntimeApi<Block, AccountId, Balance, BlockNumber, AssetId>,
+	C::Api: pool::PoolRuntimeApi<Block, Balance>,
 	P: ChainApi + Sync + Send + 'static,
 	M: jsonrpc_core::Metadata + Default,
 {
 	use srml_system_rpc::{System, SystemApi};
 	use srml_contracts_rpc::{Contracts, ContractsApi};
+	use did::{Did, DidApi};
+	use credential::{Credential, CredentialApi};
+	use escrow::{Escrow, EscrowApi};
+	use loan::{Loan, LoanApi};
+	use pool::{Pools, PoolApi};
 
 	let mut io = jsonrpc_core::IoHandler::default();
 	io.extend_with(
 		SystemApi::to_delegate(System::new(client.clone(), pool))
 	);
 	io.extend_with(
-		ContractsApi::to_delegate(Contracts::new(client))
+		ContractsApi::to_delegate(Contracts::new(client.clone()))
+	);
+	io.extend_with(
+		DidApi::to_delegate(Did::new(client.clone()))
+	);
+	io.extend_with(
+		CredentialApi::to_delegate(Credential::new(client.clone()))
+	);
+	io.extend_with(
+		EscrowApi::to_delegate(Escrow::new(client.clone()))
+	);
+	io.extend_with(
+		LoanApi::to_delegate(Loan::new(client.clone()))
+	);
+	io.extend_with(
+		PoolApi::to_delegate(Pools::new(client))
 	);
 	io
 }
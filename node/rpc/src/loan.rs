@@ -0,0 +1,205 @@
+// Copyright 2019 Parity Technologies (UK) Ltd.
+// This file is part of Substrate.
+
+// Substrate is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Substrate is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Substrate.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Node-specific RPC methods for querying bonds of the `loan` module.
+
+use std::sync::Arc;
+
+use client::blockchain::HeaderBackend;
+use codec::Codec;
+use jsonrpc_core::{Error, ErrorCode, Result};
+use jsonrpc_derive::rpc;
+use primitives::H256;
+use sr_primitives::{
+	generic::BlockId,
+	traits::{Block as BlockT, ProvideRuntimeApi},
+};
+
+use crate::did::from_did;
+
+pub use node_rpc_runtime_api::loan::{
+	Ask, Bid, BondInfo, CreditRecord, CreditScore, LoanApi as LoanRuntimeApi, OrderBook,
+	ProjectedBond, Schedule,
+};
+pub use self::gen_client::Client as LoanClient;
+
+/// Loan RPC methods.
+#[rpc]
+pub trait LoanApi<BlockHash, AccountId, Balance, BlockNumber, AssetId> {
+	/// The open asks and bids of the bond `bond_id`.
+	#[rpc(name = "loan_orders")]
+	fn orders(&self, bond_id: H256, at: Option<BlockHash>) -> Result<OrderBook<AccountId, Balance>>;
+
+	/// The bonds `borrower` borrows with, including requests which are not approved yet.
+	#[rpc(name = "loan_bondsByBorrower")]
+	fn bonds_by_borrower(
+		&self,
+		borrower: AccountId,
+		at: Option<BlockHash>,
+	) -> Result<Vec<BondInfo<AccountId, Balance, BlockNumber, AssetId>>>;
+
+	/// The bonds `lender` holds or is asked to lend with.
+	#[rpc(name = "loan_bondsByLender")]
+	fn bonds_by_lender(
+		&self,
+		lender: AccountId,
+		at: Option<BlockHash>,
+	) -> Result<Vec<BondInfo<AccountId, Balance, BlockNumber, AssetId>>>;
+
+	/// The bonds which are for sale.
+	#[rpc(name = "loan_bondsForSale")]
+	fn bonds_for_sale(
+		&self,
+		at: Option<BlockHash>,
+	) -> Result<Vec<BondInfo<AccountId, Balance, BlockNumber, AssetId>>>;
+
+	/// The bond `bond_id` with the debt it will have at block `projected_at` if nothing
+	/// is paid back before.
+	#[rpc(name = "loan_bond")]
+	fn bond(
+		&self,
+		bond_id: H256,
+		projected_at: BlockNumber,
+		at: Option<BlockHash>,
+	) -> Result<ProjectedBond<AccountId, Balance, BlockNumber, AssetId>>;
+
+	/// The repayment history of `did` with its credit score.
+	///
+	/// The identifier may also be given without the `did:vault:` prefix.
+	#[rpc(name = "loan_creditScore")]
+	fn credit_score(&self, did: String, at: Option<BlockHash>) -> Result<CreditScore>;
+}
+
+/// An implementation of loan specific RPC methods.
+pub struct Loan<C, B> {
+	client: Arc<C>,
+	_marker: std::marker::PhantomData<B>,
+}
+
+impl<C, B> Loan<C, B> {
+	/// Create new `Loan` with the given reference to the client.
+	pub fn new(client: Arc<C>) -> Self {
+		Loan { client, _marker: Default::default() }
+	}
+}
+
+const RUNTIME_ERROR: i64 = 1;
+const NOT_FOUND: i64 = 2;
+
+fn runtime_error(e: impl std::fmt::Debug) -> Error {
+	Error {
+		code: ErrorCode::ServerError(RUNTIME_ERROR),
+		message: "Unable to query the loan module.".into(),
+		data: Some(format!("{:?}", e).into()),
+	}
+}
+
+fn invalid_did(e: String) -> Error {
+	Error {
+		code: ErrorCode::InvalidParams,
+		message: e,
+		data: None,
+	}
+}
+
+fn not_found(bond_id: H256) -> Error {
+	Error {
+		code: ErrorCode::ServerError(NOT_FOUND),
+		message: format!("Bond {:?} does not exist.", bond_id),
+		data: None,
+	}
+}
+
+impl<C, Block> Loan<C, Block> where
+	Block: BlockT,
+	C: HeaderBackend<Block>,
+{
+	fn block_id(&self, at: Option<<Block as BlockT>::Hash>) -> BlockId<Block> {
+		BlockId::hash(at.unwrap_or_else(||
+			// If the block hash is not supplied assume the best block.
+			self.client.info().best_hash
+		))
+	}
+}
+
+impl<C, Block, AccountId, Balance, BlockNumber, AssetId>
+	LoanApi<<Block as BlockT>::Hash, AccountId, Balance, BlockNumber, AssetId> for Loan<C, Block>
+where
+	Block: BlockT,
+	C: Send + Sync + 'static,
+	C: ProvideRuntimeApi,
+	C: HeaderBackend<Block>,
+	C::Api: LoanRuntimeApi<Block, AccountId, Balance, BlockNumber, AssetId>,
+	AccountId: Codec,
+	Balance: Codec,
+	BlockNumber: Codec,
+	AssetId: Codec,
+{
+	fn orders(
+		&self,
+		bond_id: H256,
+		at: Option<<Block as BlockT>::Hash>,
+	) -> Result<OrderBook<AccountId, Balance>> {
+		let at = self.block_id(at);
+		self.client.runtime_api().orders(&at, bond_id)
+			.map_err(runtime_error)?
+			.ok_or_else(|| not_found(bond_id))
+	}
+
+	fn bonds_by_borrower(
+		&self,
+		borrower: AccountId,
+		at: Option<<Block as BlockT>::Hash>,
+	) -> Result<Vec<BondInfo<AccountId, Balance, BlockNumber, AssetId>>> {
+		let at = self.block_id(at);
+		self.client.runtime_api().bonds_by_borrower(&at, borrower).map_err(runtime_error)
+	}
+
+	fn bonds_by_lender(
+		&self,
+		lender: AccountId,
+		at: Option<<Block as BlockT>::Hash>,
+	) -> Result<Vec<BondInfo<AccountId, Balance, BlockNumber, AssetId>>> {
+		let at = self.block_id(at);
+		self.client.runtime_api().bonds_by_lender(&at, lender).map_err(runtime_error)
+	}
+
+	fn bonds_for_sale(
+		&self,
+		at: Option<<Block as BlockT>::Hash>,
+	) -> Result<Vec<BondInfo<AccountId, Balance, BlockNumber, AssetId>>> {
+		let at = self.block_id(at);
+		self.client.runtime_api().bonds_for_sale(&at).map_err(runtime_error)
+	}
+
+	fn bond(
+		&self,
+		bond_id: H256,
+		projected_at: BlockNumber,
+		at: Option<<Block as BlockT>::Hash>,
+	) -> Result<ProjectedBond<AccountId, Balance, BlockNumber, AssetId>> {
+		let at = self.block_id(at);
+		self.client.runtime_api().bond(&at, bond_id, projected_at)
+			.map_err(runtime_error)?
+			.ok_or_else(|| not_found(bond_id))
+	}
+
+	fn credit_score(&self, did: String, at: Option<<Block as BlockT>::Hash>) -> Result<CreditScore> {
+		let id = from_did(&did).map_err(invalid_did)?;
+		let at = self.block_id(at);
+		self.client.runtime_api().credit_score(&at, id).map_err(runtime_error)
+	}
+}
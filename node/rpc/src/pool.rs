@@ -0,0 +1,103 @@
+// Copyright 2019 Parity Technologies (UK) Ltd.
+// This file is part of Substrate.
+
+// Substrate is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Substrate is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Substrate.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Node-specific RPC methods for querying lending pools of the `pool` module.
+
+use std::sync::Arc;
+
+use client::blockchain::HeaderBackend;
+use codec::Codec;
+use jsonrpc_core::{Error, ErrorCode, Result};
+use jsonrpc_derive::rpc;
+use sr_primitives::{
+	generic::BlockId,
+	traits::{Block as BlockT, ProvideRuntimeApi},
+};
+
+pub use node_rpc_runtime_api::pool::{PoolApi as PoolRuntimeApi, PoolInfo};
+pub use self::gen_client::Client as PoolClient;
+
+/// Pool RPC methods.
+#[rpc]
+pub trait PoolApi<BlockHash, Balance> {
+	/// All lending pools with their current rates and yields.
+	#[rpc(name = "pool_list")]
+	fn pools(&self, at: Option<BlockHash>) -> Result<Vec<PoolInfo<Balance>>>;
+
+	/// The lending pool `pool_id` with its current rates and yields.
+	#[rpc(name = "pool_info")]
+	fn pool(&self, pool_id: u32, at: Option<BlockHash>) -> Result<PoolInfo<Balance>>;
+}
+
+/// An implementation of pool specific RPC methods.
+pub struct Pools<C, B> {
+	client: Arc<C>,
+	_marker: std::marker::PhantomData<B>,
+}
+
+impl<C, B> Pools<C, B> {
+	/// Create new `Pools` with the given reference to the client.
+	pub fn new(client: Arc<C>) -> Self {
+		Pools { client, _marker: Default::default() }
+	}
+}
+
+const RUNTIME_ERROR: i64 = 1;
+const NOT_FOUND: i64 = 2;
+
+fn runtime_error(e: impl std::fmt::Debug) -> Error {
+	Error {
+		code: ErrorCode::ServerError(RUNTIME_ERROR),
+		message: "Unable to query the pool module.".into(),
+		data: Some(format!("{:?}", e).into()),
+	}
+}
+
+impl<C, Block, Balance> PoolApi<<Block as BlockT>::Hash, Balance> for Pools<C, Block>
+where
+	Block: BlockT,
+	C: Send + Sync + 'static,
+	C: ProvideRuntimeApi,
+	C: HeaderBackend<Block>,
+	C::Api: PoolRuntimeApi<Block, Balance>,
+	Balance: Codec,
+{
+	fn pools(&self, at: Option<<Block as BlockT>::Hash>) -> Result<Vec<PoolInfo<Balance>>> {
+		let api = self.client.runtime_api();
+		let at = BlockId::hash(at.unwrap_or_else(||
+			// If the block hash is not supplied assume the best block.
+			self.client.info().best_hash
+		));
+
+		api.pools(&at).map_err(runtime_error)
+	}
+
+	fn pool(&self, pool_id: u32, at: Option<<Block as BlockT>::Hash>) -> Result<PoolInfo<Balance>> {
+		let api = self.client.runtime_api();
+		let at = BlockId::hash(at.unwrap_or_else(||
+			// If the block hash is not supplied assume the best block.
+			self.client.info().best_hash
+		));
+
+		api.pool(&at, pool_id)
+			.map_err(runtime_error)?
+			.ok_or_else(|| Error {
+				code: ErrorCode::ServerError(NOT_FOUND),
+				message: format!("Pool {} does not exist.", pool_id),
+				data: None,
+			})
+	}
+}
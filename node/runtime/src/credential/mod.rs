@@ -0,0 +1,198 @@
+//! # Credential Module
+//!
+//! ## Overview
+//!
+//! A module that anchors verifiable credentials issued by DIDs of the `did` module.
+//!
+//! ## Interface
+//!
+//! Only the hash of a credential is stored on chain, together with its schema, subject and
+//! expiry. Every issuer DID has a revocation bitmap (status list) where each anchored
+//! credential gets a bit, which is set when the credential is revoked.
+//!
+//! The sender of `anchor` reserves a deposit in `Currency` for the storage of the credential.
+//! Credentials stay anchored after they expire or are revoked, so the deposit is not released.
+//!
+//! ### Dispatchable Functions
+//!
+//! - `anchor` - Issuer anchors a credential hash for a subject DID.
+//! - `revoke` - Issuer revokes an anchored credential.
+//!
+
+use codec::{Decode, Encode};
+use primitives::H256;
+use rstd::prelude::*;
+use sr_primitives::traits::Saturating;
+use sr_primitives::weights::{SimpleDispatchInfo, READ_WEIGHT, WRITE_WEIGHT};
+use support::traits::{Currency, Get, ReservableCurrency};
+use support::{decl_event, decl_module, decl_storage, dispatch::Result, ensure};
+use system::ensure_signed;
+use did::DIDIdentifier;
+pub use node_rpc_runtime_api::credential::CredentialStatus;
+
+#[cfg(test)]
+mod test;
+
+type BalanceOf<T> = <<T as Trait>::Currency as Currency<<T as system::Trait>::AccountId>>::Balance;
+
+pub type SchemaId = Vec<u8>;
+pub type StatusIndex = u32;
+
+/// An anchored credential.
+#[derive(Encode, Decode, Clone, Eq, PartialEq)]
+#[cfg_attr(feature = "std", derive(Debug))]
+pub struct Credential<BlockNumber> {
+    /// DID of the issuer
+    pub issuer: DIDIdentifier,
+    /// DID the credential makes claims about
+    pub subject: DIDIdentifier,
+    /// identifier of the credential schema
+    pub schema: SchemaId,
+    /// block the credential was anchored at
+    pub issued_at: BlockNumber,
+    /// block from which the credential is expired
+    pub expires_at: Option<BlockNumber>,
+    /// bit of the credential in the status list of the issuer
+    pub status_index: StatusIndex,
+    /// block from which the credential is revoked
+    pub revoked_at: Option<BlockNumber>,
+}
+
+impl<BlockNumber: PartialOrd + Copy> Credential<BlockNumber> {
+    pub fn status_at(&self, block: BlockNumber) -> CredentialStatus {
+        if block < self.issued_at {
+            CredentialStatus::NotIssued
+        } else if self.revoked_at.map_or(false, |revoked| revoked <= block) {
+            CredentialStatus::Revoked
+        } else if self.expires_at.map_or(false, |expires| expires <= block) {
+            CredentialStatus::Expired
+        } else {
+            CredentialStatus::Valid
+        }
+    }
+}
+
+/// Set bit `index` of a status list, growing it as needed.
+///
+/// Bits are counted from the most significant bit of the first byte.
+pub fn set_status_bit(list: &mut Vec<u8>, index: StatusIndex) {
+    let byte = (index / 8) as usize;
+    if list.len() <= byte {
+        list.resize(byte + 1, 0);
+    }
+    list[byte] |= 0x80 >> (index % 8);
+}
+
+/// Whether bit `index` of a status list is set.
+pub fn status_bit(list: &[u8], index: StatusIndex) -> bool {
+    list.get((index / 8) as usize).map_or(false, |byte| byte & (0x80 >> (index % 8)) != 0)
+}
+
+// Module's function and Methods of custom struct to be placed here
+impl<T: Trait> Module<T> {
+    /// Status of `credential` at block `at`.
+    pub fn status(credential: H256, at: T::BlockNumber) -> CredentialStatus {
+        match Self::credential(credential) {
+            Some(c) => c.status_at(at),
+            None => CredentialStatus::Unknown,
+        }
+    }
+
+    /// Whether `credential` is valid at block `at`.
+    pub fn is_valid(credential: H256, at: T::BlockNumber) -> bool {
+        Self::status(credential, at) == CredentialStatus::Valid
+    }
+
+    /// Deposit needed for anchoring `anchored` under `credential`, including its share of the
+    /// status list of the issuer.
+    pub fn deposit_for(credential: &H256, anchored: &Credential<T::BlockNumber>) -> BalanceOf<T> {
+        let bytes = credential.encode().len() + anchored.encode().len() + 1;
+        <T as Trait>::ByteDeposit::get().saturating_mul(BalanceOf::<T>::from(bytes as u32))
+    }
+}
+
+/// The module's configuration trait.
+pub trait Trait: system::Trait + did::Trait {
+    /// The overarching event type.
+    type Event: From<Event> + Into<<Self as system::Trait>::Event>;
+
+    /// The currency the storage deposit of a credential is reserved in.
+    type Currency: ReservableCurrency<Self::AccountId>;
+
+    /// The deposit reserved per byte of storage used by a credential.
+    type ByteDeposit: Get<BalanceOf<Self>>;
+}
+
+// This module's storage items.
+decl_storage! {
+    trait Store for Module<T: Trait> as Credential {
+        pub Credentials get(credential): map H256 => Option<Credential<T::BlockNumber>>;
+        pub StatusLists get(status_list): map DIDIdentifier => Vec<u8>;
+        pub NextStatusIndex get(next_status_index): map DIDIdentifier => StatusIndex;
+        pub Deposits get(deposit): map H256 => Option<(T::AccountId, BalanceOf<T>)>;
+    }
+}
+
+decl_module! {
+
+    pub struct Module<T: Trait> for enum Call where origin: <T as system::Trait>::Origin {
+        // Initializing events
+        fn deposit_event() = default;
+
+        /// The deposit reserved per byte of storage used by a credential.
+        const ByteDeposit: BalanceOf<T> = <T as Trait>::ByteDeposit::get();
+
+        /// Anchor `credential` of the DID `issuer` about `subject`. The sender reserves a
+        /// deposit for the storage of the credential.
+        #[weight = SimpleDispatchInfo::FixedNormal(7 * READ_WEIGHT + 4 * WRITE_WEIGHT)]
+        pub fn anchor(origin, credential: H256, issuer: DIDIdentifier, subject: DIDIdentifier, schema: SchemaId, expires_at: Option<T::BlockNumber>) -> Result {
+            let sender = ensure_signed(origin)?;
+            ensure!(<did::Module<T>>::is_active(issuer.clone()), "The issuer DID is not registered or deactivated");
+            ensure!(<did::Module<T>>::is_id_issuer(issuer.clone(), sender.clone()), "You are not the issuer of this DID");
+            ensure!(<did::Module<T>>::is_active(subject.clone()), "The subject DID is not registered or deactivated");
+            ensure!(!<Credentials<T>>::exists(credential), "The credential is already anchored");
+            let current_block = <system::Module<T>>::block_number();
+            if let Some(expires_at) = expires_at {
+                ensure!(expires_at > current_block, "The credential is already expired");
+            }
+            let status_index = Self::next_status_index(issuer.clone());
+            let next_index = status_index.checked_add(1).ok_or("The status list of the issuer is full")?;
+            let anchored = Credential {
+                issuer: issuer.clone(),
+                subject: subject.clone(),
+                schema,
+                issued_at: current_block,
+                expires_at,
+                status_index,
+                revoked_at: None,
+            };
+            let deposit = Self::deposit_for(&credential, &anchored);
+            <T as Trait>::Currency::reserve(&sender, deposit)?;
+            <Deposits<T>>::insert(credential, (sender, deposit));
+            <NextStatusIndex>::insert(issuer.clone(), next_index);
+            <Credentials<T>>::insert(credential, anchored);
+            Self::deposit_event(Event::CredentialAnchored(credential, issuer, subject, status_index));
+            Ok(())
+        }
+
+        #[weight = SimpleDispatchInfo::FixedNormal(3 * READ_WEIGHT + 2 * WRITE_WEIGHT)]
+        pub fn revoke(origin, credential: H256) -> Result {
+            let sender = ensure_signed(origin)?;
+            let mut anchored = Self::credential(credential).ok_or("The credential is not anchored")?;
+            ensure!(<did::Module<T>>::is_id_issuer(anchored.issuer.clone(), sender), "You are not the issuer of this credential");
+            ensure!(anchored.revoked_at.is_none(), "The credential is already revoked");
+            anchored.revoked_at = Some(<system::Module<T>>::block_number());
+            <StatusLists>::mutate(anchored.issuer.clone(), |list| set_status_bit(list, anchored.status_index));
+            <Credentials<T>>::insert(credential, anchored.clone());
+            Self::deposit_event(Event::CredentialRevoked(credential, anchored.issuer, anchored.status_index));
+            Ok(())
+        }
+    }
+}
+
+decl_event!(
+    pub enum Event {
+        CredentialAnchored(H256, DIDIdentifier, DIDIdentifier, StatusIndex),
+        CredentialRevoked(H256, DIDIdentifier, StatusIndex),
+    }
+);
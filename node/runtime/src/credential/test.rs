@@ -0,0 +1,90 @@
+//! Tests for the credential module.
+
+use super::*;
+
+use support::{assert_noop, assert_ok};
+use crate::mock::{Balances, Credential, Origin, System};
+
+/// Root issuer DID of account 10.
+const ISSUER: &[u8] = b"did:vault:issuer";
+/// DID of account 1, issued by `ISSUER`.
+const SUBJECT: &[u8] = b"did:vault:subject";
+/// Root issuer DID of account 11, which can not pay deposits.
+const POOR_ISSUER: &[u8] = b"did:vault:poor";
+
+fn new_test_ext() -> runtime_io::TestExternalities {
+	crate::mock::new_test_ext(vec![(1, 1_000), (10, 1_000), (11, 1)], vec![
+		(ISSUER.to_vec(), vec![0], vec![10; 32], 10, 10),
+		(SUBJECT.to_vec(), ISSUER.to_vec(), vec![1; 32], 10, 1),
+		(POOR_ISSUER.to_vec(), vec![0], vec![11; 32], 11, 11),
+	])
+}
+
+fn anchor(credential: H256, expires_at: Option<u64>) -> Result {
+	Credential::anchor(Origin::signed(10), credential, ISSUER.to_vec(), SUBJECT.to_vec(), b"degree".to_vec(), expires_at)
+}
+
+#[test]
+fn anchor_reserves_deposit() {
+	new_test_ext().execute_with(|| {
+		let credential = H256::repeat_byte(1);
+		assert_noop!(
+			Credential::anchor(Origin::signed(1), credential, ISSUER.to_vec(), SUBJECT.to_vec(), vec![], None),
+			"You are not the issuer of this DID"
+		);
+		assert_ok!(anchor(credential, None));
+		assert_noop!(anchor(credential, None), "The credential is already anchored");
+
+		let deposit = Credential::deposit_for(&credential, &Credential::credential(credential).unwrap());
+		assert!(deposit > 0);
+		assert_eq!(Balances::reserved_balance(&10), deposit);
+		assert_eq!(Credential::deposit(credential), Some((10, deposit)));
+		assert_eq!(Credential::status(credential, 1), CredentialStatus::Valid);
+		assert_eq!(Credential::status(credential, 0), CredentialStatus::NotIssued);
+		assert_eq!(Credential::status(H256::repeat_byte(2), 1), CredentialStatus::Unknown);
+	});
+}
+
+#[test]
+fn anchor_fails_without_deposit() {
+	new_test_ext().execute_with(|| {
+		assert!(Credential::anchor(
+			Origin::signed(11), H256::repeat_byte(1), POOR_ISSUER.to_vec(), SUBJECT.to_vec(), vec![], None,
+		).is_err());
+		assert_eq!(Credential::credential(H256::repeat_byte(1)), None);
+		assert_eq!(Credential::next_status_index(POOR_ISSUER.to_vec()), 0);
+	});
+}
+
+#[test]
+fn credentials_expire() {
+	new_test_ext().execute_with(|| {
+		assert_noop!(anchor(H256::repeat_byte(1), Some(1)), "The credential is already expired");
+		assert_ok!(anchor(H256::repeat_byte(1), Some(5)));
+		assert!(Credential::is_valid(H256::repeat_byte(1), 4));
+		assert_eq!(Credential::status(H256::repeat_byte(1), 5), CredentialStatus::Expired);
+	});
+}
+
+#[test]
+fn revoke_sets_the_status_bit() {
+	new_test_ext().execute_with(|| {
+		for n in 1..=10 {
+			assert_ok!(anchor(H256::repeat_byte(n), None));
+		}
+		System::set_block_number(3);
+		assert_noop!(
+			Credential::revoke(Origin::signed(1), H256::repeat_byte(10)),
+			"You are not the issuer of this credential"
+		);
+		assert_ok!(Credential::revoke(Origin::signed(10), H256::repeat_byte(10)));
+		assert_noop!(
+			Credential::revoke(Origin::signed(10), H256::repeat_byte(10)),
+			"The credential is already revoked"
+		);
+		assert_eq!(Credential::status_list(ISSUER.to_vec()), vec![0, 0x40]);
+		assert!(status_bit(&Credential::status_list(ISSUER.to_vec()), 9));
+		assert!(Credential::is_valid(H256::repeat_byte(10), 2));
+		assert_eq!(Credential::status(H256::repeat_byte(10), 3), CredentialStatus::Revoked);
+	});
+}
@@ -0,0 +1,379 @@
+//! # Escrow Module
+//!
+//! ## Overview
+//!
+//! A module that holds payments with the currency in substrate until their conditions are met.
+//!
+//! ## Interface
+//!
+//! A payer creates an escrow for a payee, and the amount is reserved in the account of the
+//! payer with the `ReservableCurrency` of the module. The payee has until `accept_by` to accept
+//! the escrow, or the funds go back to the payer. Once accepted, the funds go to the payee when
+//! the payer releases them, or at the `deadline` of the escrow. Both deadlines are kept by named
+//! calls of the scheduler.
+//!
+//! The payer also reserves a deposit of `ByteDeposit` per byte the escrow takes in storage,
+//! including its entries in the escrows of each party, which is refunded when it is paid out.
+//!
+//! Either party can dispute an accepted escrow before its deadline. The funds of a disputed
+//! escrow stay reserved until they are split between the parties by the arbiter account named
+//! in the escrow, or by the `ArbiterOrigin` of the runtime. The payer can still release the
+//! funds of a disputed escrow.
+//!
+//! ### Dispatchable Functions
+//!
+//! - `create` - Payer reserves an amount for a payee, with an optional arbiter.
+//! - `accept` - Payee accepts the escrow before `accept_by`.
+//! - `cancel` - Payer takes back the funds of an escrow the payee has not accepted.
+//! - `release` - Payer hands the funds to the payee.
+//! - `dispute` - Payer or payee stops an accepted escrow from paying out at its deadline.
+//! - `resolve` - Arbiter splits the funds of a disputed escrow between the parties.
+//!
+
+use codec::{Decode, Encode};
+use primitives::H256;
+use rstd::prelude::*;
+use sr_primitives::traits::{BlakeTwo256, EnsureOrigin, Hash, Saturating, Zero};
+use sr_primitives::weights::{SimpleDispatchInfo, READ_WEIGHT, TRANSFER_WEIGHT, WRITE_WEIGHT};
+use sr_primitives::Perbill;
+use support::traits::{schedule, Currency, Get, ReservableCurrency};
+use support::{decl_event, decl_module, decl_storage, dispatch::Result, ensure};
+use system::{ensure_root, ensure_signed};
+
+pub use node_rpc_runtime_api::escrow::{EscrowInfo, EscrowStatus};
+
+#[cfg(test)]
+mod test;
+
+type BalanceOf<T> = <<T as Trait>::Currency as Currency<<T as system::Trait>::AccountId>>::Balance;
+
+#[derive(Encode, Decode, Clone, Eq, PartialEq)]
+#[cfg_attr(feature = "std", derive(Debug))]
+pub struct Escrow<AccountId, Balance, BlockNumber> {
+    payer: AccountId,
+    payee: AccountId,
+    /// account which resolves disputes besides the arbiter origin
+    arbiter: Option<AccountId>,
+    /// funds reserved in the account of the payer
+    amount: Balance,
+    accept_by: BlockNumber,
+    deadline: BlockNumber,
+    status: EscrowStatus,
+}
+
+type EscrowOf<T> = Escrow<<T as system::Trait>::AccountId, BalanceOf<T>, <T as system::Trait>::BlockNumber>;
+
+// Module's function and Methods of custom struct to be placed here
+impl<T: Trait> Module<T> {
+    /// Id the expiry of `escrow_id` with `status` is scheduled under.
+    fn expiry_task(escrow_id: H256, status: EscrowStatus) -> Vec<u8> {
+        (b"escrow/expire", escrow_id, status).encode()
+    }
+
+    /// Expire `escrow_id` with `status` at block `when` from the scheduler.
+    fn schedule_expiry(escrow_id: H256, status: EscrowStatus, when: T::BlockNumber) -> Result {
+        T::Scheduler::schedule_named(
+            Self::expiry_task(escrow_id, status),
+            when,
+            None,
+            Call::<T>::expire(escrow_id).into(),
+        )?;
+        Ok(())
+    }
+
+    fn cancel_expiry(escrow_id: H256, status: EscrowStatus) {
+        // a disputed escrow has no expiry
+        let _ = T::Scheduler::cancel_named(Self::expiry_task(escrow_id, status));
+    }
+
+    /// Deposit needed for storing `escrow` under `escrow_id` and listing it for its parties.
+    pub fn deposit_for(escrow_id: &H256, escrow: &EscrowOf<T>) -> BalanceOf<T> {
+        let bytes = (escrow_id, escrow).encode().len() + Self::parties(escrow).len() * escrow_id.encode().len();
+        T::ByteDeposit::get().saturating_mul(BalanceOf::<T>::from(bytes as u32))
+    }
+
+    /// Remove `escrow_id` from storage and from the escrows of its parties, and refund the
+    /// deposit of the payer.
+    fn close(escrow_id: H256, escrow: &EscrowOf<T>) {
+        Self::cancel_expiry(escrow_id, escrow.status);
+        <Escrows<T>>::remove(escrow_id);
+        for who in Self::parties(escrow) {
+            <EscrowsOf<T>>::mutate(who, |ids| ids.retain(|id| *id != escrow_id));
+        }
+        T::Currency::unreserve(&escrow.payer, <Deposits<T>>::take(escrow_id));
+    }
+
+    fn parties(escrow: &EscrowOf<T>) -> Vec<T::AccountId> {
+        let mut parties = vec![escrow.payer.clone(), escrow.payee.clone()];
+        parties.extend(escrow.arbiter.clone());
+        parties
+    }
+
+    /// Move `to_payee` of the funds of `escrow_id` to the payee, give the rest back to the
+    /// payer, and close the escrow. Returns the parts which were paid to the payee and given
+    /// back to the payer, which fall short if the reserved funds of the payer were slashed.
+    fn pay_out(
+        escrow_id: H256,
+        escrow: &EscrowOf<T>,
+        to_payee: BalanceOf<T>,
+    ) -> rstd::result::Result<(BalanceOf<T>, BalanceOf<T>), &'static str> {
+        let paid = if to_payee.is_zero() {
+            to_payee
+        } else {
+            let missing = T::Currency::repatriate_reserved(&escrow.payer, &escrow.payee, to_payee)?;
+            to_payee - missing
+        };
+        let to_payer = escrow.amount - to_payee;
+        let refunded = to_payer - T::Currency::unreserve(&escrow.payer, to_payer);
+        Self::close(escrow_id, escrow);
+        Ok((paid, refunded))
+    }
+
+    /// Hand the funds of `escrow_id` to the payee.
+    fn release_to_payee(escrow_id: H256, escrow: EscrowOf<T>) -> Result {
+        let (paid, _) = Self::pay_out(escrow_id, &escrow, escrow.amount)?;
+        Self::deposit_event(RawEvent::Released(escrow_id, escrow.payee, paid));
+        Ok(())
+    }
+
+    /// Give the funds of `escrow_id` back to the payer.
+    fn refund_to_payer(escrow_id: H256, escrow: EscrowOf<T>) -> Result {
+        let (_, refunded) = Self::pay_out(escrow_id, &escrow, Zero::zero())?;
+        Self::deposit_event(RawEvent::Refunded(escrow_id, escrow.payer, refunded));
+        Ok(())
+    }
+
+    /// Pay out an escrow whose payee did not accept in time, or whose deadline passed.
+    pub fn expire_escrow(escrow_id: H256, current_block: T::BlockNumber) -> Result {
+        let escrow = Self::escrow(escrow_id).ok_or("escrow does not exist")?;
+        match escrow.status {
+            EscrowStatus::Proposed => {
+                ensure!(escrow.accept_by <= current_block, "The escrow can still be accepted");
+                Self::refund_to_payer(escrow_id, escrow)
+            }
+            EscrowStatus::Accepted => {
+                ensure!(escrow.deadline <= current_block, "The deadline of the escrow has not passed");
+                Self::release_to_payee(escrow_id, escrow)
+            }
+            EscrowStatus::Disputed => Err("The escrow is disputed"),
+        }
+    }
+
+    /// `escrow_id` in the form the runtime API returns it.
+    pub fn escrow_info(
+        escrow_id: H256,
+    ) -> Option<EscrowInfo<T::AccountId, BalanceOf<T>, T::BlockNumber>> {
+        let escrow = Self::escrow(escrow_id)?;
+        Some(EscrowInfo {
+            id: escrow_id,
+            payer: escrow.payer,
+            payee: escrow.payee,
+            arbiter: escrow.arbiter,
+            amount: escrow.amount,
+            accept_by: escrow.accept_by,
+            deadline: escrow.deadline,
+            status: escrow.status,
+        })
+    }
+
+    /// The open escrows `who` pays, receives or arbitrates.
+    pub fn escrows_by_party(
+        who: T::AccountId,
+    ) -> Vec<EscrowInfo<T::AccountId, BalanceOf<T>, T::BlockNumber>> {
+        Self::escrows_of(who).into_iter().filter_map(Self::escrow_info).collect()
+    }
+}
+
+/// The module's configuration trait.
+pub trait Trait: system::Trait {
+    /// The overarching event type.
+    type Event: From<Event<Self>> + Into<<Self as system::Trait>::Event>;
+
+    /// The currency whose funds are reserved in escrow.
+    type Currency: ReservableCurrency<Self::AccountId>;
+
+    /// The origin which resolves disputes of any escrow.
+    type ArbiterOrigin: EnsureOrigin<Self::Origin>;
+
+    /// The calls of this module, as the scheduler dispatches them.
+    type Call: From<Call<Self>>;
+
+    /// The scheduler which expires escrows at their deadlines.
+    type Scheduler: schedule::Named<Self::BlockNumber, <Self as Trait>::Call>;
+
+    /// The deposit reserved from the payer per byte an escrow takes in storage.
+    type ByteDeposit: Get<BalanceOf<Self>>;
+}
+
+// This module's storage items.
+decl_storage! {
+    trait Store for Module<T: Trait> as Escrow {
+        pub Escrows get(escrow): map H256 => Option<EscrowOf<T>>;
+        /// Open escrows of each payer, payee and arbiter.
+        pub EscrowsOf get(escrows_of): map T::AccountId => Vec<H256>;
+        /// Deposit reserved from the payer of each open escrow.
+        pub Deposits get(deposit): map H256 => BalanceOf<T>;
+        pub Nonce get(nonce): u64;
+    }
+}
+
+decl_module! {
+
+    pub struct Module<T: Trait> for enum Call where origin: T::Origin {
+        // Initializing events
+        fn deposit_event() = default;
+
+        /// The deposit reserved from the payer per byte an escrow takes in storage.
+        const ByteDeposit: BalanceOf<T> = T::ByteDeposit::get();
+
+        /// Reserve `amount` for `payee`, who has until `accept_by` to accept it. The funds go
+        /// to the payee at `deadline` unless the escrow is disputed, in which case `arbiter`
+        /// or the arbiter origin decides.
+        #[weight = SimpleDispatchInfo::FixedNormal(4 * READ_WEIGHT + 9 * WRITE_WEIGHT)]
+        pub fn create(
+            origin,
+            payee: T::AccountId,
+            amount: BalanceOf<T>,
+            arbiter: Option<T::AccountId>,
+            accept_by: T::BlockNumber,
+            deadline: T::BlockNumber
+        ) -> Result {
+            let payer = ensure_signed(origin)?;
+            ensure!(payee != payer, "The payee should not be the payer");
+            ensure!(
+                arbiter.as_ref().map_or(true, |a| *a != payer && *a != payee),
+                "The arbiter should not be a party of the escrow"
+            );
+            ensure!(!amount.is_zero(), "Nothing to hold in escrow");
+            let current_block = <system::Module<T>>::block_number();
+            ensure!(accept_by > current_block, "The escrow should be accepted in the future");
+            ensure!(deadline >= accept_by, "The deadline should not be before the escrow is accepted");
+            let nonce = Self::nonce();
+            let escrow_id = BlakeTwo256::hash_of(&(b"escrow", payer.clone(), nonce));
+            ensure!(!<Escrows<T>>::exists(escrow_id), "Hash collision!");
+            let escrow = Escrow {
+                payer: payer.clone(),
+                payee: payee.clone(),
+                arbiter,
+                amount,
+                accept_by,
+                deadline,
+                status: EscrowStatus::Proposed,
+            };
+            let deposit = Self::deposit_for(&escrow_id, &escrow);
+            T::Currency::reserve(&payer, amount.saturating_add(deposit))?;
+            if let Err(e) = Self::schedule_expiry(escrow_id, EscrowStatus::Proposed, accept_by) {
+                T::Currency::unreserve(&payer, amount.saturating_add(deposit));
+                return Err(e);
+            }
+            for who in Self::parties(&escrow) {
+                <EscrowsOf<T>>::mutate(who, |ids| ids.push(escrow_id));
+            }
+            <Deposits<T>>::insert(escrow_id, deposit);
+            <Escrows<T>>::insert(escrow_id, escrow);
+            Nonce::put(nonce + 1);
+            Self::deposit_event(RawEvent::Created(escrow_id, payer, payee, amount));
+            Ok(())
+        }
+
+        /// Accept `escrow_id`, so that its funds go to the payee at the deadline.
+        #[weight = SimpleDispatchInfo::FixedNormal(3 * READ_WEIGHT + 5 * WRITE_WEIGHT)]
+        pub fn accept(origin, escrow_id: H256) -> Result {
+            let who = ensure_signed(origin)?;
+            let mut escrow = Self::escrow(escrow_id).ok_or("escrow does not exist")?;
+            ensure!(escrow.payee == who, "You are not the payee of this escrow");
+            ensure!(escrow.status == EscrowStatus::Proposed, "The escrow is already accepted");
+            ensure!(
+                escrow.accept_by > <system::Module<T>>::block_number(),
+                "The escrow can not be accepted anymore"
+            );
+            // the escrow keeps its expiry at `accept_by` if the deadline can not be scheduled
+            Self::schedule_expiry(escrow_id, EscrowStatus::Accepted, escrow.deadline)?;
+            Self::cancel_expiry(escrow_id, EscrowStatus::Proposed);
+            escrow.status = EscrowStatus::Accepted;
+            <Escrows<T>>::insert(escrow_id, escrow);
+            Self::deposit_event(RawEvent::Accepted(escrow_id, who));
+            Ok(())
+        }
+
+        /// Take back the funds of `escrow_id`, which the payee has not accepted.
+        #[weight = SimpleDispatchInfo::FixedNormal(READ_WEIGHT + 5 * WRITE_WEIGHT)]
+        pub fn cancel(origin, escrow_id: H256) -> Result {
+            let who = ensure_signed(origin)?;
+            let escrow = Self::escrow(escrow_id).ok_or("escrow does not exist")?;
+            ensure!(escrow.payer == who, "You are not the payer of this escrow");
+            ensure!(escrow.status == EscrowStatus::Proposed, "The escrow is already accepted");
+            Self::refund_to_payer(escrow_id, escrow)
+        }
+
+        /// Hand the funds of the accepted or disputed `escrow_id` to the payee.
+        #[weight = SimpleDispatchInfo::FixedNormal(TRANSFER_WEIGHT + READ_WEIGHT + 5 * WRITE_WEIGHT)]
+        pub fn release(origin, escrow_id: H256) -> Result {
+            let who = ensure_signed(origin)?;
+            let escrow = Self::escrow(escrow_id).ok_or("escrow does not exist")?;
+            ensure!(escrow.payer == who, "You are not the payer of this escrow");
+            ensure!(escrow.status != EscrowStatus::Proposed, "The escrow is not accepted yet");
+            Self::release_to_payee(escrow_id, escrow)
+        }
+
+        /// Stop the accepted `escrow_id` from paying out at its deadline, and leave its funds
+        /// to the arbiter.
+        #[weight = SimpleDispatchInfo::FixedNormal(READ_WEIGHT + 2 * WRITE_WEIGHT)]
+        pub fn dispute(origin, escrow_id: H256) -> Result {
+            let who = ensure_signed(origin)?;
+            let mut escrow = Self::escrow(escrow_id).ok_or("escrow does not exist")?;
+            ensure!(escrow.payer == who || escrow.payee == who, "You are not a party of this escrow");
+            ensure!(escrow.status == EscrowStatus::Accepted, "Only an accepted escrow can be disputed");
+            Self::cancel_expiry(escrow_id, EscrowStatus::Accepted);
+            escrow.status = EscrowStatus::Disputed;
+            <Escrows<T>>::insert(escrow_id, escrow);
+            Self::deposit_event(RawEvent::Disputed(escrow_id, who));
+            Ok(())
+        }
+
+        /// Give `payee_share` of the funds of the disputed `escrow_id` to the payee, and the
+        /// rest back to the payer. Either the arbiter of the escrow or the arbiter origin
+        /// decides.
+        #[weight = SimpleDispatchInfo::FixedNormal(TRANSFER_WEIGHT + READ_WEIGHT + 5 * WRITE_WEIGHT)]
+        pub fn resolve(origin, escrow_id: H256, payee_share: Perbill) -> Result {
+            let escrow = Self::escrow(escrow_id).ok_or("escrow does not exist")?;
+            if let Err(origin) = T::ArbiterOrigin::try_origin(origin) {
+                let who = ensure_signed(origin)?;
+                ensure!(escrow.arbiter.as_ref() == Some(&who), "You are not the arbiter of this escrow");
+            }
+            ensure!(escrow.status == EscrowStatus::Disputed, "The escrow is not disputed");
+            let (paid, refunded) = Self::pay_out(escrow_id, &escrow, payee_share * escrow.amount)?;
+            Self::deposit_event(RawEvent::Resolved(escrow_id, paid, refunded));
+            Ok(())
+        }
+
+        /// Pay out `escrow_id` after the payee failed to accept it or after its deadline. The
+        /// scheduler dispatches this as root.
+        #[weight = SimpleDispatchInfo::FixedOperational(TRANSFER_WEIGHT + 2 * READ_WEIGHT + 6 * WRITE_WEIGHT)]
+        fn expire(origin, escrow_id: H256) -> Result {
+            ensure_root(origin)?;
+            Self::expire_escrow(escrow_id, <system::Module<T>>::block_number())
+        }
+    }
+}
+
+decl_event!(
+    pub enum Event<T>
+    where
+        AccountId = <T as system::Trait>::AccountId,
+        Balance = BalanceOf<T>,
+    {
+        /// An escrow was created with its payer, payee and amount.
+        Created(H256, AccountId, AccountId, Balance),
+        /// The payee accepted an escrow.
+        Accepted(H256, AccountId),
+        /// A party disputed an escrow.
+        Disputed(H256, AccountId),
+        /// The funds of an escrow went to the payee.
+        Released(H256, AccountId, Balance),
+        /// The funds of an escrow went back to the payer.
+        Refunded(H256, AccountId, Balance),
+        /// The arbiter split the funds of an escrow, to the payee and to the payer.
+        Resolved(H256, Balance, Balance),
+    }
+);
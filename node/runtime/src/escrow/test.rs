@@ -0,0 +1,134 @@
+//! Tests for the escrow module.
+
+use super::*;
+
+use sr_primitives::traits::OnInitialize;
+use support::{assert_noop, assert_ok};
+use crate::mock::{Balances, Call, Escrow, Event, Origin, Scheduler, System};
+
+/// Account 1 pays, account 2 is paid and account 3 arbitrates.
+fn new_test_ext() -> runtime_io::TestExternalities {
+	crate::mock::new_test_ext(vec![(1, 1_000), (2, 100)], vec![])
+}
+
+/// Hold 100 of account 1 for account 2, who can accept until block 5. The funds go to account
+/// 2 at block 10.
+fn create_escrow() -> H256 {
+	let id = BlakeTwo256::hash_of(&(b"escrow", 1u64, Escrow::nonce()));
+	assert_ok!(Escrow::create(Origin::signed(1), 2, 100, Some(3), 5, 10));
+	assert!(Escrow::escrow(id).is_some());
+	id
+}
+
+/// Advance to block `n`, initializing every block on the way.
+fn run_to_block(n: u64) {
+	while System::block_number() < n {
+		System::set_block_number(System::block_number() + 1);
+		Scheduler::on_initialize(System::block_number());
+	}
+}
+
+fn escrow_events() -> Vec<RawEvent<u64, u64>> {
+	System::events().into_iter()
+		.filter_map(|record| match record.event {
+			Event::escrow(event) => Some(event),
+			_ => None,
+		})
+		.collect()
+}
+
+#[test]
+fn create_reserves_the_amount_and_a_deposit() {
+	new_test_ext().execute_with(|| {
+		assert_noop!(Escrow::create(Origin::signed(1), 1, 100, None, 5, 10), "The payee should not be the payer");
+		assert_noop!(
+			Escrow::create(Origin::signed(1), 2, 100, None, 5, 4),
+			"The deadline should not be before the escrow is accepted"
+		);
+		let id = create_escrow();
+		let deposit = Escrow::deposit(id);
+		assert!(deposit > 0);
+		assert_eq!(deposit, Escrow::deposit_for(&id, &Escrow::escrow(id).unwrap()));
+		assert_eq!(Balances::reserved_balance(&1), 100 + deposit);
+		assert_eq!(Escrow::escrows_of(2), vec![id]);
+		assert_eq!(Escrow::escrows_of(3), vec![id]);
+	});
+}
+
+#[test]
+fn unaccepted_escrows_are_refunded() {
+	new_test_ext().execute_with(|| {
+		let id = create_escrow();
+		run_to_block(5);
+		assert_eq!(Escrow::escrow(id), None);
+		assert_eq!(Balances::free_balance(&1), 1_000);
+		assert_eq!(Balances::reserved_balance(&1), 0);
+		assert!(Escrow::escrows_of(2).is_empty());
+		assert!(escrow_events().contains(&RawEvent::Refunded(id, 1, 100)));
+	});
+}
+
+#[test]
+fn accepted_escrows_are_released_at_the_deadline() {
+	new_test_ext().execute_with(|| {
+		let id = create_escrow();
+		assert_noop!(Escrow::accept(Origin::signed(3), id), "You are not the payee of this escrow");
+		assert_ok!(Escrow::accept(Origin::signed(2), id));
+		assert_noop!(Escrow::cancel(Origin::signed(1), id), "The escrow is already accepted");
+		assert_eq!(Scheduler::lookup(Escrow::expiry_task(id, EscrowStatus::Proposed)), None);
+		run_to_block(9);
+		assert_eq!(Balances::free_balance(&2), 100);
+		run_to_block(10);
+		assert_eq!(Balances::free_balance(&2), 200);
+		assert_eq!(Balances::free_balance(&1), 900);
+		assert_eq!(Balances::reserved_balance(&1), 0);
+		assert!(escrow_events().contains(&RawEvent::Released(id, 2, 100)));
+	});
+}
+
+#[test]
+fn accept_keeps_the_expiry_if_the_deadline_can_not_be_scheduled() {
+	new_test_ext().execute_with(|| {
+		let id = create_escrow();
+		let task = Escrow::expiry_task(id, EscrowStatus::Accepted);
+		assert_ok!(<Scheduler as schedule::Named<_, _>>::schedule_named(task, 20, None, Call::System(system::Call::remark(vec![]))));
+		assert_noop!(Escrow::accept(Origin::signed(2), id), "A call is already scheduled under this id");
+		assert!(Scheduler::lookup(Escrow::expiry_task(id, EscrowStatus::Proposed)).is_some());
+		run_to_block(5);
+		assert_eq!(Escrow::escrow(id), None);
+		assert_eq!(Balances::free_balance(&1), 1_000);
+	});
+}
+
+#[test]
+fn disputed_escrows_wait_for_the_arbiter() {
+	new_test_ext().execute_with(|| {
+		let id = create_escrow();
+		assert_ok!(Escrow::accept(Origin::signed(2), id));
+		assert_ok!(Escrow::dispute(Origin::signed(2), id));
+		run_to_block(10);
+		assert_eq!(Balances::reserved_balance(&1), 100 + Escrow::deposit(id));
+		assert_noop!(
+			Escrow::resolve(Origin::signed(2), id, Perbill::one()),
+			"You are not the arbiter of this escrow"
+		);
+		assert_ok!(Escrow::resolve(Origin::signed(3), id, Perbill::from_percent(30)));
+		assert_eq!(Balances::free_balance(&2), 130);
+		assert_eq!(Balances::free_balance(&1), 970);
+		assert_eq!(Balances::reserved_balance(&1), 0);
+		assert!(escrow_events().contains(&RawEvent::Resolved(id, 30, 70)));
+	});
+}
+
+#[test]
+fn slashed_funds_are_not_paid_out() {
+	new_test_ext().execute_with(|| {
+		let id = create_escrow();
+		assert_ok!(Escrow::accept(Origin::signed(2), id));
+		let deposit = Escrow::deposit(id);
+		let _ = Balances::slash_reserved(&1, deposit + 40);
+		assert_ok!(Escrow::release(Origin::signed(1), id));
+		assert_eq!(Balances::free_balance(&2), 160);
+		assert!(escrow_events().contains(&RawEvent::Released(id, 2, 60)));
+	});
+}
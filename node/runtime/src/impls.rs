@@ -16,13 +16,15 @@
 
 //! Some configurable implementations as associated type for the substrate runtime.
 
-use node_primitives::Balance;
+use node_primitives::{AccountId, AssetId, Balance};
 use sr_primitives::weights::Weight;
 use sr_primitives::traits::{Convert, Saturating};
 use sr_primitives::Fixed64;
+use support::{dispatch::Result, ensure};
 use support::traits::{OnUnbalanced, Currency};
-use crate::{Balances, Authorship, MaximumBlockWeight, NegativeImbalance};
+use crate::{Balances, Authorship, GenericAsset, MaximumBlockWeight, NegativeImbalance, Oracle};
 use crate::constants::fee::TARGET_BLOCK_FULLNESS;
+use loan::{CollateralAssets, PriceFeed};
 
 pub struct Author;
 impl OnUnbalanced<NegativeImbalance> for Author {
@@ -31,6 +33,38 @@ impl OnUnbalanced<NegativeImbalance> for Author {
 	}
 }
 
+/// Holds loan collateral in `generic-asset` tokens.
+pub struct GenericAssetCollateral;
+
+impl CollateralAssets<AccountId, AssetId, Balance> for GenericAssetCollateral {
+	fn reserve(asset: &AssetId, who: &AccountId, amount: Balance) -> Result {
+		GenericAsset::reserve(asset, who, amount)
+	}
+
+	fn unreserve(asset: &AssetId, who: &AccountId, amount: Balance) {
+		GenericAsset::unreserve(asset, who, amount);
+	}
+
+	fn reserved_balance(asset: &AssetId, who: &AccountId) -> Balance {
+		GenericAsset::reserved_balance(asset, who)
+	}
+
+	fn repatriate_reserved(asset: &AssetId, from: &AccountId, to: &AccountId, amount: Balance) -> Result {
+		ensure!(GenericAsset::reserved_balance(asset, from) >= amount, "Not enough collateral is reserved");
+		GenericAsset::repatriate_reserved(asset, from, to, amount);
+		Ok(())
+	}
+}
+
+/// Prices loan collateral with the `oracle` module.
+pub struct OraclePriceFeed;
+
+impl PriceFeed<AssetId> for OraclePriceFeed {
+	fn price(asset: &AssetId) -> Option<Fixed64> {
+		Oracle::price(*asset)
+	}
+}
+
 /// Struct that handles the conversion of Balance -> `u64`. This is used for staking's election
 /// calculation.
 pub struct CurrencyToVoteHandler;
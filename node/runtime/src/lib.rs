@@ -34,10 +34,10 @@ use grandpa::fg_primitives;
 use grandpa::{AuthorityId as GrandpaId, AuthorityWeight as GrandpaWeight};
 use im_online::sr25519::AuthorityId as ImOnlineId;
 use node_primitives::{
-	AccountId, AccountIndex, Balance, BlockNumber, Hash, Index, Moment, Signature,
+	AccountId, AccountIndex, AssetId, Balance, BlockNumber, Hash, Index, Moment, Signature,
 };
 use primitives::u32_trait::{_1, _2, _3, _4};
-use primitives::OpaqueMetadata;
+use primitives::{OpaqueMetadata, H256};
 use rstd::prelude::*;
 use sr_primitives::curve::PiecewiseLinear;
 use sr_primitives::traits::{
@@ -46,7 +46,7 @@ use sr_primitives::traits::{
 use sr_primitives::transaction_validity::TransactionValidity;
 use sr_primitives::weights::Weight;
 use sr_primitives::{
-	create_runtime_str, generic, impl_opaque_keys, key_types, ApplyResult, Perbill, Permill,
+	create_runtime_str, generic, impl_opaque_keys, key_types, ApplyResult, Fixed64, Perbill, Permill,
 };
 use support::{
 	construct_runtime, parameter_types,
@@ -65,11 +65,19 @@ pub use staking::StakerStatus;
 pub use support::StorageValue;
 pub use timestamp::Call as TimestampCall;
 
-mod did;
+mod credential;
+mod escrow;
 /// Implementations of some helper traits passed into runtime modules as associated types.
 pub mod impls;
-mod loan;
-use impls::{Author, CurrencyToVoteHandler, FeeMultiplierUpdateHandler, WeightToFee};
+#[cfg(test)]
+mod mock;
+mod oracle;
+mod pool;
+mod subscription;
+use impls::{
+	Author, CurrencyToVoteHandler, FeeMultiplierUpdateHandler,
+	GenericAssetCollateral, OraclePriceFeed, WeightToFee,
+};
 
 /// Constant values used within the runtime.
 pub mod constants;
@@ -88,8 +96,8 @@ pub const VERSION: RuntimeVersion = RuntimeVersion {
 	// and set impl_version to equal spec_version. If only runtime
 	// implementation changes and behavior does not, then leave spec_version as
 	// is and increment impl_version.
-	spec_version: 179,
-	impl_version: 180,
+	spec_version: 198,
+	impl_version: 198,
 	apis: RUNTIME_API_VERSIONS,
 };
 
@@ -144,6 +152,16 @@ impl utility::Trait for Runtime {
 	type Call = Call;
 }
 
+parameter_types! {
+	pub const MaximumSchedulerWeight: Weight = 100_000_000;
+}
+
+impl scheduler::Trait for Runtime {
+	type Event = Event;
+	type Call = Call;
+	type MaximumWeight = MaximumSchedulerWeight;
+}
+
 parameter_types! {
 	pub const EpochDuration: u64 = EPOCH_DURATION_IN_SLOTS;
 	pub const ExpectedBlockTime: Moment = MILLISECS_PER_BLOCK;
@@ -445,12 +463,89 @@ impl sudo::Trait for Runtime {
 	type Proposal = Call;
 }
 
+parameter_types! {
+	pub const DidByteDeposit: Balance = 1 * CENTS;
+	pub const LoanByteDeposit: Balance = 1 * CENTS;
+	pub const CredentialByteDeposit: Balance = 1 * CENTS;
+	pub const EscrowByteDeposit: Balance = 1 * CENTS;
+	pub const BlocksPerYear: BlockNumber = 365 * DAYS;
+	pub const InitialCollateralRatio: Fixed64 = Fixed64::from_rational(3, 2);
+	pub const MaintenanceRatio: Fixed64 = Fixed64::from_rational(6, 5);
+	pub const LiquidationBonus: Perbill = Perbill::from_percent(5);
+	pub const CollateralChecks: u32 = 32;
+	pub const MaxPriceAge: BlockNumber = 1 * HOURS;
+	pub const AccrualPeriod: BlockNumber = 1 * HOURS;
+}
+
+impl generic_asset::Trait for Runtime {
+	type Balance = Balance;
+	type AssetId = AssetId;
+	type Event = Event;
+}
+
+impl oracle::Trait for Runtime {
+	type Event = Event;
+	type AssetId = AssetId;
+	type FeedOrigin = collective::EnsureProportionMoreThan<_1, _2, AccountId, TechnicalCollective>;
+	type MaxPriceAge = MaxPriceAge;
+}
+
 impl did::Trait for Runtime {
 	type Event = Event;
+	type Origin = Origin;
+	type Proposal = Call;
+	type Currency = Balances;
+	type ByteDeposit = DidByteDeposit;
+}
+
+impl credential::Trait for Runtime {
+	type Event = Event;
+	type Currency = Balances;
+	type ByteDeposit = CredentialByteDeposit;
 }
 
 impl loan::Trait for Runtime {
 	type Event = Event;
+	type Currency = Balances;
+	type ByteDeposit = LoanByteDeposit;
+	type BlocksPerYear = BlocksPerYear;
+	type AssetId = AssetId;
+	type Assets = GenericAssetCollateral;
+	type PriceFeed = OraclePriceFeed;
+	type InitialCollateralRatio = InitialCollateralRatio;
+	type MaintenanceRatio = MaintenanceRatio;
+	type LiquidationBonus = LiquidationBonus;
+	type CollateralChecks = CollateralChecks;
+	type Identity = DID;
+	type Call = Call;
+	type Scheduler = Scheduler;
+}
+
+impl pool::Trait for Runtime {
+	type Event = Event;
+	type Call = Call;
+	type Scheduler = Scheduler;
+	type Currency = Balances;
+	type CreateOrigin = collective::EnsureProportionAtLeast<_1, _2, AccountId, CouncilCollective>;
+	type AccrualPeriod = AccrualPeriod;
+	type BlocksPerYear = BlocksPerYear;
+	type InitialCollateralRatio = InitialCollateralRatio;
+	type MaintenanceRatio = MaintenanceRatio;
+}
+
+impl subscription::Trait for Runtime {
+	type Event = Event;
+	type Call = Call;
+	type Scheduler = Scheduler;
+}
+
+impl escrow::Trait for Runtime {
+	type Event = Event;
+	type Currency = Balances;
+	type ArbiterOrigin = collective::EnsureProportionAtLeast<_1, _2, AccountId, CouncilCollective>;
+	type Call = Call;
+	type Scheduler = Scheduler;
+	type ByteDeposit = EscrowByteDeposit;
 }
 
 type SubmitTransaction = TransactionSubmitter<ImOnlineId, Runtime, UncheckedExtrinsic>;
@@ -545,8 +640,15 @@ construct_runtime!(
 		Treasury: treasury::{Module, Call, Storage, Event<T>},
 		Contracts: contracts,
 		Sudo: sudo,
-		DID: did::{Module, Call, Storage, Event<T>},
-		Loan: loan::{Module, Call, Storage, Event<T>},
+		DID: did::{Module, Call, Storage, Event<T>, Origin, Config<T>},
+		Credential: credential::{Module, Call, Storage, Event},
+		Scheduler: scheduler::{Module, Call, Storage, Event<T>},
+		Loan: loan::{Module, Call, Storage, Event<T>, Config<T>},
+		GenericAsset: generic_asset::{Module, Call, Storage, Event<T>, Config<T>},
+		Oracle: oracle::{Module, Call, Storage, Event<T>},
+		Pool: pool::{Module, Call, Storage, Event<T>},
+		Subscription: subscription::{Module, Call, Storage, Event<T>},
+		Escrow: escrow::{Module, Call, Storage, Event<T>},
 		ImOnline: im_online::{Module, Call, Storage, Event<T>, ValidateUnsigned, Config<T>},
 		AuthorityDiscovery: authority_discovery::{Module, Call, Config<T>},
 		Offences: offences::{Module, Call, Storage, Event},
@@ -725,6 +827,82 @@ impl_runtime_apis! {
 		}
 	}
 
+	impl node_rpc_runtime_api::DidApi<Block, BlockNumber> for Runtime {
+		fn resolve(id: Vec<u8>) -> Option<node_rpc_runtime_api::did::DidDocument> {
+			DID::document(id)
+		}
+
+		fn verify_signature(
+			id: Vec<u8>,
+			purpose: node_rpc_runtime_api::did::KeyPurpose,
+			message: Vec<u8>,
+			signature: Vec<u8>,
+			at: BlockNumber,
+		) -> bool {
+			DID::verify_at(id, purpose, &message, &signature, at)
+		}
+
+		fn nonce(id: Vec<u8>) -> u64 {
+			DID::nonce(id)
+		}
+	}
+
+	impl node_rpc_runtime_api::CredentialApi<Block, BlockNumber> for Runtime {
+		fn status(credential: Hash, at: BlockNumber) -> node_rpc_runtime_api::credential::CredentialStatus {
+			Credential::status(credential, at)
+		}
+
+		fn status_list(issuer: Vec<u8>) -> Vec<u8> {
+			Credential::status_list(issuer)
+		}
+	}
+
+	impl node_rpc_runtime_api::LoanApi<Block, AccountId, Balance, BlockNumber, AssetId> for Runtime {
+		fn orders(bond_id: H256) -> Option<node_rpc_runtime_api::loan::OrderBook<AccountId, Balance>> {
+			Loan::orders(bond_id)
+		}
+
+		fn bonds_by_borrower(borrower: AccountId) -> Vec<node_rpc_runtime_api::loan::BondInfo<AccountId, Balance, BlockNumber, AssetId>> {
+			Loan::bonds_by_borrower(borrower)
+		}
+
+		fn bonds_by_lender(lender: AccountId) -> Vec<node_rpc_runtime_api::loan::BondInfo<AccountId, Balance, BlockNumber, AssetId>> {
+			Loan::bonds_by_lender(lender)
+		}
+
+		fn bonds_for_sale() -> Vec<node_rpc_runtime_api::loan::BondInfo<AccountId, Balance, BlockNumber, AssetId>> {
+			Loan::bonds_for_sale()
+		}
+
+		fn bond(bond_id: H256, at: BlockNumber) -> Option<node_rpc_runtime_api::loan::ProjectedBond<AccountId, Balance, BlockNumber, AssetId>> {
+			Loan::projected_bond(bond_id, at)
+		}
+
+		fn credit_score(id: Vec<u8>) -> node_rpc_runtime_api::loan::CreditScore {
+			Loan::credit_score(id)
+		}
+	}
+
+	impl node_rpc_runtime_api::PoolApi<Block, Balance> for Runtime {
+		fn pools() -> Vec<node_rpc_runtime_api::pool::PoolInfo<Balance>> {
+			Pool::pools()
+		}
+
+		fn pool(pool_id: u32) -> Option<node_rpc_runtime_api::pool::PoolInfo<Balance>> {
+			Pool::pool_info(pool_id)
+		}
+	}
+
+	impl node_rpc_runtime_api::EscrowApi<Block, AccountId, Balance, BlockNumber> for Runtime {
+		fn escrows_of(who: AccountId) -> Vec<node_rpc_runtime_api::escrow::EscrowInfo<AccountId, Balance, BlockNumber>> {
+			Escrow::escrows_by_party(who)
+		}
+
+		fn escrow(escrow_id: H256) -> Option<node_rpc_runtime_api::escrow::EscrowInfo<AccountId, Balance, BlockNumber>> {
+			Escrow::escrow_info(escrow_id)
+		}
+	}
+
 	impl substrate_session::SessionKeys<Block> for Runtime {
 		fn generate_session_keys(seed: Option<Vec<u8>>) -> Vec<u8> {
 			let seed = seed.as_ref().map(|s| rstd::str::from_utf8(&s).expect("Seed is an utf8 string"));
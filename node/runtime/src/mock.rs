@@ -0,0 +1,162 @@
+//! Test runtime shared by the tests of the modules of this runtime.
+
+use std::cell::RefCell;
+use primitives::H256;
+use sr_primitives::{
+	BuildStorage, Fixed64, Perbill, traits::{BlakeTwo256, IdentityLookup}, testing::Header,
+	weights::Weight,
+};
+use support::{parameter_types, traits::Get};
+use system::EnsureRoot;
+use crate::{credential, escrow, oracle, pool, subscription};
+
+parameter_types! {
+	pub const BlockHashCount: u64 = 250;
+	pub const MaximumBlockWeight: u32 = 1024;
+	pub const MaximumBlockLength: u32 = 2 * 1024;
+	pub const AvailableBlockRatio: Perbill = Perbill::one();
+
+	pub const ExistentialDeposit: u64 = 0;
+	pub const CreationFee: u64 = 0;
+
+	pub const MaximumSchedulerWeight: Weight = 100_000_000;
+
+	pub const ByteDeposit: u64 = 1;
+
+	pub const AccrualPeriod: u64 = 10;
+	pub const BlocksPerYear: u64 = 100;
+	pub const InitialCollateralRatio: Fixed64 = Fixed64::from_rational(3, 2);
+	pub const MaintenanceRatio: Fixed64 = Fixed64::from_rational(6, 5);
+
+	pub const MaxPriceAge: u64 = 10;
+}
+
+thread_local! {
+	pub static TRANSFER_FEE: RefCell<u64> = RefCell::new(0);
+}
+
+/// Fee of a balance transfer, zero unless a test sets it.
+pub struct TransferFee;
+impl Get<u64> for TransferFee {
+	fn get() -> u64 {
+		TRANSFER_FEE.with(|fee| *fee.borrow())
+	}
+}
+
+impl system::Trait for Test {
+	type Origin = Origin;
+	type Index = u64;
+	type BlockNumber = u64;
+	type Call = Call;
+	type Hash = H256;
+	type Hashing = BlakeTwo256;
+	type AccountId = u64;
+	type Lookup = IdentityLookup<Self::AccountId>;
+	type Header = Header;
+	type Event = Event;
+	type BlockHashCount = BlockHashCount;
+	type MaximumBlockWeight = MaximumBlockWeight;
+	type MaximumBlockLength = MaximumBlockLength;
+	type AvailableBlockRatio = AvailableBlockRatio;
+	type Version = ();
+}
+
+impl balances::Trait for Test {
+	type Balance = u64;
+	type OnFreeBalanceZero = ();
+	type OnNewAccount = ();
+	type Event = Event;
+	type TransferPayment = ();
+	type DustRemoval = ();
+	type ExistentialDeposit = ExistentialDeposit;
+	type TransferFee = TransferFee;
+	type CreationFee = CreationFee;
+}
+
+impl scheduler::Trait for Test {
+	type Event = Event;
+	type Call = Call;
+	type MaximumWeight = MaximumSchedulerWeight;
+}
+
+impl did::Trait for Test {
+	type Event = Event;
+	type Origin = Origin;
+	type Proposal = Call;
+	type Currency = balances::Module<Self>;
+	type ByteDeposit = ByteDeposit;
+}
+
+impl credential::Trait for Test {
+	type Event = Event;
+	type Currency = balances::Module<Self>;
+	type ByteDeposit = ByteDeposit;
+}
+
+impl escrow::Trait for Test {
+	type Event = Event;
+	type Currency = balances::Module<Self>;
+	type ArbiterOrigin = EnsureRoot<u64>;
+	type Call = Call;
+	type Scheduler = scheduler::Module<Self>;
+	type ByteDeposit = ByteDeposit;
+}
+
+impl oracle::Trait for Test {
+	type Event = Event;
+	type AssetId = u32;
+	type FeedOrigin = EnsureRoot<u64>;
+	type MaxPriceAge = MaxPriceAge;
+}
+
+impl pool::Trait for Test {
+	type Event = Event;
+	type Call = Call;
+	type Scheduler = scheduler::Module<Self>;
+	type Currency = balances::Module<Self>;
+	type CreateOrigin = EnsureRoot<u64>;
+	type AccrualPeriod = AccrualPeriod;
+	type BlocksPerYear = BlocksPerYear;
+	type InitialCollateralRatio = InitialCollateralRatio;
+	type MaintenanceRatio = MaintenanceRatio;
+}
+
+impl subscription::Trait for Test {
+	type Event = Event;
+	type Call = Call;
+	type Scheduler = scheduler::Module<Self>;
+}
+
+pub type Block = sr_primitives::generic::Block<Header, UncheckedExtrinsic>;
+pub type UncheckedExtrinsic = sr_primitives::generic::UncheckedExtrinsic<u32, u64, Call, ()>;
+
+support::construct_runtime!(
+	pub enum Test where
+		Block = Block,
+		NodeBlock = Block,
+		UncheckedExtrinsic = UncheckedExtrinsic
+	{
+		System: system::{Module, Call, Event},
+		Balances: balances::{Module, Call, Storage, Config<T>, Event<T>},
+		Scheduler: scheduler::{Module, Call, Storage, Event<T>},
+		Did: did::{Module, Call, Storage, Event<T>, Origin, Config<T>},
+		Credential: credential::{Module, Call, Storage, Event},
+		Escrow: escrow::{Module, Call, Storage, Event<T>},
+		Oracle: oracle::{Module, Call, Storage, Event<T>},
+		Pool: pool::{Module, Call, Storage, Event<T>},
+		Subscription: subscription::{Module, Call, Storage, Event<T>},
+	}
+);
+
+/// Externalities at block 1, with the free `balances` of accounts and the DIDs `ids`.
+pub fn new_test_ext(
+	balances: Vec<(u64, u64)>,
+	ids: Vec<(did::DIDIdentifier, did::DIDIdentifier, did::PublicKey, u64, u64)>,
+) -> runtime_io::TestExternalities {
+	let mut ext: runtime_io::TestExternalities = GenesisConfig {
+		balances: Some(balances::GenesisConfig { balances, vesting: vec![] }),
+		did: Some(did::GenesisConfig { ids }),
+	}.build_storage().unwrap().into();
+	ext.execute_with(|| System::set_block_number(1));
+	ext
+}
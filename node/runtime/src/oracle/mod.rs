@@ -0,0 +1,113 @@
+//! # Oracle Module
+//!
+//! ## Overview
+//!
+//! A module that keeps the prices of assets in the native currency.
+//!
+//! ## Interface
+//!
+//! Prices are fed by `FeedOrigin`, e.g. a majority of a collective, so that no single
+//! feeder can move the prices loans are liquidated at. A price is only returned
+//! by `price` for `MaxPriceAge` blocks after it was fed, so that modules relying on it do
+//! not act on stale prices.
+//!
+//! ### Dispatchable Functions
+//!
+//! - `feed` - Feeder sets the price of an asset.
+//! - `remove` - Feeder removes the price of an asset.
+//!
+
+use codec::{Decode, Encode};
+use sr_primitives::traits::{EnsureOrigin, Member};
+use sr_primitives::weights::SimpleDispatchInfo;
+use sr_primitives::Fixed64;
+use support::dispatch::Parameter;
+use support::{decl_event, decl_module, decl_storage, dispatch::Result, ensure, traits::Get};
+
+#[cfg(test)]
+mod test;
+
+/// A price together with the block it was fed at.
+#[derive(Encode, Decode, Copy, Clone, Eq, PartialEq, Default)]
+#[cfg_attr(feature = "std", derive(Debug))]
+pub struct PriceInfo<BlockNumber> {
+    /// price of one unit of the asset in units of the native currency
+    pub price: Fixed64,
+    pub updated_at: BlockNumber,
+}
+
+impl<T: Trait> Module<T> {
+    /// Price of one unit of `asset` in units of the native currency, unless it is older
+    /// than `MaxPriceAge`.
+    pub fn price(asset: T::AssetId) -> Option<Fixed64> {
+        let info = Self::price_info(asset)?;
+        let current_block = <system::Module<T>>::block_number();
+        if current_block - info.updated_at > T::MaxPriceAge::get() {
+            None
+        } else {
+            Some(info.price)
+        }
+    }
+}
+
+/// The module's configuration trait.
+pub trait Trait: system::Trait {
+    /// The overarching event type.
+    type Event: From<Event<Self>> + Into<<Self as system::Trait>::Event>;
+
+    /// Identifier of the assets prices are kept for.
+    type AssetId: Parameter + Member + Copy + Default;
+
+    /// The origin which feeds prices.
+    type FeedOrigin: EnsureOrigin<Self::Origin>;
+
+    /// The number of blocks a price is valid for after it was fed.
+    type MaxPriceAge: Get<Self::BlockNumber>;
+}
+
+// This module's storage items.
+decl_storage! {
+    trait Store for Module<T: Trait> as Oracle {
+        pub Prices get(price_info): map T::AssetId => Option<PriceInfo<T::BlockNumber>>;
+    }
+}
+
+decl_module! {
+
+    pub struct Module<T: Trait> for enum Call where origin: T::Origin {
+        // Initializing events
+        fn deposit_event() = default;
+
+        /// The number of blocks a price is valid for after it was fed.
+        const MaxPriceAge: T::BlockNumber = T::MaxPriceAge::get();
+
+        #[weight = SimpleDispatchInfo::FixedNormal(100_000)]
+        pub fn feed(origin, asset: T::AssetId, price: Fixed64) -> Result {
+            T::FeedOrigin::ensure_origin(origin)?;
+            ensure!(price > Fixed64::default(), "The price should be positive");
+            let updated_at = <system::Module<T>>::block_number();
+            <Prices<T>>::insert(asset, PriceInfo { price, updated_at });
+            Self::deposit_event(RawEvent::PriceFed(asset, price));
+            Ok(())
+        }
+
+        #[weight = SimpleDispatchInfo::FixedNormal(100_000)]
+        pub fn remove(origin, asset: T::AssetId) -> Result {
+            T::FeedOrigin::ensure_origin(origin)?;
+            ensure!(<Prices<T>>::exists(asset), "There is no price for this asset");
+            <Prices<T>>::remove(asset);
+            Self::deposit_event(RawEvent::PriceRemoved(asset));
+            Ok(())
+        }
+    }
+}
+
+decl_event!(
+    pub enum Event<T>
+    where
+        AssetId = <T as Trait>::AssetId,
+    {
+        PriceFed(AssetId, Fixed64),
+        PriceRemoved(AssetId),
+    }
+);
@@ -0,0 +1,63 @@
+//! Tests for the oracle module.
+
+use super::*;
+
+use support::{assert_noop, assert_ok};
+use crate::mock::{Oracle, Origin, System};
+
+fn new_test_ext() -> runtime_io::TestExternalities {
+	crate::mock::new_test_ext(vec![], vec![])
+}
+
+#[test]
+fn fed_prices_are_returned() {
+	new_test_ext().execute_with(|| {
+		assert_eq!(Oracle::price(0), None);
+		assert_ok!(Oracle::feed(Origin::ROOT, 0, Fixed64::from_rational(3, 2)));
+		assert_eq!(Oracle::price(0), Some(Fixed64::from_rational(3, 2)));
+		assert_eq!(Oracle::price(1), None);
+		assert_eq!(Oracle::price_info(0), Some(PriceInfo { price: Fixed64::from_rational(3, 2), updated_at: 1 }));
+
+		// a new price replaces the old one
+		System::set_block_number(5);
+		assert_ok!(Oracle::feed(Origin::ROOT, 0, Fixed64::from_natural(2)));
+		assert_eq!(Oracle::price(0), Some(Fixed64::from_natural(2)));
+		assert_eq!(Oracle::price_info(0).map(|info| info.updated_at), Some(5));
+	});
+}
+
+#[test]
+fn only_the_feed_origin_feeds_positive_prices() {
+	new_test_ext().execute_with(|| {
+		assert_noop!(Oracle::feed(Origin::signed(1), 0, Fixed64::from_natural(1)), "Invalid origin");
+		assert_noop!(Oracle::feed(Origin::ROOT, 0, Fixed64::default()), "The price should be positive");
+		assert_noop!(Oracle::feed(Origin::ROOT, 0, Fixed64::from_natural(-1)), "The price should be positive");
+		assert_eq!(Oracle::price(0), None);
+	});
+}
+
+#[test]
+fn prices_expire_after_max_price_age() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Oracle::feed(Origin::ROOT, 0, Fixed64::from_natural(1)));
+		System::set_block_number(11);
+		assert_eq!(Oracle::price(0), Some(Fixed64::from_natural(1)));
+		System::set_block_number(12);
+		assert_eq!(Oracle::price(0), None);
+		// the stale price is kept until it is fed again
+		assert!(Oracle::price_info(0).is_some());
+		assert_ok!(Oracle::feed(Origin::ROOT, 0, Fixed64::from_natural(1)));
+		assert_eq!(Oracle::price(0), Some(Fixed64::from_natural(1)));
+	});
+}
+
+#[test]
+fn removed_prices_are_not_returned() {
+	new_test_ext().execute_with(|| {
+		assert_noop!(Oracle::remove(Origin::ROOT, 0), "There is no price for this asset");
+		assert_ok!(Oracle::feed(Origin::ROOT, 0, Fixed64::from_natural(1)));
+		assert_noop!(Oracle::remove(Origin::signed(1), 0), "Invalid origin");
+		assert_ok!(Oracle::remove(Origin::ROOT, 0));
+		assert_eq!(Oracle::price(0), None);
+	});
+}
@@ -0,0 +1,531 @@
+//! # Pool Module
+//!
+//! ## Overview
+//!
+//! A module that lends the currency in substrate from shared liquidity pools.
+//!
+//! ## Interface
+//!
+//! Depositors put funds into a pool and receive pool shares. Borrowers lock collateral with
+//! the `LockableCurrency` of the module and borrow from the pool, as long as their collateral
+//! covers `InitialCollateralRatio` times their debt. A borrower whose collateral drops below
+//! `MaintenanceRatio` times the debt can be liquidated by anyone who pays the debt back.
+//!
+//! The borrow rate of a pool is a function of its utilisation, the share of the deposits which
+//! is lent out, given by the `RateModel` of the pool. Interest accrues on the debt of the
+//! borrowers every `AccrualPeriod` blocks by a periodic call of the scheduler. The interest grows
+//! the value of every pool share, so it goes to the depositors pro rata.
+//!
+//! ### Dispatchable Functions
+//!
+//! - `create` - Creates a pool with a rate model.
+//! - `deposit` - Depositor puts funds into a pool for pool shares.
+//! - `withdraw` - Depositor redeems pool shares for funds.
+//! - `add_collateral` - Borrower locks collateral for borrowing from a pool.
+//! - `remove_collateral` - Borrower unlocks collateral which is not needed for its debt.
+//! - `borrow` - Borrower takes funds from a pool against its collateral.
+//! - `repay` - Borrower pays its debt back.
+//! - `liquidate` - Anyone pays the debt of an undercollateralised borrower and takes its collateral.
+//! - `accrue` - Scheduler accrues the interest of a period on the debt of a pool.
+//!
+
+use codec::{Decode, Encode};
+use rstd::prelude::*;
+use sr_primitives::helpers_128bit::multiply_by_rational;
+use sr_primitives::traits::{
+    AccountIdConversion, Bounded, EnsureOrigin, SaturatedConversion, Saturating, Zero,
+};
+use sr_primitives::weights::{SimpleDispatchInfo, READ_WEIGHT, TRANSFER_WEIGHT, WRITE_WEIGHT};
+use sr_primitives::{Fixed64, ModuleId, Perbill};
+use support::traits::{
+    schedule, Currency, ExistenceRequirement, Get, LockIdentifier, LockableCurrency, WithdrawReason,
+    WithdrawReasons,
+};
+use support::{decl_event, decl_module, decl_storage, dispatch::Result, ensure};
+use system::{ensure_root, ensure_signed};
+
+pub use node_rpc_runtime_api::pool::PoolInfo;
+
+#[cfg(test)]
+mod test;
+
+/// Identifier of the accounts holding the funds of the pools.
+const MODULE_ID: ModuleId = ModuleId(*b"py/lpool");
+
+/// Identifier of the lock on the collateral of the borrowers.
+const COLLATERAL_ID: LockIdentifier = *b"pool    ";
+
+/// Maximum number of accrual periods compounded when computing a yearly yield.
+const MAX_PERIODS_PER_YEAR: u64 = 1_000_000;
+
+pub type PoolId = u32;
+
+type BalanceOf<T> = <<T as Trait>::Currency as Currency<<T as system::Trait>::AccountId>>::Balance;
+type NegativeImbalanceOf<T> =
+    <<T as Trait>::Currency as Currency<<T as system::Trait>::AccountId>>::NegativeImbalance;
+
+/// Annual borrow rate of a pool as a function of its utilisation.
+///
+/// The rate grows by `slope` per unit of utilisation up to `kink`, and by `jump` per unit of
+/// utilisation above it, so that borrowing gets expensive before the pool runs dry.
+#[derive(Encode, Decode, Copy, Clone, Eq, PartialEq, Default)]
+#[cfg_attr(feature = "std", derive(Debug))]
+pub struct RateModel {
+    /// rate at zero utilisation
+    pub base: Perbill,
+    pub slope: Perbill,
+    pub kink: Perbill,
+    pub jump: Perbill,
+}
+
+impl RateModel {
+    /// Annual borrow rate at `utilisation`, saturating at 100%.
+    pub fn borrow_rate(&self, utilisation: Perbill) -> Perbill {
+        let normal = utilisation.deconstruct().min(self.kink.deconstruct()) as u64;
+        let excess = utilisation.deconstruct().saturating_sub(self.kink.deconstruct()) as u64;
+        let parts = self.base.deconstruct() as u64 + self.slope * normal + self.jump * excess;
+        Perbill::from_parts(parts.min(Perbill::accuracy() as u64) as u32)
+    }
+}
+
+#[derive(Encode, Decode, Copy, Clone, Eq, PartialEq, Default)]
+#[cfg_attr(feature = "std", derive(Debug))]
+pub struct Pool<Balance> {
+    model: RateModel,
+    /// deposits which are not lent out
+    cash: Balance,
+    /// outstanding debt of the borrowers with the interest accrued on it
+    borrows: Balance,
+    /// pool shares issued to the depositors
+    shares: Balance,
+    /// debt shares issued to the borrowers
+    debt_shares: Balance,
+}
+
+/// Holdings of an account in a pool.
+#[derive(Encode, Decode, Copy, Clone, Eq, PartialEq, Default)]
+#[cfg_attr(feature = "std", derive(Debug))]
+pub struct Position<Balance> {
+    /// pool shares of a depositor
+    shares: Balance,
+    /// debt shares of a borrower
+    debt_shares: Balance,
+    /// collateral locked for borrowing from the pool
+    collateral: Balance,
+}
+
+// Module's function and Methods of custom struct to be placed here
+impl<T: Trait> Module<T> {
+    /// The account holding the funds of `pool_id`.
+    pub fn account_id(pool_id: PoolId) -> T::AccountId {
+        MODULE_ID.into_sub_account(pool_id)
+    }
+
+    /// `amount * numerator / denominator`, rounded down, or zero if `denominator` is zero.
+    fn mul_ratio(amount: BalanceOf<T>, numerator: BalanceOf<T>, denominator: BalanceOf<T>) -> BalanceOf<T> {
+        if denominator.is_zero() {
+            return Zero::zero();
+        }
+        multiply_by_rational(
+            amount.saturated_into(),
+            numerator.saturated_into(),
+            denominator.saturated_into(),
+        )
+        .map(|v| v.saturated_into())
+        .unwrap_or_else(|_| Bounded::max_value())
+    }
+
+    /// Shares worth `amount` when `total_shares` are worth `total`. The first shares are
+    /// issued one for one.
+    fn shares_for(amount: BalanceOf<T>, total_shares: BalanceOf<T>, total: BalanceOf<T>) -> BalanceOf<T> {
+        if total_shares.is_zero() || total.is_zero() {
+            amount
+        } else {
+            Self::mul_ratio(amount, total_shares, total)
+        }
+    }
+
+    /// Outstanding debt of `position` in `pool`.
+    pub fn debt_of(pool: &Pool<BalanceOf<T>>, position: &Position<BalanceOf<T>>) -> BalanceOf<T> {
+        Self::mul_ratio(position.debt_shares, pool.borrows, pool.debt_shares)
+    }
+
+    /// Share of the deposits of `pool` which is lent out.
+    pub fn utilisation(pool: &Pool<BalanceOf<T>>) -> Perbill {
+        let total = pool.cash.saturating_add(pool.borrows);
+        if total.is_zero() {
+            return Perbill::zero();
+        }
+        Perbill::from_rational_approximation(pool.borrows, total)
+    }
+
+    /// Interest rate of a single accrual period, from the annual `rate`.
+    pub fn period_rate(rate: Perbill) -> Fixed64 {
+        let period = T::AccrualPeriod::get().saturated_into::<u32>() as i64;
+        let blocks_per_year = T::BlocksPerYear::get().saturated_into::<u32>() as u64;
+        Fixed64::from_rational(
+            rate.deconstruct() as i64 * period,
+            Perbill::accuracy() as u64 * blocks_per_year,
+        )
+    }
+
+    /// Yearly yield of the annual `rate` compounded every accrual period, in parts per billion.
+    pub fn apy(rate: Perbill) -> u64 {
+        let period = T::AccrualPeriod::get().saturated_into::<u32>().max(1) as u64;
+        let periods = (T::BlocksPerYear::get().saturated_into::<u32>() as u64 / period)
+            .min(MAX_PERIODS_PER_YEAR);
+        // (1 + period rate) ^ periods, in parts of `Fixed64::accuracy()`
+        let one = Fixed64::accuracy() as u64;
+        let period_rate = Self::period_rate(rate);
+        let growth = (0..periods).fold(one, |g, _| period_rate.saturated_multiply_accumulate(g));
+        growth.saturating_sub(one)
+    }
+
+    /// Whether `collateral` is worth at least `ratio` times `debt`.
+    fn is_covered(collateral: BalanceOf<T>, debt: BalanceOf<T>, ratio: Fixed64) -> bool {
+        collateral >= ratio.saturated_multiply_accumulate(debt).saturating_sub(debt)
+    }
+
+    /// Lock `amount` of the collateral of `who` in every pool.
+    fn set_collateral(who: &T::AccountId, amount: BalanceOf<T>) {
+        if amount.is_zero() {
+            T::Currency::remove_lock(COLLATERAL_ID, who);
+            <LockedCollateral<T>>::remove(who);
+        } else {
+            T::Currency::set_lock(
+                COLLATERAL_ID,
+                who,
+                amount,
+                T::BlockNumber::max_value(),
+                WithdrawReasons::all(),
+            );
+            <LockedCollateral<T>>::insert(who, amount);
+        }
+    }
+
+    /// Take `amount` out of `from` without a transfer fee, which a pool could not pay from the
+    /// funds it tracks.
+    fn take(from: &T::AccountId, amount: BalanceOf<T>) -> rstd::result::Result<NegativeImbalanceOf<T>, &'static str> {
+        T::Currency::withdraw(from, amount, WithdrawReason::Transfer, ExistenceRequirement::AllowDeath)
+    }
+
+    /// Accrue the interest of one period on the debt of `pool_id`.
+    pub fn accrue_interest(pool_id: PoolId) -> Result {
+        let mut pool = Self::pool(pool_id).ok_or("pool does not exist")?;
+        let rate = pool.model.borrow_rate(Self::utilisation(&pool));
+        let before = pool.borrows;
+        pool.borrows = Self::period_rate(rate).saturated_multiply_accumulate(pool.borrows);
+        let interest = pool.borrows - before;
+        <Pools<T>>::insert(pool_id, pool);
+        if !interest.is_zero() {
+            Self::deposit_event(RawEvent::InterestAccrued(pool_id, interest, rate));
+        }
+        Ok(())
+    }
+
+    /// Id the interest accrual of `pool_id` is scheduled under.
+    fn accrual_task(pool_id: PoolId) -> Vec<u8> {
+        (b"pool/accrue", pool_id).encode()
+    }
+
+    /// `pool_id` with its current rates, in the form the runtime API returns it.
+    pub fn pool_info(pool_id: PoolId) -> Option<PoolInfo<BalanceOf<T>>> {
+        let pool = Self::pool(pool_id)?;
+        let utilisation = Self::utilisation(&pool);
+        let borrow_rate = pool.model.borrow_rate(utilisation);
+        // depositors earn the interest of the borrowers on the whole pool
+        let supply_rate = utilisation * borrow_rate.deconstruct();
+        Some(PoolInfo {
+            id: pool_id,
+            cash: pool.cash,
+            borrows: pool.borrows,
+            utilisation: utilisation.deconstruct(),
+            borrow_rate: borrow_rate.deconstruct(),
+            borrow_apy: Self::apy(borrow_rate),
+            supply_apy: Self::apy(Perbill::from_parts(supply_rate)),
+        })
+    }
+
+    /// All pools with their current rates.
+    pub fn pools() -> Vec<PoolInfo<BalanceOf<T>>> {
+        (0..Self::next_pool_id()).filter_map(Self::pool_info).collect()
+    }
+}
+
+/// The module's configuration trait.
+pub trait Trait: system::Trait {
+    /// The overarching event type.
+    type Event: From<Event<Self>> + Into<<Self as system::Trait>::Event>;
+
+    /// The calls of this module, as the scheduler dispatches them.
+    type Call: From<Call<Self>>;
+
+    /// The scheduler which accrues the interest of the pools every period.
+    type Scheduler: schedule::Named<Self::BlockNumber, <Self as Trait>::Call>;
+
+    /// The currency which is deposited, borrowed and locked as collateral.
+    type Currency: LockableCurrency<Self::AccountId, Moment = Self::BlockNumber>;
+
+    /// The origin which creates pools.
+    type CreateOrigin: EnsureOrigin<Self::Origin>;
+
+    /// The number of blocks between two accruals of interest.
+    type AccrualPeriod: Get<Self::BlockNumber>;
+
+    /// The number of blocks in a year, which annual interest rates are converted with.
+    type BlocksPerYear: Get<Self::BlockNumber>;
+
+    /// The ratio of collateral to debt a borrower needs to borrow or remove collateral.
+    type InitialCollateralRatio: Get<Fixed64>;
+
+    /// The ratio of collateral to debt below which a borrower can be liquidated.
+    type MaintenanceRatio: Get<Fixed64>;
+}
+
+// This module's storage items.
+decl_storage! {
+    trait Store for Module<T: Trait> as Pool {
+        pub Pools get(pool): map PoolId => Option<Pool<BalanceOf<T>>>;
+        pub NextPoolId get(next_pool_id): PoolId;
+        pub Positions get(position): map (PoolId, T::AccountId) => Position<BalanceOf<T>>;
+        /// Collateral of each borrower in every pool, which is locked in its account.
+        pub LockedCollateral get(locked_collateral): map T::AccountId => BalanceOf<T>;
+    }
+}
+
+decl_module! {
+
+    pub struct Module<T: Trait> for enum Call where origin: T::Origin {
+        // Initializing events
+        fn deposit_event() = default;
+
+        /// The number of blocks between two accruals of interest.
+        const AccrualPeriod: T::BlockNumber = T::AccrualPeriod::get();
+
+        /// The number of blocks in a year, which annual interest rates are converted with.
+        const BlocksPerYear: T::BlockNumber = T::BlocksPerYear::get();
+
+        /// The ratio of collateral to debt a borrower needs to borrow or remove collateral.
+        const InitialCollateralRatio: Fixed64 = T::InitialCollateralRatio::get();
+
+        /// The ratio of collateral to debt below which a borrower can be liquidated.
+        const MaintenanceRatio: Fixed64 = T::MaintenanceRatio::get();
+
+        /// Create a pool whose borrow rate follows `model`.
+        #[weight = SimpleDispatchInfo::FixedNormal(3 * READ_WEIGHT + 4 * WRITE_WEIGHT)]
+        pub fn create(origin, model: RateModel) -> Result {
+            T::CreateOrigin::ensure_origin(origin)?;
+            ensure!(!T::AccrualPeriod::get().is_zero(), "The accrual period should be longer than zero");
+            let pool_id = Self::next_pool_id();
+            let period = T::AccrualPeriod::get();
+            T::Scheduler::schedule_named(
+                Self::accrual_task(pool_id),
+                <system::Module<T>>::block_number() + period,
+                Some(period),
+                Call::<T>::accrue(pool_id).into(),
+            )?;
+            <Pools<T>>::insert(pool_id, Pool { model, ..Default::default() });
+            NextPoolId::put(pool_id + 1);
+            Self::deposit_event(RawEvent::PoolCreated(pool_id));
+            Ok(())
+        }
+
+        /// Deposit `amount` into `pool_id` for pool shares.
+        #[weight = SimpleDispatchInfo::FixedNormal(TRANSFER_WEIGHT + 2 * READ_WEIGHT + 2 * WRITE_WEIGHT)]
+        pub fn deposit(origin, pool_id: PoolId, amount: BalanceOf<T>) -> Result {
+            let who = ensure_signed(origin)?;
+            let mut pool = Self::pool(pool_id).ok_or("pool does not exist")?;
+            let shares = Self::shares_for(amount, pool.shares, pool.cash.saturating_add(pool.borrows));
+            ensure!(!shares.is_zero(), "The deposit is worth no shares");
+            T::Currency::transfer(&who, &Self::account_id(pool_id), amount)?;
+            pool.cash += amount;
+            pool.shares += shares;
+            <Pools<T>>::insert(pool_id, pool);
+            <Positions<T>>::mutate((pool_id, who.clone()), |p| p.shares += shares);
+            Self::deposit_event(RawEvent::Deposited(pool_id, who, amount, shares));
+            Ok(())
+        }
+
+        /// Redeem `shares` of `pool_id` for their part of the pool.
+        #[weight = SimpleDispatchInfo::FixedNormal(TRANSFER_WEIGHT + 2 * READ_WEIGHT + 2 * WRITE_WEIGHT)]
+        pub fn withdraw(origin, pool_id: PoolId, shares: BalanceOf<T>) -> Result {
+            let who = ensure_signed(origin)?;
+            let mut pool = Self::pool(pool_id).ok_or("pool does not exist")?;
+            let mut position = Self::position((pool_id, who.clone()));
+            ensure!(!shares.is_zero(), "Nothing to withdraw");
+            ensure!(position.shares >= shares, "You do not have enough shares");
+            let amount = Self::mul_ratio(shares, pool.cash.saturating_add(pool.borrows), pool.shares);
+            ensure!(amount <= pool.cash, "The pool does not have enough cash");
+            let left = pool.cash - amount;
+            ensure!(left.is_zero() || left >= T::Currency::minimum_balance(), "The pool would be left with dust");
+            T::Currency::resolve_creating(&who, Self::take(&Self::account_id(pool_id), amount)?);
+            pool.cash = left;
+            pool.shares -= shares;
+            position.shares -= shares;
+            <Pools<T>>::insert(pool_id, pool);
+            <Positions<T>>::insert((pool_id, who.clone()), position);
+            Self::deposit_event(RawEvent::Withdrawn(pool_id, who, amount, shares));
+            Ok(())
+        }
+
+        /// Lock `amount` of the free balance as collateral for borrowing from `pool_id`.
+        #[weight = SimpleDispatchInfo::FixedNormal(3 * READ_WEIGHT + 3 * WRITE_WEIGHT)]
+        pub fn add_collateral(origin, pool_id: PoolId, amount: BalanceOf<T>) -> Result {
+            let who = ensure_signed(origin)?;
+            ensure!(<Pools<T>>::exists(pool_id), "pool does not exist");
+            let locked = Self::locked_collateral(&who).saturating_add(amount);
+            ensure!(T::Currency::free_balance(&who) >= locked, "You do not have enough free balance");
+            Self::set_collateral(&who, locked);
+            <Positions<T>>::mutate((pool_id, who.clone()), |p| p.collateral += amount);
+            Self::deposit_event(RawEvent::CollateralAdded(pool_id, who, amount));
+            Ok(())
+        }
+
+        /// Unlock `amount` of the collateral in `pool_id` which is not needed for the debt.
+        #[weight = SimpleDispatchInfo::FixedNormal(4 * READ_WEIGHT + 3 * WRITE_WEIGHT)]
+        pub fn remove_collateral(origin, pool_id: PoolId, amount: BalanceOf<T>) -> Result {
+            let who = ensure_signed(origin)?;
+            let pool = Self::pool(pool_id).ok_or("pool does not exist")?;
+            let mut position = Self::position((pool_id, who.clone()));
+            ensure!(position.collateral >= amount, "You do not have enough collateral");
+            position.collateral -= amount;
+            let debt = Self::debt_of(&pool, &position);
+            ensure!(
+                Self::is_covered(position.collateral, debt, T::InitialCollateralRatio::get()),
+                "The collateral would not cover the debt"
+            );
+            Self::set_collateral(&who, Self::locked_collateral(&who).saturating_sub(amount));
+            <Positions<T>>::insert((pool_id, who.clone()), position);
+            Self::deposit_event(RawEvent::CollateralRemoved(pool_id, who, amount));
+            Ok(())
+        }
+
+        /// Borrow `amount` from `pool_id` against the collateral in it.
+        #[weight = SimpleDispatchInfo::FixedNormal(TRANSFER_WEIGHT + 2 * READ_WEIGHT + 2 * WRITE_WEIGHT)]
+        pub fn borrow(origin, pool_id: PoolId, amount: BalanceOf<T>) -> Result {
+            let who = ensure_signed(origin)?;
+            let mut pool = Self::pool(pool_id).ok_or("pool does not exist")?;
+            let mut position = Self::position((pool_id, who.clone()));
+            ensure!(!amount.is_zero(), "Nothing to borrow");
+            ensure!(amount <= pool.cash, "The pool does not have enough cash");
+            let left = pool.cash - amount;
+            ensure!(left.is_zero() || left >= T::Currency::minimum_balance(), "The pool would be left with dust");
+            let debt = Self::debt_of(&pool, &position).saturating_add(amount);
+            ensure!(
+                Self::is_covered(position.collateral, debt, T::InitialCollateralRatio::get()),
+                "The collateral does not cover the loan"
+            );
+            let debt_shares = Self::shares_for(amount, pool.debt_shares, pool.borrows);
+            T::Currency::resolve_creating(&who, Self::take(&Self::account_id(pool_id), amount)?);
+            pool.cash = left;
+            pool.borrows += amount;
+            pool.debt_shares += debt_shares;
+            position.debt_shares += debt_shares;
+            <Pools<T>>::insert(pool_id, pool);
+            <Positions<T>>::insert((pool_id, who.clone()), position);
+            Self::deposit_event(RawEvent::Borrowed(pool_id, who, amount));
+            Ok(())
+        }
+
+        /// Pay up to `amount` of the debt in `pool_id` back.
+        #[weight = SimpleDispatchInfo::FixedNormal(TRANSFER_WEIGHT + 2 * READ_WEIGHT + 2 * WRITE_WEIGHT)]
+        pub fn repay(origin, pool_id: PoolId, amount: BalanceOf<T>) -> Result {
+            let who = ensure_signed(origin)?;
+            let mut pool = Self::pool(pool_id).ok_or("pool does not exist")?;
+            let mut position = Self::position((pool_id, who.clone()));
+            let debt = Self::debt_of(&pool, &position);
+            let paid = amount.min(debt);
+            ensure!(!paid.is_zero(), "Nothing to repay");
+            let burnt = if paid == debt {
+                position.debt_shares
+            } else {
+                Self::mul_ratio(paid, pool.debt_shares, pool.borrows)
+            };
+            T::Currency::transfer(&who, &Self::account_id(pool_id), paid)?;
+            pool.cash += paid;
+            pool.borrows = pool.borrows.saturating_sub(paid);
+            pool.debt_shares = pool.debt_shares.saturating_sub(burnt);
+            position.debt_shares -= burnt;
+            <Pools<T>>::insert(pool_id, pool);
+            <Positions<T>>::insert((pool_id, who.clone()), position);
+            Self::deposit_event(RawEvent::Repaid(pool_id, who, paid));
+            Ok(())
+        }
+
+        /// Pay the debt of `borrower` in `pool_id`, whose collateral is below the maintenance
+        /// ratio, and take its collateral in the pool.
+        #[weight = SimpleDispatchInfo::FixedNormal(2 * TRANSFER_WEIGHT + 4 * READ_WEIGHT + 4 * WRITE_WEIGHT)]
+        pub fn liquidate(origin, pool_id: PoolId, borrower: T::AccountId) -> Result {
+            let liquidator = ensure_signed(origin)?;
+            ensure!(liquidator != borrower, "You cannot liquidate yourself");
+            let mut pool = Self::pool(pool_id).ok_or("pool does not exist")?;
+            let position = Self::position((pool_id, borrower.clone()));
+            let debt = Self::debt_of(&pool, &position);
+            ensure!(!debt.is_zero(), "The borrower has no debt");
+            ensure!(
+                !Self::is_covered(position.collateral, debt, T::MaintenanceRatio::get()),
+                "The borrower is above the maintenance ratio"
+            );
+            ensure!(T::Currency::free_balance(&liquidator) >= debt, "You do not have enough free balance");
+            // collateral in other pools stays locked
+            let total_locked = Self::locked_collateral(&borrower);
+            let locked = total_locked.saturating_sub(position.collateral);
+            let seized = position.collateral.min(T::Currency::free_balance(&borrower).saturating_sub(locked));
+            let payment = T::Currency::withdraw(
+                &liquidator,
+                debt,
+                WithdrawReason::Transfer,
+                ExistenceRequirement::KeepAlive,
+            )?;
+            Self::set_collateral(&borrower, locked);
+            // other locks of the borrower can hold the collateral back; undo the payment then
+            let collateral = match Self::take(&borrower, seized) {
+                Ok(collateral) => collateral,
+                Err(e) => {
+                    Self::set_collateral(&borrower, total_locked);
+                    T::Currency::resolve_creating(&liquidator, payment);
+                    return Err(e);
+                }
+            };
+            T::Currency::resolve_creating(&Self::account_id(pool_id), payment);
+            T::Currency::resolve_creating(&liquidator, collateral);
+            pool.cash += debt;
+            pool.borrows = pool.borrows.saturating_sub(debt);
+            pool.debt_shares = pool.debt_shares.saturating_sub(position.debt_shares);
+            <Pools<T>>::insert(pool_id, pool);
+            <Positions<T>>::insert((pool_id, borrower.clone()), Position { shares: position.shares, ..Default::default() });
+            Self::deposit_event(RawEvent::Liquidated(pool_id, borrower, liquidator, debt, seized));
+            Ok(())
+        }
+
+        /// Accrue the interest of the period of `pool_id` which ended. The scheduler
+        /// dispatches this as root every period of a pool.
+        #[weight = SimpleDispatchInfo::FixedOperational(READ_WEIGHT + WRITE_WEIGHT)]
+        fn accrue(origin, pool_id: PoolId) -> Result {
+            ensure_root(origin)?;
+            Self::accrue_interest(pool_id)
+        }
+    }
+}
+
+decl_event!(
+    pub enum Event<T>
+    where
+        AccountId = <T as system::Trait>::AccountId,
+        Balance = BalanceOf<T>,
+    {
+        PoolCreated(PoolId),
+        /// Account, amount, shares.
+        Deposited(PoolId, AccountId, Balance, Balance),
+        /// Account, amount, shares.
+        Withdrawn(PoolId, AccountId, Balance, Balance),
+        CollateralAdded(PoolId, AccountId, Balance),
+        CollateralRemoved(PoolId, AccountId, Balance),
+        Borrowed(PoolId, AccountId, Balance),
+        Repaid(PoolId, AccountId, Balance),
+        /// Borrower, liquidator, debt paid, collateral seized.
+        Liquidated(PoolId, AccountId, AccountId, Balance, Balance),
+        /// Interest added to the debt of the borrowers, at the annual rate.
+        InterestAccrued(PoolId, Balance, Perbill),
+    }
+);
@@ -0,0 +1,153 @@
+//! Tests for the pool module.
+
+use super::*;
+
+use sr_primitives::traits::OnInitialize;
+use support::{assert_noop, assert_ok};
+use crate::mock::{Balances, Event, Origin, Pool, Scheduler, System, TRANSFER_FEE};
+
+/// Account 1 deposits into the pools, account 2 borrows from them and account 3 liquidates.
+fn new_test_ext() -> runtime_io::TestExternalities {
+	crate::mock::new_test_ext(vec![(1, 10_000), (2, 1_000), (3, 1_000)], vec![])
+}
+
+/// Create a pool with the flat annual rate `base`, 10% a period, and deposit 1_000 into it.
+fn create_pool(base: Perbill) -> PoolId {
+	let pool_id = Pool::next_pool_id();
+	let model = RateModel { base, ..Default::default() };
+	assert_ok!(Pool::create(Origin::ROOT, model));
+	assert_ok!(Pool::deposit(Origin::signed(1), pool_id, 1_000));
+	pool_id
+}
+
+/// Lock 300 of collateral of account 2 and borrow 200 against it.
+fn borrow(pool_id: PoolId) {
+	assert_ok!(Pool::add_collateral(Origin::signed(2), pool_id, 300));
+	assert_ok!(Pool::borrow(Origin::signed(2), pool_id, 200));
+}
+
+fn debt(pool_id: PoolId, who: u64) -> u64 {
+	Pool::debt_of(&Pool::pool(pool_id).unwrap(), &Pool::position((pool_id, who)))
+}
+
+/// Advance to block `n`, initializing every block on the way.
+fn run_to_block(n: u64) {
+	while System::block_number() < n {
+		System::set_block_number(System::block_number() + 1);
+		Scheduler::on_initialize(System::block_number());
+	}
+}
+
+fn pool_events() -> Vec<RawEvent<u64, u64>> {
+	System::events().into_iter()
+		.filter_map(|record| match record.event {
+			Event::pool(event) => Some(event),
+			_ => None,
+		})
+		.collect()
+}
+
+#[test]
+fn deposits_are_withdrawn_for_shares() {
+	new_test_ext().execute_with(|| {
+		assert_noop!(Pool::create(Origin::signed(1), RateModel::default()), "Invalid origin");
+		let pool_id = create_pool(Perbill::zero());
+		assert_eq!(Pool::position((pool_id, 1)).shares, 1_000);
+		assert_eq!(Balances::free_balance(&Pool::account_id(pool_id)), 1_000);
+		assert_noop!(Pool::withdraw(Origin::signed(1), pool_id, 1_001), "You do not have enough shares");
+		assert_ok!(Pool::withdraw(Origin::signed(1), pool_id, 400));
+		assert_eq!(Balances::free_balance(&1), 9_400);
+		assert_eq!(Pool::pool_info(pool_id).unwrap().cash, 600);
+	});
+}
+
+#[test]
+fn borrowing_needs_collateral() {
+	new_test_ext().execute_with(|| {
+		let pool_id = create_pool(Perbill::zero());
+		assert_ok!(Pool::add_collateral(Origin::signed(2), pool_id, 300));
+		assert_noop!(Pool::borrow(Origin::signed(2), pool_id, 201), "The collateral does not cover the loan");
+		assert_ok!(Pool::borrow(Origin::signed(2), pool_id, 200));
+		assert_eq!(Balances::free_balance(&2), 1_200);
+		assert_noop!(
+			Pool::remove_collateral(Origin::signed(2), pool_id, 1),
+			"The collateral would not cover the debt"
+		);
+		assert_ok!(Pool::repay(Origin::signed(2), pool_id, 500));
+		assert_eq!(Balances::free_balance(&2), 1_000);
+		assert_eq!(debt(pool_id, 2), 0);
+		assert_ok!(Pool::remove_collateral(Origin::signed(2), pool_id, 300));
+		assert_eq!(Pool::locked_collateral(&2), 0);
+	});
+}
+
+#[test]
+fn interest_accrues_every_period() {
+	new_test_ext().execute_with(|| {
+		let pool_id = create_pool(Perbill::one());
+		borrow(pool_id);
+		assert_noop!(Pool::accrue(Origin::signed(1), pool_id), "RequireRootOrigin");
+		run_to_block(10);
+		assert_eq!(debt(pool_id, 2), 200);
+		run_to_block(11);
+		assert_eq!(debt(pool_id, 2), 220);
+		assert_eq!(pool_events().pop(), Some(RawEvent::InterestAccrued(pool_id, 20, Perbill::one())));
+		run_to_block(21);
+		assert_eq!(debt(pool_id, 2), 242);
+		assert!(Scheduler::lookup(Pool::accrual_task(pool_id)).is_some());
+	});
+}
+
+#[test]
+fn undercollateralised_borrowers_are_liquidated() {
+	new_test_ext().execute_with(|| {
+		let pool_id = create_pool(Perbill::one());
+		borrow(pool_id);
+		run_to_block(21);
+		assert_noop!(
+			Pool::liquidate(Origin::signed(3), pool_id, 2),
+			"The borrower is above the maintenance ratio"
+		);
+		// 300 of collateral is below 120% of the debt of 266
+		run_to_block(31);
+		assert_eq!(debt(pool_id, 2), 266);
+		assert_noop!(Pool::liquidate(Origin::signed(2), pool_id, 2), "You cannot liquidate yourself");
+		assert_ok!(Pool::liquidate(Origin::signed(3), pool_id, 2));
+		assert_eq!(Balances::free_balance(&3), 1_000 - 266 + 300);
+		assert_eq!(Balances::free_balance(&2), 900);
+		assert_eq!(debt(pool_id, 2), 0);
+		assert_eq!(Pool::locked_collateral(&2), 0);
+		assert_eq!(pool_events().pop(), Some(RawEvent::Liquidated(pool_id, 2, 3, 266, 300)));
+	});
+}
+
+#[test]
+fn pool_payouts_are_not_charged_a_transfer_fee() {
+	new_test_ext().execute_with(|| {
+		TRANSFER_FEE.with(|fee| *fee.borrow_mut() = 5);
+		let pool_id = create_pool(Perbill::zero());
+		assert_eq!(Balances::free_balance(&1), 10_000 - 1_000 - 5);
+		borrow(pool_id);
+		assert_eq!(Balances::free_balance(&2), 1_200);
+		assert_ok!(Pool::repay(Origin::signed(2), pool_id, 200));
+		assert_ok!(Pool::withdraw(Origin::signed(1), pool_id, 1_000));
+		assert_eq!(Balances::free_balance(&1), 10_000 - 5);
+		assert_eq!(Balances::free_balance(&Pool::account_id(pool_id)), 0);
+	});
+}
+
+#[test]
+fn liquidation_is_undone_when_the_collateral_can_not_be_seized() {
+	new_test_ext().execute_with(|| {
+		let pool_id = create_pool(Perbill::one());
+		borrow(pool_id);
+		run_to_block(31);
+		Balances::set_lock(*b"vesting ", &2, 1_200, u64::max_value(), WithdrawReasons::all());
+		assert!(Pool::liquidate(Origin::signed(3), pool_id, 2).is_err());
+		assert_eq!(Balances::free_balance(&3), 1_000);
+		assert_eq!(Balances::free_balance(&2), 1_200);
+		assert_eq!(Balances::free_balance(&Pool::account_id(pool_id)), 800);
+		assert_eq!(Pool::locked_collateral(&2), 300);
+		assert_eq!(debt(pool_id, 2), 266);
+	});
+}
@@ -1,160 +1,203 @@
-//! # Loan Module
+//! # Subscription Module
 //!
 //! ## Overview
 //!
-//! A module that enables lending/borrowing with the currency in substrate.
+//! A module that enables recurring payments with the currency in substrate.
 //!
 //! ## Interface
 //!
-//! This module implements peer-to-peer loan and bond.
+//! A provider creates a subscription plan, and accounts subscribe to it. Every subscriber is
+//! charged the plan amount once per period by a periodic call of the scheduler. A charge which
+//! fails does not abort the block; the subscriber is suspended until it resumes the subscription.
 //!
 //! ### Dispatchable Functions
 //!
-//! - `request` - Request loan and get bond_id to get approval from others.
-//! - `approve` - Approver accepts loan request and lend money for the borrower
-//! - `redeem` - Borrower ends loan by paying back the borrowed money with interest.
-//! - `repossess` - Lender takes collateral from borrower when the bond expires.
-//! - `sell_bond` - Lender sets bond for sale
-//! - `buy_bond` - Other lender takses lender's bond for sale by paying it.
-//!  
+//! - `create` - Provider creates a subscription plan with an amount charged per period.
+//! - `subscribe` - Subscriber pays the first period and joins the plan.
+//! - `unsubscribe` - Subscriber leaves the plan.
+//! - `pause` - Subscriber stops being charged without leaving the plan.
+//! - `resume` - Paused or suspended subscriber pays the current period and is charged again.
+//! - `cancel` - Provider closes the plan and removes every subscriber.
+//!
+//! A plan has at most `MAX_SUBSCRIBERS` subscribers, so `cancel` is weighed for all of them.
+//!
 
-use crate::RandomnessCollectiveFlip;
 use codec::{Decode, Encode};
 use primitives::H256;
 use rstd::prelude::*;
-use sr_primitives::weights::SimpleDispatchInfo;
+use sr_primitives::traits::{BlakeTwo256, Hash};
+use sr_primitives::weights::{SimpleDispatchInfo, Weight, READ_WEIGHT, TRANSFER_WEIGHT, WRITE_WEIGHT};
 use support::dispatch::Parameter;
-use support::{
-    decl_event, decl_module, decl_storage,
-    dispatch::Result,
-    ensure,
-    traits::Randomness,
-    traits::{Currency, LockIdentifier, LockableCurrency, WithdrawReason, WithdrawReasons},
-};
-use system::ensure_signed;
+use support::{decl_event, decl_module, decl_storage, dispatch::Result, ensure};
+use support::traits::{schedule, Currency};
+use system::{ensure_root, ensure_signed};
+
+#[cfg(test)]
+mod test;
+
+/// Maximum number of subscribers of a plan.
+pub const MAX_SUBSCRIBERS: usize = 100;
 
 pub type Reason = Vec<u8>;
 
-#[derive(Encode, Decode, Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Default)]
+#[derive(Encode, Decode, Clone, Eq, PartialEq, Default)]
 pub struct Subscription<Balance: Parameter, AccountId: Parameter, BlockNumber: Parameter> {
-    subscribers: Vec<AccountId>,
-    to: AccountId,
+    provider: AccountId,
     paying_for: Reason,
     amount: Balance,
     period: BlockNumber,
-    next_payment: BlockNumber,
+    subscribers: Vec<AccountId>,
 }
 
 impl<Balance: Parameter, AccountId: Parameter, BlockNumber: Parameter>
     Subscription<Balance, AccountId, BlockNumber>
 {
-    pub fn new(
-        subscriber: Option<AccountId>,
-        to: AccountId,
-        paying_for: Reason,
-        amount: Balance,
-        period: BlockNumber,
-        next_payment: BlockNumber,
-    ) -> Self {
+    pub fn new(provider: AccountId, paying_for: Reason, amount: Balance, period: BlockNumber) -> Self {
         Subscription {
-            subscribers,
-            to,
+            provider,
             paying_for,
             amount,
             period,
-            next_payment,
+            subscribers: Vec::new(),
         }
     }
 
+    pub fn sender_is_provider(&self, provider: AccountId) -> bool {
+        self.provider == provider
+    }
+
     pub fn add_subscriber(&mut self, subscriber: AccountId) {
         self.subscribers.push(subscriber);
     }
+
+    pub fn remove_subscriber(&mut self, subscriber: &AccountId) {
+        self.subscribers.retain(|s| s != subscriber);
+    }
+}
+
+/// Charging state of a single subscriber.
+#[derive(Encode, Decode, Copy, Clone, Eq, PartialEq)]
+#[cfg_attr(feature = "std", derive(Debug))]
+pub enum Status {
+    /// Charged every period.
+    Active,
+    /// Stopped by the subscriber.
+    Paused,
+    /// Stopped because a charge failed.
+    Suspended,
+}
+
+impl Default for Status {
+    fn default() -> Self {
+        Status::Active
+    }
+}
+
+#[derive(Encode, Decode, Copy, Clone, Eq, PartialEq, Default)]
+pub struct SubscriberInfo<BlockNumber: Parameter> {
+    status: Status,
+    next_payment: BlockNumber,
 }
 
 // Module's function and Methods of custom struct to be placed here
 impl<T: Trait> Module<T> {
-    pub fn activate_payment(
-        mut subscription Subscription<T::Balance, T::AccountId, T::BlockNumber>,
-        subscriber: T::AccountId
-    ) -> Subscription<T::Balance, T::AccountId, T::BlockNumber> {
+    /// Charge `subscriber` for one period of `subscription_id`.
+    pub fn charge(subscription_id: H256, subscriber: T::AccountId, current_block: T::BlockNumber) -> Result {
+        ensure!(
+            <Subscriptions<T>>::exists(subscription_id),
+            "subscription does not exist"
+        );
+        let subscription = Self::subscription(subscription_id);
         <balances::Module<T> as Currency<_>>::transfer(
-            &subscriber.clone(),
-            &subcription.clone().to,
-            subscription.clone().amount,
-        )
-        .expect("Transfer the lending amount from lender to borrower");
-        subscription.next_payment += subcription.period;
-        subscription
+            &subscriber,
+            &subscription.provider,
+            subscription.amount,
+        )?;
+        let next = current_block + subscription.period;
+        <Subscribers<T>>::insert(
+            (subscription_id, subscriber.clone()),
+            SubscriberInfo {
+                status: Status::Active,
+                next_payment: next,
+            },
+        );
+        Self::deposit_event(RawEvent::SubscriptionPaid(
+            subscription_id,
+            subscriber,
+            subscription.amount,
+            next,
+        ));
+        Ok(())
     }
 
-    pub fn process_subscription((subscription_id, subscriber): (H256, T::AccountId)) -> Result {
-        let mut updated_subscription = Self::subscription(subscription_id.clone());
-        updated_subscription = Self::activate_payment(updated_subscription.clone(), subscriber.clone());
-        <Subscriptions<T>>::mutate(subscription_id.clone(), |s| *s = updated_subscription.clone());
-        let next = updated_subscription.clone().next_payment;
-        if <SubscriptionCallBacks<T>>::exists(next.clone()) {
-            <SubscriptionCallBacks<T>>::mutate(next.clone(), |c| {
-                c.push(subscription_id);
-            });
-            Self::deposit_event(RawEvent::SubscriptionPaid(
-                bond_id.clone(),
-                before,
-                updated_bond.clone().amount,
-                next.clone(),
-            ));
-        } else {
-            <SubscriptionCallBacks<T>>::insert(next.clone(), vec![subscription_id]);
-            Self::deposit_event(RawEvent::SubscriptionPaid(
-                bond_id.clone(),
-                before,
-                updated_bond.clone().amount,
-                next.clone(),
+    /// Charge `subscriber` now, and every period of `subscription_id` from the scheduler.
+    fn start_charging(subscription_id: H256, subscriber: T::AccountId, current_block: T::BlockNumber) -> Result {
+        let period = Self::subscription(subscription_id).period;
+        T::Scheduler::schedule_named(
+            Self::charge_task(subscription_id, &subscriber),
+            current_block + period,
+            Some(period),
+            Call::<T>::process(subscription_id, subscriber.clone()).into(),
+        )?;
+        if let Err(e) = Self::charge(subscription_id, subscriber.clone(), current_block) {
+            Self::stop_charging(subscription_id, &subscriber);
+            return Err(e);
+        }
+        Ok(())
+    }
+
+    /// Process a scheduled charge. A failed charge suspends the subscriber instead of failing.
+    pub fn process_subscription(subscription_id: H256, subscriber: T::AccountId, current_block: T::BlockNumber) -> Result {
+        let record = Self::subscriber((subscription_id, subscriber.clone())).ok_or("You are not subscribed")?;
+        ensure!(record.status == Status::Active, "The subscription is not active");
+        ensure!(record.next_payment <= current_block, "The period of the subscription is not over yet");
+        if Self::charge(subscription_id, subscriber.clone(), current_block).is_err() {
+            Self::stop_charging(subscription_id, &subscriber);
+            <Subscribers<T>>::insert(
+                (subscription_id, subscriber.clone()),
+                SubscriberInfo {
+                    status: Status::Suspended,
+                    next_payment: record.next_payment,
+                },
+            );
+            Self::deposit_event(RawEvent::PaymentFailed(
+                subscription_id,
+                subscriber,
+                Self::subscription(subscription_id).amount,
             ));
         }
-        return Ok(());
+        Ok(())
     }
 
-    pub fn to_balance(u: u32, digit: &str) -> T::Balance {
-        let power = |u: u32, p: u32| -> T::Balance {
-            let mut base = T::Balance::from(u);
-            for _i in 0..p {
-                base *= T::Balance::from(10)
-            }
-            return base;
-        };
-        let result = match digit {
-            "femto" => T::Balance::from(u),
-            "nano" => power(u, 3),
-            "micro" => power(u, 6),
-            "milli" => power(u, 9),
-            "one" => power(u, 12),
-            "kilo" => power(u, 15),
-            "mega" => power(u, 18),
-            "giga" => power(u, 21),
-            "tera" => power(u, 24),
-            "peta" => power(u, 27),
-            "exa" => power(u, 30),
-            "zetta" => power(u, 33),
-            "yotta" => power(u, 36),
-            _ => T::Balance::from(u),
-        };
-        result
+    /// Id the charges of `subscriber` to `subscription_id` are scheduled under.
+    fn charge_task(subscription_id: H256, subscriber: &T::AccountId) -> Vec<u8> {
+        (b"subscription/charge", subscription_id, subscriber).encode()
+    }
+
+    /// Cancel the scheduled charges of `subscriber` to `subscription_id`.
+    fn stop_charging(subscription_id: H256, subscriber: &T::AccountId) {
+        let _ = T::Scheduler::cancel_named(Self::charge_task(subscription_id, subscriber));
     }
 }
 
 /// The module's configuration trait.
 pub trait Trait: system::Trait + balances::Trait {
-    // TODO: Add other types and constants required configure this module.
-
     /// The overarching event type.
     type Event: From<Event<Self>> + Into<<Self as system::Trait>::Event>;
+
+    /// The calls of this module, as the scheduler dispatches them.
+    type Call: From<Call<Self>>;
+
+    /// The scheduler which charges the subscribers every period.
+    type Scheduler: schedule::Named<Self::BlockNumber, <Self as Trait>::Call>;
 }
 
 // This module's storage items.
 decl_storage! {
-    trait Store for Module<T: Trait> as Loan {
+    trait Store for Module<T: Trait> as Subscription {
         pub Subscriptions get(subscription): map H256 => Subscription<T::Balance, T::AccountId, T::BlockNumber>;
-        pub SubscriptionCallBacks get(callback): map T::BlockNumber => Vec<(H256, T::AccountId)>;
+        pub Subscribers get(subscriber): map (H256, T::AccountId) => Option<SubscriberInfo<T::BlockNumber>>;
+        pub Nonce get(nonce): u64;
     }
 }
 
@@ -163,95 +206,95 @@ decl_module! {
     pub struct Module<T: Trait> for enum Call where origin: T::Origin {
         // Initializing events
         fn deposit_event() = default;
-        fn on_finalize(block_number: T::BlockNumber) {
-            let bonds = Self::callback(block_number);
-            for i in bonds {
-                if let Err(e) = Self::process_subscription(i) {
-                    sr_primitives::print(e);
-                }
-            }
-        }
 
-        #[weight = SimpleDispatchInfo::FixedNormal(0)]
-        pub fn generate_subscription(origin, lender: T::AccountId, amount: T::Balance, expires_at: T::BlockNumber, interest: T::Balance, collateral: T::Balance, period: T::BlockNumber) -> Result {
+        #[weight = SimpleDispatchInfo::FixedNormal(2 * READ_WEIGHT + 2 * WRITE_WEIGHT)]
+        pub fn create(origin, paying_for: Reason, amount: T::Balance, period: T::BlockNumber) -> Result {
             let provider = ensure_signed(origin)?;
-            let subscription_hash = H256::from_slice(&RandomnessCollectiveFlip::random_seed().encode() as &[u8]);
-            ensure!(!<Subscriptions<T>>::exists(subscription_hash.clone()), "Hash collision!");
-            new_subscription = Subscription::new()
-            <Subscriptions<T>>::insert(subscription_hash, new_bond.clone());
-            Self::deposit_event(RawEvent::LoanRequested(new_bond.clone().lender, new_bond.clone().borrower, bond_hash.clone()));
+            ensure!(period > T::BlockNumber::from(0), "The period should be longer than zero");
+            let nonce = Self::nonce();
+            let subscription_hash = BlakeTwo256::hash_of(&(b"subscription", provider.clone(), nonce));
+            ensure!(!<Subscriptions<T>>::exists(subscription_hash), "Hash collision!");
+            let new_subscription = Subscription::new(provider.clone(), paying_for, amount, period);
+            <Subscriptions<T>>::insert(subscription_hash, new_subscription);
+            Nonce::put(nonce + 1);
+            Self::deposit_event(RawEvent::SubscriptionCreated(subscription_hash, provider, amount, period));
             Ok(())
         }
 
-        #[weight = SimpleDispatchInfo::FixedNormal(0)]
-        pub fn approve(origin, bond_id: H256) -> Result {
-            let approver = ensure_signed(origin)?;
-            ensure!(<Bonds<T>>::exists(bond_id.clone()), "bond does not exist");
-            let mut the_bond = Self::bond(bond_id.clone());
-            ensure!(the_bond.sender_is_lender(approver.clone()), "You are not the lender for this borrower");
-            the_bond.approve(approver);
+        #[weight = SimpleDispatchInfo::FixedNormal(TRANSFER_WEIGHT + 7 * READ_WEIGHT + 4 * WRITE_WEIGHT)]
+        pub fn subscribe(origin, subscription_id: H256) -> Result {
+            let subscriber = ensure_signed(origin)?;
+            ensure!(<Subscriptions<T>>::exists(subscription_id), "subscription does not exist");
+            ensure!(Self::subscriber((subscription_id, subscriber.clone())).is_none(), "You are already subscribed");
+            ensure!(
+                Self::subscription(subscription_id).subscribers.len() < MAX_SUBSCRIBERS,
+                "The subscription has the maximum number of subscribers"
+            );
             let current_block = <system::Module<T>>::block_number();
-            <Bonds<T>>::mutate(bond_id, |l| {*l = the_bond.clone()});
-            <balances::Module<T>>::set_lock(COLLATERAL_ID, &the_bond.clone().borrower, the_bond.clone().collateral,  T::BlockNumber::from(100000000), WithdrawReasons::all());
-            <balances::Module<T> as Currency<_>>::transfer(&the_bond.clone().lender, &the_bond.clone().borrower, the_bond.clone().amount).expect("Transfer the lending amount from lender to borrower");
-            Self::process_bond(bond_id.clone(), current_block.clone()).expect("loan operation is stored in a callback storage");
-            Self::deposit_event(RawEvent::LoanApproved(the_bond.clone().lender, the_bond.clone().borrower, current_block.clone()));
+            Self::start_charging(subscription_id, subscriber.clone(), current_block)?;
+            <Subscriptions<T>>::mutate(subscription_id, |s| s.add_subscriber(subscriber.clone()));
+            Self::deposit_event(RawEvent::Subscribed(subscription_id, subscriber));
             Ok(())
         }
 
-        #[weight = SimpleDispatchInfo::FixedNormal(0)]
-        pub fn redeem(origin, bond_id: H256) -> Result {
-            let redeemer = ensure_signed(origin)?;
-            ensure!(<Bonds<T>>::exists(bond_id.clone()), "Loan does not exist");
-            let the_bond = Self::bond(bond_id.clone());
-            ensure!(the_bond.clone().sender_is_borrower(redeemer), "You are not the redeemer for this loan");
-            <balances::Module<T>>::remove_lock(COLLATERAL_ID, &the_bond.clone().borrower);
-            <balances::Module<T> as Currency<_>>::transfer(&the_bond.clone().borrower, &the_bond.clone().lender, the_bond.clone().amount).expect("Transfer the owed amount from borrower to lender");
-            <LoanCallBacks<T>>::remove(the_bond.clone().next_increment);
-            <Bonds<T>>::remove(bond_id);
-            Self::deposit_event(RawEvent::LoanRedeemed(the_bond.clone().lender, the_bond.clone().borrower, the_bond.clone().amount));
+        #[weight = SimpleDispatchInfo::FixedNormal(4 * READ_WEIGHT + 4 * WRITE_WEIGHT)]
+        pub fn unsubscribe(origin, subscription_id: H256) -> Result {
+            let subscriber = ensure_signed(origin)?;
+            ensure!(Self::subscriber((subscription_id, subscriber.clone())).is_some(), "You are not subscribed");
+            Self::stop_charging(subscription_id, &subscriber);
+            <Subscribers<T>>::remove((subscription_id, subscriber.clone()));
+            <Subscriptions<T>>::mutate(subscription_id, |s| s.remove_subscriber(&subscriber));
+            Self::deposit_event(RawEvent::Unsubscribed(subscription_id, subscriber));
             Ok(())
         }
 
-        #[weight = SimpleDispatchInfo::FixedNormal(0)]
-        pub fn repossess(origin, bond_id: H256) -> Result {
-            let lender = ensure_signed(origin)?;
-            ensure!(<Bonds<T>>::exists(bond_id.clone()), "Loan does not exist");
-            let the_bond = Self::bond(bond_id.clone());
-            ensure!(the_bond.clone().sender_is_lender(lender), "You are not the lender for this loan");
-            let current_block = <system::Module<T>>::block_number();
-            ensure!(the_bond.clone().expires_at.unwrap() < current_block, "The bond is not expired yet");
-            <balances::Module<T>>::remove_lock(COLLATERAL_ID, &the_bond.clone().borrower);
-            <balances::Module<T> as Currency<_>>::transfer(&the_bond.clone().borrower, &the_bond.clone().lender, the_bond.clone().collateral).expect("Transfer the collateral amount from borrower to lender");
-            <LoanCallBacks<T>>::remove(the_bond.clone().next_increment);
-            <Bonds<T>>::remove(bond_id);
-            Self::deposit_event(RawEvent::LoanRepossessed(the_bond.clone().lender, the_bond.clone().borrower, the_bond.clone().collateral));
+        #[weight = SimpleDispatchInfo::FixedNormal(3 * READ_WEIGHT + 3 * WRITE_WEIGHT)]
+        pub fn pause(origin, subscription_id: H256) -> Result {
+            let subscriber = ensure_signed(origin)?;
+            let record = Self::subscriber((subscription_id, subscriber.clone())).ok_or("You are not subscribed")?;
+            ensure!(record.status == Status::Active, "The subscription is not active");
+            Self::stop_charging(subscription_id, &subscriber);
+            <Subscribers<T>>::insert((subscription_id, subscriber.clone()), SubscriberInfo { status: Status::Paused, ..record });
+            Self::deposit_event(RawEvent::SubscriptionPaused(subscription_id, subscriber));
             Ok(())
         }
 
-        pub fn sell_bond(origin, bond_id: H256, price: T::Balance ) -> Result {
-            let sender = ensure_signed(origin)?;
-            ensure!(<Bonds<T>>::exists(bond_id.clone()), "bond does not exist");
-            let mut the_bond = Self::bond(bond_id.clone());
-            ensure!(the_bond.clone().sender_is_lender(sender.clone()), "You are not the lender for this bond");
-            the_bond.request_transfer(price.clone());
-            <Bonds<T>>::mutate(bond_id, |b| {*b = the_bond});
-            Self::deposit_event(RawEvent::BondTransferRequested(bond_id.clone(), sender.clone(), price));
+        #[weight = SimpleDispatchInfo::FixedNormal(TRANSFER_WEIGHT + 6 * READ_WEIGHT + 3 * WRITE_WEIGHT)]
+        pub fn resume(origin, subscription_id: H256) -> Result {
+            let subscriber = ensure_signed(origin)?;
+            let record = Self::subscriber((subscription_id, subscriber.clone())).ok_or("You are not subscribed")?;
+            ensure!(record.status != Status::Active, "The subscription is already active");
+            let current_block = <system::Module<T>>::block_number();
+            Self::start_charging(subscription_id, subscriber.clone(), current_block)?;
+            Self::deposit_event(RawEvent::SubscriptionResumed(subscription_id, subscriber));
             Ok(())
         }
 
-        pub fn buy_bond(origin, bond_id: H256) -> Result {
-            let sender = ensure_signed(origin)?;
-            ensure!(<Bonds<T>>::exists(bond_id.clone()), "bond does not exist");
-            let mut the_bond = Self::bond(bond_id.clone());
-            ensure!(the_bond.clone().for_sale.is_some(), "The bond is not open for sale");
-            <balances::Module<T> as Currency<_>>::transfer(&sender, &the_bond.clone().lender, the_bond.clone().for_sale.unwrap()).expect("Transfer the current debt amount from buyer to lender");
-            the_bond.approve_transfer(sender.clone());
-            <Bonds<T>>::mutate(bond_id, |b| {*b = the_bond});
-            Self::deposit_event(RawEvent::BondTransferApproved(bond_id.clone(), sender.clone()));
+        /// Close `subscription_id`, and remove its subscribers with their scheduled charges.
+        #[weight = SimpleDispatchInfo::FixedNormal(
+            (2 + 2 * MAX_SUBSCRIBERS as Weight) * READ_WEIGHT + (1 + 3 * MAX_SUBSCRIBERS as Weight) * WRITE_WEIGHT
+        )]
+        pub fn cancel(origin, subscription_id: H256) -> Result {
+            let provider = ensure_signed(origin)?;
+            ensure!(<Subscriptions<T>>::exists(subscription_id), "subscription does not exist");
+            let subscription = Self::subscription(subscription_id);
+            ensure!(subscription.sender_is_provider(provider.clone()), "You are not the provider of this subscription");
+            for subscriber in subscription.subscribers.iter() {
+                <Subscribers<T>>::remove((subscription_id, subscriber.clone()));
+                Self::stop_charging(subscription_id, subscriber);
+            }
+            <Subscriptions<T>>::remove(subscription_id);
+            Self::deposit_event(RawEvent::SubscriptionCancelled(subscription_id, provider));
             Ok(())
         }
 
+        /// Charge `subscriber` for the period of `subscription_id` which ended. The scheduler
+        /// dispatches this as root every period of an active subscriber.
+        #[weight = SimpleDispatchInfo::FixedOperational(TRANSFER_WEIGHT + 4 * READ_WEIGHT + 2 * WRITE_WEIGHT)]
+        fn process(origin, subscription_id: H256, subscriber: T::AccountId) -> Result {
+            ensure_root(origin)?;
+            Self::process_subscription(subscription_id, subscriber, <system::Module<T>>::block_number())
+        }
     }
 }
 
@@ -260,18 +303,16 @@ decl_event!(
     where
         Time = <T as system::Trait>::BlockNumber,
         Price = <T as balances::Trait>::Balance,
-        Lender = <T as system::Trait>::AccountId,
-        Borrower = <T as system::Trait>::AccountId,
-        Redeemed = <T as balances::Trait>::Balance,
-        Before = <T as balances::Trait>::Balance,
-        After = <T as balances::Trait>::Balance,
+        Provider = <T as system::Trait>::AccountId,
+        Subscriber = <T as system::Trait>::AccountId,
     {
-        LoanRequested(Lender, Borrower, H256),
-        LoanApproved(Lender, Borrower, Time),
-        LoanRedeemed(Lender, Borrower, Redeemed),
-        LoanRepossessed(Lender, Borrower, Price),
-        LoanAmountIncreased(H256, Before, After, Time),
-        BondTransferRequested(H256, Lender, Price),
-        BondTransferApproved(H256, Lender),
+        SubscriptionCreated(H256, Provider, Price, Time),
+        SubscriptionCancelled(H256, Provider),
+        Subscribed(H256, Subscriber),
+        Unsubscribed(H256, Subscriber),
+        SubscriptionPaused(H256, Subscriber),
+        SubscriptionResumed(H256, Subscriber),
+        SubscriptionPaid(H256, Subscriber, Price, Time),
+        PaymentFailed(H256, Subscriber, Price),
     }
 );
@@ -0,0 +1,123 @@
+//! Tests for the subscription module.
+
+use super::*;
+
+use primitives::H256;
+use sr_primitives::traits::OnInitialize;
+use support::{assert_noop, assert_ok};
+use crate::mock::{Balances, Event, Origin, Scheduler, Subscription, System, Test};
+
+/// Account 1 provides plans, accounts 2 and 3 subscribe to them.
+fn new_test_ext() -> runtime_io::TestExternalities {
+	crate::mock::new_test_ext(vec![(1, 100), (2, 100), (3, 15)], vec![])
+}
+
+/// Create a plan of account 1 which charges 10 every 5 blocks, and return its id.
+fn create_plan() -> H256 {
+	let id = BlakeTwo256::hash_of(&(b"subscription", 1u64, Subscription::nonce()));
+	assert_ok!(Subscription::create(Origin::signed(1), b"news".to_vec(), 10, 5));
+	assert!(<Subscriptions<Test>>::exists(id));
+	id
+}
+
+fn status(id: H256, subscriber: u64) -> Option<Status> {
+	Subscription::subscriber((id, subscriber)).map(|info| info.status)
+}
+
+/// Advance to block `n`, initializing every block on the way.
+fn run_to_block(n: u64) {
+	while System::block_number() < n {
+		System::set_block_number(System::block_number() + 1);
+		Scheduler::on_initialize(System::block_number());
+	}
+}
+
+fn subscription_events() -> Vec<RawEvent<u64, u64, u64, u64>> {
+	System::events().into_iter()
+		.filter_map(|record| match record.event {
+			Event::subscription(event) => Some(event),
+			_ => None,
+		})
+		.collect()
+}
+
+#[test]
+fn subscribers_are_charged_every_period() {
+	new_test_ext().execute_with(|| {
+		let id = create_plan();
+		assert_ok!(Subscription::subscribe(Origin::signed(2), id));
+		assert_noop!(Subscription::subscribe(Origin::signed(2), id), "You are already subscribed");
+		assert_eq!(Balances::free_balance(2), 90);
+		run_to_block(5);
+		assert_eq!(Balances::free_balance(2), 90);
+		run_to_block(6);
+		assert_eq!(Balances::free_balance(2), 80);
+		run_to_block(11);
+		assert_eq!(Balances::free_balance(2), 70);
+		assert_eq!(Balances::free_balance(1), 130);
+		assert!(subscription_events().contains(&RawEvent::SubscriptionPaid(id, 2, 10, 16)));
+	});
+}
+
+#[test]
+fn paused_subscribers_are_not_charged() {
+	new_test_ext().execute_with(|| {
+		let id = create_plan();
+		assert_ok!(Subscription::subscribe(Origin::signed(2), id));
+		assert_ok!(Subscription::pause(Origin::signed(2), id));
+		assert_eq!(status(id, 2), Some(Status::Paused));
+		run_to_block(11);
+		assert_eq!(Balances::free_balance(2), 90);
+		assert_ok!(Subscription::resume(Origin::signed(2), id));
+		assert_eq!(Balances::free_balance(2), 80);
+		run_to_block(16);
+		assert_eq!(Balances::free_balance(2), 70);
+	});
+}
+
+#[test]
+fn failed_charge_suspends_the_subscriber() {
+	new_test_ext().execute_with(|| {
+		let id = create_plan();
+		assert_ok!(Subscription::subscribe(Origin::signed(3), id));
+		run_to_block(6);
+		assert_eq!(Balances::free_balance(3), 5);
+		assert_eq!(status(id, 3), Some(Status::Suspended));
+		assert!(subscription_events().contains(&RawEvent::PaymentFailed(id, 3, 10)));
+		assert_eq!(Scheduler::lookup(Subscription::charge_task(id, &3)), None);
+
+		let _ = Balances::deposit_creating(&3, 5);
+		assert_ok!(Subscription::resume(Origin::signed(3), id));
+		assert_eq!(status(id, 3), Some(Status::Active));
+		assert_eq!(Balances::free_balance(3), 0);
+	});
+}
+
+#[test]
+fn cancel_removes_subscribers_and_their_charges() {
+	new_test_ext().execute_with(|| {
+		let id = create_plan();
+		assert_ok!(Subscription::subscribe(Origin::signed(2), id));
+		assert_ok!(Subscription::subscribe(Origin::signed(3), id));
+		assert_noop!(Subscription::cancel(Origin::signed(2), id), "You are not the provider of this subscription");
+		assert_ok!(Subscription::cancel(Origin::signed(1), id));
+		assert!(!<Subscriptions<Test>>::exists(id));
+		assert_eq!(status(id, 2), None);
+		assert_eq!(Scheduler::lookup(Subscription::charge_task(id, &2)), None);
+		run_to_block(6);
+		assert_eq!(Balances::free_balance(2), 90);
+		assert_eq!(Balances::free_balance(3), 5);
+	});
+}
+
+#[test]
+fn subscribers_are_limited() {
+	new_test_ext().execute_with(|| {
+		let id = create_plan();
+		<Subscriptions<Test>>::mutate(id, |s| s.subscribers = (100..100 + MAX_SUBSCRIBERS as u64).collect());
+		assert_noop!(
+			Subscription::subscribe(Origin::signed(2), id),
+			"The subscription has the maximum number of subscribers"
+		);
+	});
+}
@@ -96,5 +96,8 @@ pub fn config(support_changes_trie: bool, code: Option<&[u8]>) -> GenesisConfig
 		membership_Instance1: Some(Default::default()),
 		elections: Some(Default::default()),
 		sudo: Some(Default::default()),
+		generic_asset: Some(Default::default()),
+		did: Some(Default::default()),
+		loan: Some(Default::default()),
 	}
 }
@@ -18,7 +18,8 @@
 
 #![cfg(test)]
 
-use crate::mock::{Aura, new_test_ext};
+use inherents::{InherentData, ProvideInherent};
+use crate::{INHERENT_IDENTIFIER, mock::{Aura, Test, new_test_ext}};
 
 #[test]
 fn initial_values() {
@@ -27,3 +28,19 @@ fn initial_values() {
 		assert_eq!(Aura::authorities().len(), 4);
 	});
 }
+
+#[test]
+fn timestamp_is_checked_against_the_slot() {
+	new_test_ext(vec![0, 1, 2, 3]).execute_with(|| {
+		// slots are two milliseconds long in the mock.
+		let set = timestamp::Call::<Test>::set(10);
+		let mut data = InherentData::new();
+		assert!(Aura::check_inherent(&set, &data).is_err());
+
+		data.put_data(INHERENT_IDENTIFIER, &5u64).unwrap();
+		assert!(Aura::check_inherent(&set, &data).is_ok());
+
+		data.replace_data(INHERENT_IDENTIFIER, &6u64);
+		assert!(Aura::check_inherent(&set, &data).is_err());
+	});
+}
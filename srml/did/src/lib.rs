@@ -0,0 +1,867 @@
+// Copyright 2019 Parity Technologies (UK) Ltd.
+// This file is part of Substrate.
+
+// Substrate is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Substrate is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Substrate.  If not, see <http://www.gnu.org/licenses/>.
+
+//! # DID Module
+//!
+//! A module for decentralised identifiers. A DID is registered by an issuer, which is either
+//! a root issuer or the owner of another DID, for an owner account, and keeps a history of
+//! the keys it was controlled with.
+//!
+//! The issuer reserves a deposit in `Currency` for the storage of the DID. It stays reserved
+//! after the DID is deactivated, because the record is kept as a tombstone. DIDs can be handed over to a set of controllers, which approve
+//! actions on it by a threshold, and can dispatch calls with the `Did` origin of this module.
+//!
+//! DIDs can be registered at genesis with the `ids` of the `GenesisConfig`. No deposit is
+//! taken for them until their keys change.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use support::{decl_module, decl_storage, decl_event, dispatch::Result, ensure, Parameter};
+use support::dispatch::Dispatchable;
+use support::traits::{Currency, Get, ReservableCurrency};
+use primitives::H256;
+use sr_primitives::weights::{ClassifyDispatch, DispatchClass, GetDispatchInfo, SimpleDispatchInfo, WeighData, Weight, READ_WEIGHT, WRITE_WEIGHT};
+use codec::{Encode, Decode};
+use system::{ensure_signed};
+use rstd::prelude::*;
+use rstd::{convert::TryFrom, result};
+use primitives::{ed25519, sr25519};
+use sr_primitives::traits::{BlakeTwo256, EnsureOrigin, Hash, Saturating, Verify, Zero};
+
+/// Maximum number of issuers followed when resolving the issuer chain of a DID.
+const MAX_ISSUER_DEPTH: usize = 16;
+
+/// Maximum number of controllers of a DID.
+const MAX_CONTROLLERS: usize = 16;
+
+/// Weight of checking a signature against the keys of a DID.
+const VERIFY_WEIGHT: Weight = 250_000;
+
+/// Domain of the payloads signed for `call_as_did`.
+const CALL_DOMAIN: &[u8] = b"did:call";
+/// Domain of the payloads signed for `approve_as_did`.
+const APPROVE_DOMAIN: &[u8] = b"did:approve";
+
+type BalanceOf<T> = <<T as Trait>::Currency as Currency<<T as system::Trait>::AccountId>>::Balance;
+
+/// Weight of `call_as_did`, which is the weight of checking the signature plus the weight of
+/// the dispatched call.
+struct WeightForCallAsDid(Weight);
+
+impl<Call: GetDispatchInfo> WeighData<(&DIDIdentifier, &Box<Call>, &Vec<u8>)> for WeightForCallAsDid {
+    fn weigh_data(&self, (_, call, _): (&DIDIdentifier, &Box<Call>, &Vec<u8>)) -> Weight {
+        self.0.saturating_add(call.get_dispatch_info().weight)
+    }
+}
+
+impl<Call> ClassifyDispatch<(&DIDIdentifier, &Box<Call>, &Vec<u8>)> for WeightForCallAsDid {
+    fn classify_dispatch(&self, _: (&DIDIdentifier, &Box<Call>, &Vec<u8>)) -> DispatchClass {
+        DispatchClass::Normal
+    }
+}
+
+/*
+#[derive(Encode, Decode, Clone, Eq, PartialEq)]
+pub enum Status {
+    /// Allowing access
+    Yes,
+    /// Blocked
+    Blocked,
+    /// Temporary
+    Temporary(u64)
+}
+
+impl Default for Status {
+    fn default() -> Self { Status::Yes }
+}
+*/
+
+//pub type Id = Vec<u8>;
+
+/// Data storage type for each account
+#[derive(Encode, Decode, Default, Clone, Eq, PartialEq)]
+#[cfg_attr(feature = "std", derive(Debug))]
+pub struct DID {
+    /// public key to authenticate
+    pub public_key: Vec<u8>,
+    /// issuer did identifier
+    pub issuer_id: Vec<u8>,
+    /// hash from issuer tx public key
+    pub issuer: H256,
+    /// hash from owner tx public key
+    pub owner: H256 
+}
+
+impl DID {
+    pub fn new (public_key: Vec<u8>, issuer_id: Option<Vec<u8>>, issuer: H256, owner: H256) -> Self {
+        if issuer_id.is_some() {
+            DID {
+                public_key,
+                issuer_id: issuer_id.unwrap(),
+                issuer,
+                owner
+            }
+        } else {
+            DID {
+                public_key,
+                issuer_id: vec!{1},
+                issuer,
+                owner
+            }
+        }
+    }
+}
+
+/// An account or another DID which controls a DID.
+#[derive(Encode, Decode, Clone, Eq, PartialEq)]
+#[cfg_attr(feature = "std", derive(Debug))]
+pub enum Controller<AccountId> {
+    Account(AccountId),
+    Did(DIDIdentifier),
+}
+
+/// The controllers of a DID and the number of them needed to approve an action.
+#[derive(Encode, Decode, Clone, Eq, PartialEq)]
+#[cfg_attr(feature = "std", derive(Debug))]
+pub struct ControllerSet<AccountId> {
+    pub controllers: Vec<Controller<AccountId>>,
+    pub threshold: u32,
+}
+
+/// An action on a DID which needs the approval of its controllers.
+#[derive(Encode, Decode, Clone, Eq, PartialEq)]
+#[cfg_attr(feature = "std", derive(Debug))]
+pub enum ControllerAction<AccountId> {
+    /// Rotate the primary authentication key, as `update` does.
+    Update(PublicKey),
+    /// Deactivate the DID, as `deactivate` does.
+    Deactivate,
+    /// Replace the controllers and the threshold.
+    SetControllers(Vec<Controller<AccountId>>, u32),
+    /// Add a key, as `add_key` does.
+    AddKey(KeyPurpose, KeyType, PublicKey),
+    /// Revoke the key at an index, as `revoke_key` does.
+    RevokeKey(KeyIndex),
+    /// Replace the key at an index, as `rotate_key` does.
+    RotateKey(KeyIndex, PublicKey),
+}
+
+/// Origin for the did module.
+#[derive(PartialEq, Eq, Clone)]
+#[cfg_attr(feature = "std", derive(Debug))]
+pub enum RawOrigin {
+    /// The call was signed by an authentication key of the DID.
+    Did(DIDIdentifier),
+}
+
+/// Origin for the did module.
+pub type Origin = RawOrigin;
+
+/// Ensure that the origin `o` represents a DID. Returns `Ok` with the identifier of the DID
+/// or an `Err` otherwise.
+pub fn ensure_did<OuterOrigin>(o: OuterOrigin) -> result::Result<DIDIdentifier, &'static str>
+    where OuterOrigin: Into<result::Result<RawOrigin, OuterOrigin>>
+{
+    match o.into() {
+        Ok(RawOrigin::Did(id)) => Ok(id),
+        _ => Err("bad origin: expected to be a DID origin"),
+    }
+}
+
+/// `EnsureOrigin` implementation which only accepts calls signed by a key of a DID, so that
+/// other modules can require the approval of a DID instead of an account.
+pub struct EnsureDidController;
+impl<O: Into<result::Result<RawOrigin, O>> + From<RawOrigin>> EnsureOrigin<O> for EnsureDidController {
+    type Success = DIDIdentifier;
+    fn try_origin(o: O) -> result::Result<Self::Success, O> {
+        o.into().and_then(|o| match o {
+            RawOrigin::Did(id) => Ok(id),
+        })
+    }
+}
+
+/// The verification relationship a key is registered for.
+#[derive(Eq, PartialEq, Copy, Clone, Encode, Decode)]
+#[cfg_attr(feature = "std", derive(Debug, serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "std", serde(rename_all = "camelCase"))]
+pub enum KeyPurpose {
+    /// Authenticate as the DID subject.
+    Authentication,
+    /// Issue claims on behalf of the DID subject.
+    Assertion,
+    /// Establish encrypted channels with the DID subject.
+    KeyAgreement,
+    /// Delegate capabilities of the DID subject.
+    Delegation,
+}
+
+/// The signature scheme of a key.
+#[derive(Eq, PartialEq, Copy, Clone, Encode, Decode)]
+#[cfg_attr(feature = "std", derive(Debug, serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "std", serde(rename_all = "camelCase"))]
+pub enum KeyType {
+    /// An Ed25519 public key.
+    Ed25519,
+    /// A Schnorrkel/Ristretto public key.
+    Sr25519,
+    /// An uncompressed secp256k1 public key.
+    Ecdsa,
+}
+
+/// A key registered for a DID.
+#[derive(Eq, PartialEq, Clone, Encode, Decode)]
+#[cfg_attr(feature = "std", derive(Debug))]
+pub struct VerificationMethod {
+    /// Position of the key in the key history of the DID.
+    pub index: u32,
+    /// The relationship the key is registered for.
+    pub purpose: KeyPurpose,
+    /// The signature scheme of the key.
+    pub key_type: KeyType,
+    /// Raw public key bytes.
+    pub public_key: Vec<u8>,
+}
+
+/// An account or another DID which controls a DID.
+#[derive(Eq, PartialEq, Clone, Encode, Decode)]
+#[cfg_attr(feature = "std", derive(Debug))]
+pub enum DidController {
+    /// Hash of a controlling account.
+    Account(H256),
+    /// Method-specific identifier of a controlling DID.
+    Did(Vec<u8>),
+}
+
+/// The resolved state of a DID, independent of any serialization format.
+#[derive(Eq, PartialEq, Clone, Encode, Decode)]
+#[cfg_attr(feature = "std", derive(Debug))]
+pub struct DidDocument {
+    /// The method-specific identifier.
+    pub id: Vec<u8>,
+    /// Keys of the DID which are currently valid.
+    pub verification_methods: Vec<VerificationMethod>,
+    /// Controllers of the DID. Unless the DID is controlled by a controller set, this is the
+    /// DID of the direct issuer, or the DID itself for root issuers.
+    pub controllers: Vec<DidController>,
+    /// Number of controllers needed to approve an update or the deactivation.
+    pub threshold: u32,
+    /// Hash of the account which issued the DID.
+    pub issuer: H256,
+    /// Identifiers of the issuing DIDs, starting with the direct issuer and ending
+    /// with the root issuer. Empty for root issuers.
+    pub issuer_chain: Vec<Vec<u8>>,
+    /// Whether the DID is deactivated.
+    pub deactivated: bool,
+}
+
+/// A key of a DID together with the blocks it is valid for.
+#[derive(Encode, Decode, Clone, Eq, PartialEq)]
+#[cfg_attr(feature = "std", derive(Debug))]
+pub struct VerificationKey<BlockNumber> {
+    /// relationship the key is registered for
+    pub purpose: KeyPurpose,
+    /// signature scheme of the key
+    pub key_type: KeyType,
+    /// raw public key
+    pub public_key: Vec<u8>,
+    /// first block the key is valid at
+    pub valid_from: BlockNumber,
+    /// block the key was revoked or rotated at, the key is not valid from then on
+    pub valid_until: Option<BlockNumber>,
+}
+
+impl<BlockNumber: PartialOrd + Copy> VerificationKey<BlockNumber> {
+    pub fn new(purpose: KeyPurpose, key_type: KeyType, public_key: Vec<u8>, valid_from: BlockNumber) -> Self {
+        VerificationKey {
+            purpose,
+            key_type,
+            public_key,
+            valid_from,
+            valid_until: None,
+        }
+    }
+
+    pub fn is_valid_at(&self, block: BlockNumber) -> bool {
+        self.valid_from <= block && self.valid_until.map_or(true, |until| block < until)
+    }
+
+    pub fn is_revoked(&self) -> bool {
+        self.valid_until.is_some()
+    }
+}
+
+/// Check `signature` over `message` against a raw public key of the given type.
+///
+/// Ecdsa keys are 64 byte uncompressed secp256k1 keys (optionally prefixed with `0x04`),
+/// and the signature is a 65 byte recoverable signature over the blake2 hash of `message`.
+pub fn verify_signature(key_type: KeyType, public_key: &[u8], message: &[u8], signature: &[u8]) -> bool {
+    match key_type {
+        KeyType::Ed25519 => match (ed25519::Signature::try_from(signature), ed25519::Public::try_from(public_key)) {
+            (Ok(signature), Ok(public_key)) => signature.verify(message, &public_key),
+            _ => false,
+        },
+        KeyType::Sr25519 => match (sr25519::Signature::try_from(signature), sr25519::Public::try_from(public_key)) {
+            (Ok(signature), Ok(public_key)) => signature.verify(message, &public_key),
+            _ => false,
+        },
+        KeyType::Ecdsa => {
+            if signature.len() != 65 {
+                return false;
+            }
+            let public_key = match public_key.len() {
+                64 => public_key,
+                65 if public_key[0] == 4 => &public_key[1..],
+                _ => return false,
+            };
+            let mut sig = [0u8; 65];
+            sig.copy_from_slice(signature);
+            match runtime_io::secp256k1_ecdsa_recover(&sig, &runtime_io::blake2_256(message)) {
+                Ok(recovered) => &recovered[..] == public_key,
+                Err(_) => false,
+            }
+        }
+    }
+}
+
+// Module's function and Methods of custom struct to be placed here
+impl<T: Trait> Module<T> {
+    /// The hash `who` is stored as in a DID. 32 byte account ids are kept as they are, and
+    /// other account ids are hashed.
+    pub fn account_hash(who: &T::AccountId) -> H256 {
+        let encoded = who.encode();
+        if encoded.len() == 32 {
+            H256::from_slice(&encoded)
+        } else {
+            BlakeTwo256::hash(&encoded)
+        }
+    }
+
+    pub fn is_id_issuer(id:Vec<u8>, issuer: T::AccountId) -> bool {
+        let access_who = Self::id(id.clone());
+        let issuer_hash = Self::account_hash(&issuer);
+        access_who.issuer == issuer_hash    
+    }
+
+    pub fn is_id_owner(id: Vec<u8>, owner: T::AccountId) -> bool {
+        let owner_who = Self::id(id.clone());
+        let owner_hash = Self::account_hash(&owner);
+        owner_who.owner == owner_hash    
+    }
+
+    /// Whether `id` is registered and not deactivated.
+    pub fn is_active(id: Vec<u8>) -> bool {
+        <IDs>::exists(id.clone()) && Self::deactivated_at(id).is_none()
+    }
+
+    /// Whether `who` approves actions of `id` on its own, which is the case for the
+    /// issuer of DIDs without a controller set.
+    fn is_sole_controller(id: Vec<u8>, who: T::AccountId) -> bool {
+        Self::controllers(id.clone()).is_none() && Self::is_id_issuer(id, who)
+    }
+
+    fn ensure_controller_set(controllers: &[Controller<T::AccountId>], threshold: u32) -> Result {
+        ensure!(controllers.len() <= MAX_CONTROLLERS, "Too many controllers");
+        ensure!(threshold > 0, "The threshold should be at least one");
+        ensure!(threshold as usize <= controllers.len(), "The threshold is larger than the number of controllers");
+        for (i, controller) in controllers.iter().enumerate() {
+            ensure!(!controllers[..i].contains(controller), "Duplicate controller");
+            if let Controller::Did(id) = controller {
+                ensure!(Self::is_active(id.clone()), "Controller DID is not registered or deactivated");
+            }
+        }
+        Ok(())
+    }
+
+    /// Hand `id` over to `controllers`. Pending approvals are dropped, so that approvals of
+    /// former controllers never count.
+    fn set_controller_set(id: Vec<u8>, controllers: Vec<Controller<T::AccountId>>, threshold: u32) {
+        <Controllers<T>>::insert(id.clone(), ControllerSet { controllers, threshold });
+        <Approvals<T>>::remove_prefix(&id);
+        Self::deposit_event(RawEvent::ControllersChanged(id, threshold));
+    }
+
+    /// Record the approval of `action` on `id` by `controller`, and execute the action once
+    /// the threshold of the controller set is reached.
+    fn approve_action(id: Vec<u8>, controller: Controller<T::AccountId>, action: ControllerAction<T::AccountId>, sender: T::AccountId) -> Result {
+        let set = Self::controllers(id.clone()).ok_or("DID is not controlled by a controller set")?;
+        ensure!(set.controllers.contains(&controller), "You are not a controller of this identity");
+        if let ControllerAction::SetControllers(controllers, threshold) = &action {
+            Self::ensure_controller_set(controllers, *threshold)?;
+        }
+        let action_hash = BlakeTwo256::hash_of(&action);
+        let mut approvals = Self::approvals(&id, &action_hash);
+        ensure!(!approvals.contains(&controller), "You already approved this action");
+        approvals.retain(|approver| set.controllers.contains(approver));
+        approvals.push(controller);
+        let count = approvals.len() as u32;
+        if count < set.threshold {
+            <Approvals<T>>::insert(&id, &action_hash, approvals);
+            Self::deposit_event(RawEvent::ActionApproved(id, action_hash, count));
+            return Ok(());
+        }
+        match action {
+            ControllerAction::Update(public_key) => Self::do_update(id.clone(), public_key, sender)?,
+            ControllerAction::Deactivate => Self::do_deactivate(id.clone(), sender),
+            ControllerAction::SetControllers(controllers, threshold) =>
+                Self::set_controller_set(id.clone(), controllers, threshold),
+            ControllerAction::AddKey(purpose, key_type, public_key) =>
+                Self::do_add_key(id.clone(), purpose, key_type, public_key, sender)?,
+            ControllerAction::RevokeKey(index) => Self::do_revoke_key(id.clone(), index)?,
+            ControllerAction::RotateKey(index, public_key) =>
+                Self::do_rotate_key(id.clone(), index, public_key, sender)?,
+        }
+        <Approvals<T>>::remove(&id, &action_hash);
+        Self::deposit_event(RawEvent::ActionApproved(id.clone(), action_hash, count));
+        Self::deposit_event(RawEvent::ActionExecuted(id, action_hash));
+        Ok(())
+    }
+
+    fn do_add_key(id: Vec<u8>, purpose: KeyPurpose, key_type: KeyType, public_key: PublicKey, who: T::AccountId) -> Result {
+        let mut keys = Self::keys_of(id.clone());
+        ensure!(!keys.iter().any(|key| key.public_key == public_key && !key.is_revoked()), "The key is already registered");
+        let current_block = <system::Module<T>>::block_number();
+        keys.push(VerificationKey::new(purpose, key_type, public_key.clone(), current_block));
+        let index = (keys.len() - 1) as KeyIndex;
+        Self::reserve_deposit(id.clone(), &Self::id(id.clone()), &keys, &who)?;
+        <Keys<T>>::insert(id.clone(), keys);
+        Self::deposit_event(RawEvent::KeyAdded(id, index, purpose, public_key));
+        Ok(())
+    }
+
+    fn do_revoke_key(id: Vec<u8>, index: KeyIndex) -> Result {
+        let mut keys = Self::keys_of(id.clone());
+        ensure!((index as usize) < keys.len(), "The key does not exist");
+        ensure!(!keys[index as usize].is_revoked(), "The key is already revoked");
+        keys[index as usize].valid_until = Some(<system::Module<T>>::block_number());
+        <Keys<T>>::insert(id.clone(), keys);
+        Self::deposit_event(RawEvent::KeyRevoked(id, index));
+        Ok(())
+    }
+
+    fn do_rotate_key(id: Vec<u8>, index: KeyIndex, public_key: PublicKey, who: T::AccountId) -> Result {
+        let mut keys = Self::keys_of(id.clone());
+        ensure!((index as usize) < keys.len(), "The key does not exist");
+        ensure!(!keys[index as usize].is_revoked(), "The key is already revoked");
+        let current_block = <system::Module<T>>::block_number();
+        keys[index as usize].valid_until = Some(current_block);
+        let rotated = VerificationKey::new(keys[index as usize].purpose, keys[index as usize].key_type, public_key.clone(), current_block);
+        keys.push(rotated);
+        let new_index = (keys.len() - 1) as KeyIndex;
+        Self::reserve_deposit(id.clone(), &Self::id(id.clone()), &keys, &who)?;
+        <Keys<T>>::insert(id.clone(), keys);
+        Self::deposit_event(RawEvent::KeyRotated(id, index, new_index, public_key));
+        Ok(())
+    }
+
+    /// Rotate the primary authentication key of `id`, keeping the old one in the key history.
+    fn do_update(id: Vec<u8>, public_key: PublicKey, who: T::AccountId) -> Result {
+        let current_block = <system::Module<T>>::block_number();
+        let mut keys = Self::keys_of(id.clone());
+        let primary = keys.iter().position(|key| key.purpose == KeyPurpose::Authentication && !key.is_revoked());
+        let key_type = match primary {
+            Some(index) => {
+                keys[index].valid_until = Some(current_block);
+                keys[index].key_type
+            }
+            None => KeyType::Sr25519,
+        };
+        keys.push(VerificationKey::new(KeyPurpose::Authentication, key_type, public_key.clone(), current_block));
+        let mut did = Self::id(id.clone());
+        did.public_key = public_key.clone();
+        Self::reserve_deposit(id.clone(), &did, &keys, &who)?;
+        <Keys<T>>::insert(id.clone(), keys);
+
+        // Update DID 
+        <IDs>::insert(id.clone(), did);
+        Self::deposit_event(RawEvent::IdChanged(id, public_key, who));
+        Ok(())
+    }
+
+    /// Deactivate `id`. The record is kept as a tombstone, so that the identifier can never
+    /// be registered again, and all of its keys are revoked. The deposit for the record stays
+    /// reserved for as long as the tombstone is stored.
+    fn do_deactivate(id: Vec<u8>, who: T::AccountId) {
+        let current_block = <system::Module<T>>::block_number();
+        let mut keys = Self::keys_of(id.clone());
+        for key in keys.iter_mut().filter(|key| !key.is_revoked()) {
+            key.valid_until = Some(current_block);
+        }
+        <Keys<T>>::insert(id.clone(), keys);
+        <Deactivated<T>>::insert(id.clone(), current_block);
+        <PendingOwners<T>>::remove(id.clone());
+        <Approvals<T>>::remove_prefix(&id);
+        Self::deposit_event(RawEvent::IdDeactivated(id, who));
+    }
+
+    /// Deposit needed for storing `did` and `keys` under `id`.
+    pub fn deposit_for(id: &[u8], did: &DID, keys: &[VerificationKey<T::BlockNumber>]) -> BalanceOf<T> {
+        let bytes = id.len() + did.encode().len() + keys.encode().len();
+        T::ByteDeposit::get().saturating_mul(BalanceOf::<T>::from(bytes as u32))
+    }
+
+    /// Reserve the difference between the deposit held for `id` and the deposit needed for
+    /// `did` and `keys`, before they are written. The deposit is taken from the account which
+    /// registered the DID, or from `who` for DIDs registered before deposits were taken.
+    fn reserve_deposit(id: Vec<u8>, did: &DID, keys: &[VerificationKey<T::BlockNumber>], who: &T::AccountId) -> Result {
+        let required = Self::deposit_for(&id, did, keys);
+        let (depositor, held) = Self::deposit(id.clone()).unwrap_or_else(|| (who.clone(), Zero::zero()));
+        if required > held {
+            T::Currency::reserve(&depositor, required - held)?;
+        } else {
+            T::Currency::unreserve(&depositor, held - required);
+        }
+        <Deposits<T>>::insert(id, (depositor, required));
+        Ok(())
+    }
+
+    /// Identifiers of the issuers of `id`, from its direct issuer up to the root issuer.
+    pub fn issuer_chain(id: Vec<u8>) -> Vec<Vec<u8>> {
+        let mut chain = Vec::new();
+        let mut current = Self::id(id);
+        while current.issuer_id != [1] && chain.len() < MAX_ISSUER_DEPTH {
+            let issuer_id = current.issuer_id.clone();
+            chain.push(issuer_id.clone());
+            if !<IDs>::exists(issuer_id.clone()) {
+                break;
+            }
+            current = Self::id(issuer_id);
+        }
+        chain
+    }
+
+    /// Key history of `id`.
+    ///
+    /// DIDs registered before keys were tracked only have the `public_key` of the DID,
+    /// which is treated as an sr25519 authentication key valid since genesis.
+    pub fn keys_of(id: Vec<u8>) -> Vec<VerificationKey<T::BlockNumber>> {
+        let keys = Self::keys(id.clone());
+        if keys.is_empty() && <IDs>::exists(id.clone()) {
+            let did = Self::id(id);
+            vec![VerificationKey::new(KeyPurpose::Authentication, KeyType::Sr25519, did.public_key, T::BlockNumber::from(0))]
+        } else {
+            keys
+        }
+    }
+
+    /// Check that `signature` over `message` was made with a key of `id` which was
+    /// registered for `purpose` and valid at block `at`.
+    pub fn verify_at(id: Vec<u8>, purpose: KeyPurpose, message: &[u8], signature: &[u8], at: T::BlockNumber) -> bool {
+        Self::keys_of(id).iter()
+            .filter(|key| key.purpose == purpose && key.is_valid_at(at))
+            .any(|key| verify_signature(key.key_type, &key.public_key, message, signature))
+    }
+
+    /// The payload which has to be signed by an authentication key of `id` to dispatch
+    /// `call` through `call_as_did`.
+    ///
+    /// It is tied to this chain by the genesis hash, and to `call_as_did` by a domain tag.
+    pub fn signing_payload(id: DIDIdentifier, nonce: u64, call: &<T as Trait>::Proposal) -> Vec<u8> {
+        let genesis_hash = <system::Module<T>>::block_hash(T::BlockNumber::zero());
+        (CALL_DOMAIN, genesis_hash, id, nonce, call).encode()
+    }
+
+    /// The payload which has to be signed by an authentication key of `controller` to
+    /// approve `action` on `id` through `approve_as_did`.
+    pub fn approval_payload(id: DIDIdentifier, controller: DIDIdentifier, nonce: u64, action: &ControllerAction<T::AccountId>) -> Vec<u8> {
+        let genesis_hash = <system::Module<T>>::block_hash(T::BlockNumber::zero());
+        (APPROVE_DOMAIN, genesis_hash, id, controller, nonce, action).encode()
+    }
+
+    /// Resolve `id` into a DID document, or `None` if it is not registered.
+    pub fn document(id: Vec<u8>) -> Option<DidDocument> {
+        if !<IDs>::exists(id.clone()) {
+            return None;
+        }
+        let did = Self::id(id.clone());
+        let current_block = <system::Module<T>>::block_number();
+        let verification_methods = Self::keys_of(id.clone()).into_iter()
+            .enumerate()
+            .filter(|(_, key)| key.is_valid_at(current_block))
+            .map(|(index, key)| VerificationMethod {
+                index: index as u32,
+                purpose: key.purpose,
+                key_type: key.key_type,
+                public_key: key.public_key,
+            })
+            .collect();
+        let issuer_chain = Self::issuer_chain(id.clone());
+        Some(DidDocument {
+            id: id.clone(),
+            verification_methods,
+            controllers: match Self::controllers(id.clone()) {
+                Some(set) => set.controllers.into_iter()
+                    .map(|controller| match controller {
+                        Controller::Account(account) => DidController::Account(Self::account_hash(&account)),
+                        Controller::Did(id) => DidController::Did(id),
+                    })
+                    .collect(),
+                // the issuer account, which also controls the issuing DID, updates the DID.
+                None => vec![DidController::Did(issuer_chain.first().cloned().unwrap_or_else(|| id.clone()))],
+            },
+            threshold: Self::controllers(id.clone()).map_or(1, |set| set.threshold),
+            issuer: did.issuer,
+            issuer_chain,
+            deactivated: Self::deactivated_at(id).is_some(),
+        })
+    }
+}
+
+/// The module's configuration trait.
+pub trait Trait: system::Trait {
+	// TODO: Add other types and constants required configure this module.
+
+	/// The overarching event type.
+	type Event: From<Event<Self>> + Into<<Self as system::Trait>::Event>;
+
+	/// The outer origin type.
+	type Origin: From<RawOrigin>;
+
+	/// The outer call dispatch type.
+	type Proposal: Parameter + Dispatchable<Origin=<Self as Trait>::Origin> + GetDispatchInfo;
+
+	/// The currency the storage deposit of a DID is reserved in.
+	type Currency: ReservableCurrency<Self::AccountId>;
+
+	/// The deposit reserved per byte of storage used by a DID.
+	type ByteDeposit: Get<BalanceOf<Self>>;
+}
+
+
+// This module's storage items.
+decl_storage! {
+	trait Store for Module<T: Trait> as DID {
+        pub IDs get(id): map Vec<u8> => DID;
+        pub Keys get(keys): map Vec<u8> => Vec<VerificationKey<T::BlockNumber>>;
+        pub Nonces get(nonce): map Vec<u8> => u64;
+        pub Deactivated get(deactivated_at): map Vec<u8> => Option<T::BlockNumber>;
+        pub PendingOwners get(pending_owner): map Vec<u8> => Option<T::AccountId>;
+        pub Controllers get(controllers): map Vec<u8> => Option<ControllerSet<T::AccountId>>;
+        /// Controllers which approved each pending action on a DID.
+        pub Approvals get(approvals): double_map Vec<u8>, blake2_256(H256) => Vec<Controller<T::AccountId>>;
+        pub Deposits get(deposit): map Vec<u8> => Option<(T::AccountId, BalanceOf<T>)>;
+    }
+    add_extra_genesis {
+        // `(id, issuer_id, public_key, issuer, owner)`, where an `issuer_id` of `[0]`
+        // registers a root issuer as `register` does.
+        config(ids): Vec<(DIDIdentifier, DIDIdentifier, PublicKey, T::AccountId, T::AccountId)>;
+        build(|config: &GenesisConfig<T>| {
+            for (id, issuer_id, public_key, issuer, owner) in &config.ids {
+                assert!(!<IDs>::exists(id), "DID registered twice at genesis");
+                let issuer_id = if *issuer_id == [0] { None } else { Some(issuer_id.clone()) };
+                let did = DID::new(public_key.clone(), issuer_id, <Module<T>>::account_hash(issuer), <Module<T>>::account_hash(owner));
+                let key = VerificationKey::new(KeyPurpose::Authentication, KeyType::Sr25519, public_key.clone(), T::BlockNumber::from(0));
+                <IDs>::insert(id, did);
+                <Keys<T>>::insert(id, vec![key]);
+            }
+        });
+    }
+}
+
+// The module's dispatchable functions.
+decl_module! {
+	/// The module declaration.
+	pub struct Module<T: Trait> for enum Call where origin: <T as system::Trait>::Origin {
+		// Initializing events
+		// this is needed only if you are using events in your module
+		fn deposit_event() = default;
+
+        /// The deposit reserved per byte of storage used by a DID.
+        const ByteDeposit: BalanceOf<T> = T::ByteDeposit::get();
+
+        /// Register `id`. The sender reserves a deposit for the storage of the DID, which stays
+        /// reserved for its tombstone after the DID is deactivated.
+        #[weight = SimpleDispatchInfo::FixedNormal(3 * READ_WEIGHT + 4 * WRITE_WEIGHT)]
+        pub fn register(origin, id: DIDIdentifier, issuer_id: DIDIdentifier, public_key: PublicKey, owner: T::AccountId) -> Result {
+            let issuer = ensure_signed(origin)?;
+            let issuer_hash = Self::account_hash(&issuer);
+            let owner_hash = Self::account_hash(&owner);
+            ensure!(!<IDs>::exists(id.clone()), "The id is already issued");
+            let current_block = <system::Module<T>>::block_number();
+            let did_claimer = if issuer_id == [0] {
+                DID::new(public_key.clone(), None, issuer_hash.clone(), owner_hash.clone())
+            } else {
+                ensure!(Self::is_active(issuer_id.clone()), "Issuer DID is not registered or deactivated");
+                ensure!(Self::is_id_issuer(issuer_id.clone(), issuer.clone()), "You are not the owner of this issuer_did");
+                DID::new(public_key.clone(), Some(issuer_id.clone()), issuer_hash.clone(), owner_hash.clone())
+            };
+            let keys = vec![VerificationKey::new(KeyPurpose::Authentication, KeyType::Sr25519, public_key, current_block)];
+            let deposit = Self::deposit_for(&id, &did_claimer, &keys);
+            T::Currency::reserve(&issuer, deposit)?;
+            <Deposits<T>>::insert(id.clone(), (issuer, deposit));
+            <IDs>::insert(id.clone(), did_claimer);
+            <Keys<T>>::insert(id.clone(), keys);
+            if issuer_id == [0] {
+                Self::deposit_event(RawEvent::IdIssuerRegistered(id, owner));
+            } else {
+                Self::deposit_event(RawEvent::IdIssued(id, owner, issuer_id));
+            }
+            Ok(())
+        }
+
+        /// Deactivate the DID. The identifier stays reserved and can not be registered again.
+        #[weight = SimpleDispatchInfo::FixedNormal(6 * READ_WEIGHT + 5 * WRITE_WEIGHT)]
+        pub fn deactivate(origin, id: DIDIdentifier) -> Result {
+            let issuer = ensure_signed(origin)?;
+            ensure!(Self::is_active(id.clone()), "DID is not registered or deactivated");
+            ensure!(Self::is_sole_controller(id.clone(), issuer.clone()), "You are not the issuer of this identity or it is controlled by a controller set");
+            Self::do_deactivate(id, issuer);
+            Ok(())
+        }
+
+        #[weight = SimpleDispatchInfo::FixedNormal(6 * READ_WEIGHT + 4 * WRITE_WEIGHT)]
+        pub fn update(origin, id: DIDIdentifier, public_key: PublicKey) -> Result {
+            let issuer = ensure_signed(origin)?;
+            ensure!(Self::is_active(id.clone()), "DID is not registered or deactivated");
+            ensure!(Self::is_sole_controller(id.clone(), issuer.clone()), "You are not the issuer of this identity or it is controlled by a controller set");
+            Self::do_update(id, public_key, issuer)
+        }
+
+        /// Propose `new_owner` as the owner of the DID. The transfer completes once the new
+        /// owner calls `accept_owner`.
+        #[weight = SimpleDispatchInfo::FixedNormal(3 * READ_WEIGHT + WRITE_WEIGHT)]
+        pub fn propose_owner(origin, id: DIDIdentifier, new_owner: T::AccountId) -> Result {
+            let owner = ensure_signed(origin)?;
+            ensure!(Self::is_active(id.clone()), "DID is not registered or deactivated");
+            ensure!(Self::is_id_owner(id.clone(), owner), "You are not the owner of this identity");
+            <PendingOwners<T>>::insert(id.clone(), new_owner.clone());
+            Self::deposit_event(RawEvent::OwnerProposed(id, new_owner));
+            Ok(())
+        }
+
+        #[weight = SimpleDispatchInfo::FixedNormal(3 * READ_WEIGHT + 2 * WRITE_WEIGHT)]
+        pub fn accept_owner(origin, id: DIDIdentifier) -> Result {
+            let new_owner = ensure_signed(origin)?;
+            ensure!(Self::is_active(id.clone()), "DID is not registered or deactivated");
+            ensure!(Self::pending_owner(id.clone()) == Some(new_owner.clone()), "You are not the proposed owner of this identity");
+            <PendingOwners<T>>::remove(id.clone());
+            <IDs>::mutate(id.clone(), |a| a.owner = Self::account_hash(&new_owner));
+            Self::deposit_event(RawEvent::OwnerChanged(id, new_owner));
+            Ok(())
+        }
+
+        /// Hand control over `update`, `deactivate` and the keys to `threshold` of `controllers`.
+        ///
+        /// Afterwards the controllers change the set themselves through `approve`.
+        #[weight = SimpleDispatchInfo::FixedNormal((4 + 2 * MAX_CONTROLLERS as Weight) * READ_WEIGHT + WRITE_WEIGHT)]
+        pub fn set_controllers(origin, id: DIDIdentifier, controllers: Vec<Controller<T::AccountId>>, threshold: u32) -> Result {
+            let issuer = ensure_signed(origin)?;
+            ensure!(Self::is_active(id.clone()), "DID is not registered or deactivated");
+            ensure!(Self::is_sole_controller(id.clone(), issuer), "You are not the issuer of this identity or it is controlled by a controller set");
+            Self::ensure_controller_set(&controllers, threshold)?;
+            Self::set_controller_set(id, controllers, threshold);
+            Ok(())
+        }
+
+        /// Approve `action` on the DID as an account controller.
+        #[weight = SimpleDispatchInfo::FixedNormal((7 + 2 * MAX_CONTROLLERS as Weight) * READ_WEIGHT + 6 * WRITE_WEIGHT)]
+        pub fn approve(origin, id: DIDIdentifier, action: ControllerAction<T::AccountId>) -> Result {
+            let sender = ensure_signed(origin)?;
+            ensure!(Self::is_active(id.clone()), "DID is not registered or deactivated");
+            Self::approve_action(id, Controller::Account(sender.clone()), action, sender)
+        }
+
+        /// Approve `action` on the DID as the controller DID `controller`.
+        ///
+        /// `signature` must be made over `approval_payload(id, controller, nonce, action)` with
+        /// a currently valid authentication key of `controller`, where `nonce` is the current
+        /// nonce of `controller`.
+        #[weight = SimpleDispatchInfo::FixedNormal((10 + 2 * MAX_CONTROLLERS as Weight) * READ_WEIGHT + 7 * WRITE_WEIGHT + VERIFY_WEIGHT)]
+        pub fn approve_as_did(origin, id: DIDIdentifier, controller: DIDIdentifier, action: ControllerAction<T::AccountId>, signature: Vec<u8>) -> Result {
+            let sender = ensure_signed(origin)?;
+            ensure!(Self::is_active(id.clone()), "DID is not registered or deactivated");
+            ensure!(Self::is_active(controller.clone()), "Controller DID is not registered or deactivated");
+            let nonce = Self::nonce(controller.clone());
+            let payload = Self::approval_payload(id.clone(), controller.clone(), nonce, &action);
+            let current_block = <system::Module<T>>::block_number();
+            ensure!(Self::verify_at(controller.clone(), KeyPurpose::Authentication, &payload, &signature, current_block), "The signature is not valid for the controller DID");
+            <Nonces>::insert(controller.clone(), nonce + 1);
+            Self::approve_action(id, Controller::Did(controller), action, sender)
+        }
+
+        #[weight = SimpleDispatchInfo::FixedNormal(6 * READ_WEIGHT + 3 * WRITE_WEIGHT)]
+        pub fn add_key(origin, id: DIDIdentifier, purpose: KeyPurpose, key_type: KeyType, public_key: PublicKey) -> Result {
+            let issuer = ensure_signed(origin)?;
+            ensure!(Self::is_active(id.clone()), "DID is not registered or deactivated");
+            ensure!(Self::is_sole_controller(id.clone(), issuer.clone()), "You are not the issuer of this identity or it is controlled by a controller set");
+            Self::do_add_key(id, purpose, key_type, public_key, issuer)
+        }
+
+        #[weight = SimpleDispatchInfo::FixedNormal(5 * READ_WEIGHT + WRITE_WEIGHT)]
+        pub fn revoke_key(origin, id: DIDIdentifier, index: KeyIndex) -> Result {
+            let issuer = ensure_signed(origin)?;
+            ensure!(Self::is_active(id.clone()), "DID is not registered or deactivated");
+            ensure!(Self::is_sole_controller(id.clone(), issuer), "You are not the issuer of this identity or it is controlled by a controller set");
+            Self::do_revoke_key(id, index)
+        }
+
+        #[weight = SimpleDispatchInfo::FixedNormal(6 * READ_WEIGHT + 3 * WRITE_WEIGHT)]
+        pub fn rotate_key(origin, id: DIDIdentifier, index: KeyIndex, public_key: PublicKey) -> Result {
+            let issuer = ensure_signed(origin)?;
+            ensure!(Self::is_active(id.clone()), "DID is not registered or deactivated");
+            ensure!(Self::is_sole_controller(id.clone(), issuer.clone()), "You are not the issuer of this identity or it is controlled by a controller set");
+            Self::do_rotate_key(id, index, public_key, issuer)
+        }
+
+        /// Dispatch `call` with the origin of the DID `id`.
+        ///
+        /// `signature` must be made over `signing_payload(id, nonce, call)` with a currently
+        /// valid authentication key of the DID, where `nonce` is the current nonce of the DID.
+        /// The sender pays for the transaction, including the weight of `call`.
+        #[weight = WeightForCallAsDid(5 * READ_WEIGHT + WRITE_WEIGHT + VERIFY_WEIGHT)]
+        pub fn call_as_did(origin, id: DIDIdentifier, call: Box<<T as Trait>::Proposal>, signature: Vec<u8>) -> Result {
+            let _sender = ensure_signed(origin)?;
+            ensure!(Self::is_active(id.clone()), "DID is not registered or deactivated");
+            let nonce = Self::nonce(id.clone());
+            let payload = Self::signing_payload(id.clone(), nonce, &call);
+            let current_block = <system::Module<T>>::block_number();
+            ensure!(Self::verify_at(id.clone(), KeyPurpose::Authentication, &payload, &signature, current_block), "The signature is not valid for this DID");
+            <Nonces>::insert(id.clone(), nonce + 1);
+            let ok = call.dispatch(RawOrigin::Did(id.clone()).into()).is_ok();
+            Self::deposit_event(RawEvent::DidCallDispatched(id, ok));
+            Ok(())
+        }
+	}
+}
+
+pub type DIDIdentifier = Vec<u8>;
+pub type PublicKey = Vec<u8>;
+pub type IssuedBy = Vec<u8>;
+pub type KeyIndex = u32;
+
+decl_event!(
+	pub enum Event<T> where SovereignAccount  = <T as system::Trait>::AccountId {
+
+        IdIssuerRegistered(DIDIdentifier, SovereignAccount),
+        IdIssued(DIDIdentifier, SovereignAccount, IssuedBy ),
+        IdDeactivated(DIDIdentifier, SovereignAccount ),
+        IdChanged(DIDIdentifier, PublicKey, SovereignAccount ),
+        OwnerProposed(DIDIdentifier, SovereignAccount),
+        OwnerChanged(DIDIdentifier, SovereignAccount),
+        ControllersChanged(DIDIdentifier, u32),
+        ActionApproved(DIDIdentifier, H256, u32),
+        ActionExecuted(DIDIdentifier, H256),
+        KeyAdded(DIDIdentifier, KeyIndex, KeyPurpose, PublicKey),
+        KeyRevoked(DIDIdentifier, KeyIndex),
+        KeyRotated(DIDIdentifier, KeyIndex, KeyIndex, PublicKey),
+        DidCallDispatched(DIDIdentifier, bool),
+	}
+);
+
+#[cfg(test)]
+mod mock;
+
+#[cfg(test)]
+mod tests;
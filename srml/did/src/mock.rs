@@ -0,0 +1,130 @@
+// Copyright 2019 Parity Technologies (UK) Ltd.
+// This file is part of Substrate.
+
+// Substrate is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Substrate is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Substrate.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Test utilities
+
+use super::*;
+
+use support::parameter_types;
+use primitives::{sr25519, Pair};
+use sr_primitives::{
+	Perbill, BuildStorage, traits::{BlakeTwo256, IdentityLookup}, testing::Header,
+};
+use crate as did;
+
+parameter_types! {
+	pub const BlockHashCount: u64 = 250;
+	pub const MaximumBlockWeight: u32 = 1024;
+	pub const MaximumBlockLength: u32 = 2 * 1024;
+	pub const AvailableBlockRatio: Perbill = Perbill::one();
+
+	pub const ExistentialDeposit: u64 = 0;
+	pub const TransferFee: u64 = 0;
+	pub const CreationFee: u64 = 0;
+
+	pub const ByteDeposit: u64 = 1;
+}
+
+impl system::Trait for Test {
+	type Origin = Origin;
+	type Index = u64;
+	type BlockNumber = u64;
+	type Call = Call;
+	type Hash = H256;
+	type Hashing = BlakeTwo256;
+	type AccountId = u64;
+	type Lookup = IdentityLookup<Self::AccountId>;
+	type Header = Header;
+	type Event = Event;
+	type BlockHashCount = BlockHashCount;
+	type MaximumBlockWeight = MaximumBlockWeight;
+	type MaximumBlockLength = MaximumBlockLength;
+	type AvailableBlockRatio = AvailableBlockRatio;
+	type Version = ();
+}
+
+impl balances::Trait for Test {
+	type Balance = u64;
+	type OnFreeBalanceZero = ();
+	type OnNewAccount = ();
+	type Event = Event;
+	type TransferPayment = ();
+	type DustRemoval = ();
+	type ExistentialDeposit = ExistentialDeposit;
+	type TransferFee = TransferFee;
+	type CreationFee = CreationFee;
+}
+
+impl Trait for Test {
+	type Event = Event;
+	type Origin = Origin;
+	type Proposal = Call;
+	type Currency = balances::Module<Self>;
+	type ByteDeposit = ByteDeposit;
+}
+
+pub type Block = sr_primitives::generic::Block<Header, UncheckedExtrinsic>;
+pub type UncheckedExtrinsic = sr_primitives::generic::UncheckedExtrinsic<u32, u64, Call, ()>;
+
+support::construct_runtime!(
+	pub enum Test where
+		Block = Block,
+		NodeBlock = Block,
+		UncheckedExtrinsic = UncheckedExtrinsic
+	{
+		System: system::{Module, Call, Event},
+		Balances: balances::{Module, Call, Storage, Config<T>, Event<T>},
+		DID: did::{Module, Call, Storage, Event<T>, Origin, Config<T>},
+	}
+);
+
+/// The DID registered at genesis, owned by account 1 and issued by account 10.
+pub const GENESIS_DID: &[u8] = b"did:vault:genesis";
+
+/// The root issuer DID registered at genesis for account 10.
+pub const ISSUER_DID: &[u8] = b"did:vault:issuer";
+
+/// The authentication key of `GENESIS_DID`.
+pub fn genesis_key() -> sr25519::Pair {
+	sr25519::Pair::from_seed(&[1; 32])
+}
+
+pub fn new_test_ext() -> runtime_io::TestExternalities {
+	let mut ext: runtime_io::TestExternalities = GenesisConfig {
+		balances: Some(balances::GenesisConfig {
+			balances: vec![(1, 1_000), (2, 1_000), (3, 1_000), (10, 1_000), (99, 1)],
+			vesting: vec![],
+		}),
+		did: Some(did::GenesisConfig {
+			ids: vec![
+				(ISSUER_DID.to_vec(), vec![0], vec![10; 32], 10, 10),
+				(GENESIS_DID.to_vec(), ISSUER_DID.to_vec(), genesis_key().public().0.to_vec(), 10, 1),
+			],
+		}),
+	}.build_storage().unwrap().into();
+	ext.execute_with(|| System::set_block_number(1));
+	ext
+}
+
+/// The events deposited by the did module so far.
+pub fn did_events() -> Vec<RawEvent<u64>> {
+	System::events().into_iter()
+		.filter_map(|record| match record.event {
+			Event::did(event) => Some(event),
+			_ => None,
+		})
+		.collect()
+}
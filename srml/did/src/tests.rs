@@ -0,0 +1,333 @@
+// Copyright 2019 Parity Technologies (UK) Ltd.
+// This file is part of Substrate.
+
+// Substrate is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Substrate is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Substrate.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Tests for the module.
+
+use crate::{Controller, ControllerAction, DidController, KeyPurpose, KeyType, RawEvent, RawOrigin, ensure_did};
+use mock::*;
+
+use support::{assert_ok, assert_noop};
+use primitives::{H256, Pair, sr25519};
+use sr_primitives::traits::{BlakeTwo256, Hash};
+use sr_primitives::weights::GetDispatchInfo;
+
+const NEW_DID: &[u8] = b"did:vault:new";
+
+#[test]
+fn genesis_dids_are_registered() {
+	new_test_ext().execute_with(|| {
+		assert!(DID::is_active(ISSUER_DID.to_vec()));
+		assert!(DID::is_active(GENESIS_DID.to_vec()));
+		assert!(DID::is_id_owner(GENESIS_DID.to_vec(), 1));
+		assert!(DID::is_id_issuer(GENESIS_DID.to_vec(), 10));
+		assert_eq!(DID::issuer_chain(GENESIS_DID.to_vec()), vec![ISSUER_DID.to_vec()]);
+		assert_eq!(DID::keys_of(GENESIS_DID.to_vec()).len(), 1);
+		assert_eq!(DID::deposit(GENESIS_DID.to_vec()), None);
+		assert_eq!(Balances::reserved_balance(&10), 0);
+		let controllers = |id: &[u8]| DID::document(id.to_vec()).unwrap().controllers;
+		assert_eq!(controllers(GENESIS_DID), vec![DidController::Did(ISSUER_DID.to_vec())]);
+		assert_eq!(controllers(ISSUER_DID), vec![DidController::Did(ISSUER_DID.to_vec())]);
+	});
+}
+
+#[test]
+fn register_reserves_deposit() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(DID::register(Origin::signed(10), NEW_DID.to_vec(), ISSUER_DID.to_vec(), vec![2; 32], 2));
+		let keys = DID::keys_of(NEW_DID.to_vec());
+		let deposit = DID::deposit_for(NEW_DID, &DID::id(NEW_DID.to_vec()), &keys);
+		assert!(deposit > 0);
+		assert_eq!(Balances::reserved_balance(&10), deposit);
+		assert_eq!(DID::deposit(NEW_DID.to_vec()), Some((10, deposit)));
+		assert!(DID::is_id_owner(NEW_DID.to_vec(), 2));
+		assert_eq!(did_events(), vec![RawEvent::IdIssued(NEW_DID.to_vec(), 2, ISSUER_DID.to_vec())]);
+	});
+}
+
+#[test]
+fn register_checks_issuer_and_id() {
+	new_test_ext().execute_with(|| {
+		assert_noop!(
+			DID::register(Origin::signed(2), NEW_DID.to_vec(), ISSUER_DID.to_vec(), vec![2; 32], 2),
+			"You are not the owner of this issuer_did"
+		);
+		assert_noop!(
+			DID::register(Origin::signed(10), GENESIS_DID.to_vec(), vec![0], vec![2; 32], 2),
+			"The id is already issued"
+		);
+		assert!(DID::register(Origin::signed(99), NEW_DID.to_vec(), vec![0], vec![2; 32], 99).is_err());
+	});
+}
+
+#[test]
+fn deactivated_dids_can_not_issue() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(DID::deactivate(Origin::signed(10), ISSUER_DID.to_vec()));
+		assert_noop!(
+			DID::register(Origin::signed(10), NEW_DID.to_vec(), ISSUER_DID.to_vec(), vec![2; 32], 2),
+			"Issuer DID is not registered or deactivated"
+		);
+	});
+}
+
+#[test]
+fn deactivate_keeps_tombstone_and_its_deposit() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(DID::register(Origin::signed(2), NEW_DID.to_vec(), vec![0], vec![2; 32], 2));
+		let deposit = Balances::reserved_balance(&2);
+		assert!(deposit > 0);
+		assert_noop!(
+			DID::deactivate(Origin::signed(3), NEW_DID.to_vec()),
+			"You are not the issuer of this identity or it is controlled by a controller set"
+		);
+		assert_ok!(DID::deactivate(Origin::signed(2), NEW_DID.to_vec()));
+		assert_eq!(Balances::reserved_balance(&2), deposit);
+		assert_eq!(DID::deposit(NEW_DID.to_vec()), Some((2, deposit)));
+		assert!(!DID::is_active(NEW_DID.to_vec()));
+		assert!(DID::keys_of(NEW_DID.to_vec()).iter().all(|key| key.is_revoked()));
+		assert_noop!(
+			DID::register(Origin::signed(2), NEW_DID.to_vec(), vec![0], vec![2; 32], 2),
+			"The id is already issued"
+		);
+	});
+}
+
+#[test]
+fn update_rotates_primary_key_and_takes_deposit() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(5);
+		assert_ok!(DID::update(Origin::signed(10), GENESIS_DID.to_vec(), vec![3; 32]));
+		let keys = DID::keys_of(GENESIS_DID.to_vec());
+		assert_eq!(keys.len(), 2);
+		assert_eq!(keys[0].valid_until, Some(5));
+		assert_eq!(keys[1].public_key, vec![3; 32]);
+		assert_eq!(DID::id(GENESIS_DID.to_vec()).public_key, vec![3; 32]);
+		// genesis DIDs take their deposit from the account which first changes them
+		let deposit = DID::deposit_for(GENESIS_DID, &DID::id(GENESIS_DID.to_vec()), &keys);
+		assert_eq!(DID::deposit(GENESIS_DID.to_vec()), Some((10, deposit)));
+		assert_eq!(Balances::reserved_balance(&10), deposit);
+	});
+}
+
+#[test]
+fn owner_transfer_needs_acceptance() {
+	new_test_ext().execute_with(|| {
+		assert_noop!(
+			DID::propose_owner(Origin::signed(2), GENESIS_DID.to_vec(), 3),
+			"You are not the owner of this identity"
+		);
+		assert_ok!(DID::propose_owner(Origin::signed(1), GENESIS_DID.to_vec(), 3));
+		assert!(DID::is_id_owner(GENESIS_DID.to_vec(), 1));
+		assert_noop!(
+			DID::accept_owner(Origin::signed(2), GENESIS_DID.to_vec()),
+			"You are not the proposed owner of this identity"
+		);
+		assert_ok!(DID::accept_owner(Origin::signed(3), GENESIS_DID.to_vec()));
+		assert!(DID::is_id_owner(GENESIS_DID.to_vec(), 3));
+		assert_eq!(DID::pending_owner(GENESIS_DID.to_vec()), None);
+	});
+}
+
+#[test]
+fn controllers_act_by_threshold() {
+	new_test_ext().execute_with(|| {
+		let controllers = vec![Controller::Account(2), Controller::Account(3)];
+		assert_noop!(
+			DID::set_controllers(Origin::signed(10), GENESIS_DID.to_vec(), controllers.clone(), 3),
+			"The threshold is larger than the number of controllers"
+		);
+		assert_ok!(DID::set_controllers(Origin::signed(10), GENESIS_DID.to_vec(), controllers, 2));
+		assert_noop!(
+			DID::deactivate(Origin::signed(10), GENESIS_DID.to_vec()),
+			"You are not the issuer of this identity or it is controlled by a controller set"
+		);
+		assert_ok!(DID::approve(Origin::signed(2), GENESIS_DID.to_vec(), ControllerAction::Deactivate));
+		assert!(DID::is_active(GENESIS_DID.to_vec()));
+		assert_noop!(
+			DID::approve(Origin::signed(2), GENESIS_DID.to_vec(), ControllerAction::Deactivate),
+			"You already approved this action"
+		);
+		assert_ok!(DID::approve(Origin::signed(3), GENESIS_DID.to_vec(), ControllerAction::Deactivate));
+		assert!(!DID::is_active(GENESIS_DID.to_vec()));
+	});
+}
+
+#[test]
+fn approvals_of_former_controllers_do_not_count() {
+	new_test_ext().execute_with(|| {
+		let id = GENESIS_DID.to_vec();
+		let deactivate = ControllerAction::Deactivate;
+		assert_ok!(DID::set_controllers(Origin::signed(10), id.clone(), vec![Controller::Account(2), Controller::Account(3)], 2));
+		assert_ok!(DID::approve(Origin::signed(2), id.clone(), deactivate.clone()));
+
+		let change = ControllerAction::SetControllers(vec![Controller::Account(3), Controller::Account(4)], 2);
+		assert_ok!(DID::approve(Origin::signed(2), id.clone(), change.clone()));
+		assert_ok!(DID::approve(Origin::signed(3), id.clone(), change));
+		assert!(DID::approvals(&id, &BlakeTwo256::hash_of(&deactivate)).is_empty());
+
+		// the approval of account 2 was dropped with the old controller set
+		assert_ok!(DID::approve(Origin::signed(3), id.clone(), deactivate.clone()));
+		assert!(DID::is_active(id.clone()));
+		assert_noop!(
+			DID::approve(Origin::signed(2), id.clone(), deactivate.clone()),
+			"You are not a controller of this identity"
+		);
+		assert_ok!(DID::approve(Origin::signed(4), id.clone(), deactivate));
+		assert!(!DID::is_active(id));
+	});
+}
+
+#[test]
+fn controller_sets_manage_keys_by_threshold() {
+	new_test_ext().execute_with(|| {
+		let id = GENESIS_DID.to_vec();
+		assert_ok!(DID::set_controllers(Origin::signed(10), id.clone(), vec![Controller::Account(2), Controller::Account(3)], 2));
+		let error = "You are not the issuer of this identity or it is controlled by a controller set";
+		assert_noop!(DID::add_key(Origin::signed(10), id.clone(), KeyPurpose::Authentication, KeyType::Ed25519, vec![4; 32]), error);
+		assert_noop!(DID::revoke_key(Origin::signed(10), id.clone(), 0), error);
+		assert_noop!(DID::rotate_key(Origin::signed(10), id.clone(), 0, vec![4; 32]), error);
+
+		let add = ControllerAction::AddKey(KeyPurpose::Authentication, KeyType::Ed25519, vec![4; 32]);
+		assert_ok!(DID::approve(Origin::signed(2), id.clone(), add.clone()));
+		assert_eq!(DID::keys_of(id.clone()).len(), 1);
+		assert_ok!(DID::approve(Origin::signed(3), id.clone(), add));
+		assert_eq!(DID::keys_of(id.clone()).len(), 2);
+
+		assert_ok!(DID::approve(Origin::signed(2), id.clone(), ControllerAction::RevokeKey(0)));
+		assert_ok!(DID::approve(Origin::signed(3), id.clone(), ControllerAction::RevokeKey(0)));
+		assert!(DID::keys_of(id)[0].is_revoked());
+	});
+}
+
+#[test]
+fn keys_can_be_added_revoked_and_rotated() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(DID::add_key(Origin::signed(10), GENESIS_DID.to_vec(), KeyPurpose::Assertion, KeyType::Ed25519, vec![4; 32]));
+		assert_noop!(
+			DID::add_key(Origin::signed(10), GENESIS_DID.to_vec(), KeyPurpose::Assertion, KeyType::Ed25519, vec![4; 32]),
+			"The key is already registered"
+		);
+		System::set_block_number(3);
+		assert_ok!(DID::rotate_key(Origin::signed(10), GENESIS_DID.to_vec(), 1, vec![5; 32]));
+		assert_ok!(DID::revoke_key(Origin::signed(10), GENESIS_DID.to_vec(), 2));
+		assert_noop!(
+			DID::revoke_key(Origin::signed(10), GENESIS_DID.to_vec(), 2),
+			"The key is already revoked"
+		);
+		let document = DID::document(GENESIS_DID.to_vec()).unwrap();
+		assert_eq!(document.verification_methods.len(), 1);
+		assert_eq!(document.verification_methods[0].purpose, KeyPurpose::Authentication);
+	});
+}
+
+#[test]
+fn signatures_are_verified_with_the_keys_valid_at_a_block() {
+	new_test_ext().execute_with(|| {
+		let new_key = sr25519::Pair::from_seed(&[2; 32]);
+		System::set_block_number(5);
+		assert_ok!(DID::update(Origin::signed(10), GENESIS_DID.to_vec(), new_key.public().0.to_vec()));
+		let verify = |pair: &sr25519::Pair, purpose, at| {
+			DID::verify_at(GENESIS_DID.to_vec(), purpose, b"message", &pair.sign(b"message").0, at)
+		};
+		// the genesis key was valid until the update
+		assert!(verify(&genesis_key(), KeyPurpose::Authentication, 4));
+		assert!(!verify(&genesis_key(), KeyPurpose::Authentication, 5));
+		assert!(!verify(&new_key, KeyPurpose::Authentication, 4));
+		assert!(verify(&new_key, KeyPurpose::Authentication, 5));
+		assert!(!verify(&new_key, KeyPurpose::Assertion, 5));
+	});
+}
+
+#[test]
+fn approve_as_did_checks_signature_of_the_controller_did() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(DID::register(Origin::signed(2), NEW_DID.to_vec(), vec![0], vec![2; 32], 2));
+		assert_ok!(DID::set_controllers(Origin::signed(2), NEW_DID.to_vec(), vec![Controller::Did(GENESIS_DID.to_vec())], 1));
+		let action = ControllerAction::Deactivate;
+		let payload = DID::approval_payload(NEW_DID.to_vec(), GENESIS_DID.to_vec(), 0, &action);
+		let signature = genesis_key().sign(&payload).0.to_vec();
+		assert_noop!(
+			DID::approve_as_did(Origin::signed(3), NEW_DID.to_vec(), GENESIS_DID.to_vec(), action.clone(), vec![0; 64]),
+			"The signature is not valid for the controller DID"
+		);
+		assert_noop!(
+			DID::approve_as_did(Origin::signed(3), NEW_DID.to_vec(), ISSUER_DID.to_vec(), action.clone(), signature.clone()),
+			"The signature is not valid for the controller DID"
+		);
+		assert_ok!(DID::approve_as_did(Origin::signed(3), NEW_DID.to_vec(), GENESIS_DID.to_vec(), action, signature));
+		assert_eq!(DID::nonce(GENESIS_DID.to_vec()), 1);
+		assert!(!DID::is_active(NEW_DID.to_vec()));
+	});
+}
+
+#[test]
+fn call_as_did_checks_signature_and_nonce() {
+	new_test_ext().execute_with(|| {
+		let call = Box::new(Call::System(system::Call::remark(vec![])));
+		let payload = DID::signing_payload(GENESIS_DID.to_vec(), 0, &call);
+		let signature = genesis_key().sign(&payload).0.to_vec();
+		assert_noop!(
+			DID::call_as_did(Origin::signed(2), GENESIS_DID.to_vec(), call.clone(), vec![0; 64]),
+			"The signature is not valid for this DID"
+		);
+		assert_ok!(DID::call_as_did(Origin::signed(2), GENESIS_DID.to_vec(), call.clone(), signature.clone()));
+		assert_eq!(DID::nonce(GENESIS_DID.to_vec()), 1);
+		// `remark` needs a signed origin, so it fails with the origin of the DID
+		assert_eq!(did_events(), vec![RawEvent::DidCallDispatched(GENESIS_DID.to_vec(), false)]);
+		// the signature can not be replayed with the next nonce
+		assert_noop!(
+			DID::call_as_did(Origin::signed(2), GENESIS_DID.to_vec(), call, signature),
+			"The signature is not valid for this DID"
+		);
+	});
+}
+
+#[test]
+fn call_as_did_signatures_are_bound_to_the_chain() {
+	new_test_ext().execute_with(|| {
+		let call = Box::new(Call::System(system::Call::remark(vec![])));
+		let payload = DID::signing_payload(GENESIS_DID.to_vec(), 0, &call);
+		let signature = genesis_key().sign(&payload).0.to_vec();
+		// the same DID and nonce on a chain with another genesis hash
+		<system::BlockHash<Test>>::insert(0, H256::repeat_byte(9));
+		assert_ne!(DID::signing_payload(GENESIS_DID.to_vec(), 0, &call), payload);
+		assert_noop!(
+			DID::call_as_did(Origin::signed(2), GENESIS_DID.to_vec(), call, signature),
+			"The signature is not valid for this DID"
+		);
+		let action = ControllerAction::Deactivate;
+		assert_ne!(DID::approval_payload(GENESIS_DID.to_vec(), GENESIS_DID.to_vec(), 0, &action), payload);
+	});
+}
+
+#[test]
+fn call_as_did_is_weighed_with_the_call() {
+	let weight = |call: Call| call.get_dispatch_info().weight;
+	let remark = Call::System(system::Call::remark(vec![]));
+	let base = 5 * crate::READ_WEIGHT + crate::WRITE_WEIGHT + crate::VERIFY_WEIGHT;
+	let call_as_did = |call: Call| Call::DID(crate::Call::call_as_did(GENESIS_DID.to_vec(), Box::new(call), vec![]));
+	assert_eq!(weight(call_as_did(remark.clone())), base + weight(remark.clone()));
+	assert_eq!(weight(call_as_did(call_as_did(remark.clone()))), 2 * base + weight(remark));
+}
+
+#[test]
+fn ensure_did_accepts_only_did_origin() {
+	new_test_ext().execute_with(|| {
+		let origin: mock::Origin = RawOrigin::Did(GENESIS_DID.to_vec()).into();
+		assert_eq!(ensure_did(origin), Ok(GENESIS_DID.to_vec()));
+		assert!(ensure_did(mock::Origin::signed(1)).is_err());
+	});
+}
@@ -0,0 +1,255 @@
+// Copyright 2019 Parity Technologies (UK) Ltd.
+// This file is part of Substrate.
+
+// Substrate is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Substrate is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Substrate.  If not, see <http://www.gnu.org/licenses/>.
+
+//! # Difficulty Module
+//!
+//! The Difficulty module adjusts the proof-of-work difficulty of a chain, so that its blocks
+//! come at `TargetBlockTime` on average.
+//!
+//! ## Overview
+//!
+//! The module keeps the timestamps and the difficulties of the last `Window` blocks. Once the
+//! timestamp of a block is set, the difficulty of the next block is the average difficulty of
+//! the window, scaled by the target block time over the average block time of the window:
+//!
+//! `next = average difficulty * TargetBlockTime / average block time`
+//!
+//! The average block time is clamped to between a quarter and four times the target, so one
+//! block changes the difficulty by at most four times. The difficulty never drops below one.
+//!
+//! The difficulty is read by the node through the `DifficultyApi` runtime API of the PoW
+//! consensus engine, which a runtime implements with `Module::difficulty`.
+//!
+//! ## Usage
+//!
+//! Add the module to `OnTimestampSet` of the `timestamp` module, and set the difficulty of
+//! the first block with `initial_difficulty` of the `GenesisConfig`.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use rstd::prelude::*;
+use primitives::U256;
+use sr_primitives::traits::SaturatedConversion;
+use support::{decl_module, decl_storage, traits::Get};
+use timestamp::OnTimestampSet;
+
+/// The most one block can multiply or divide the difficulty by.
+const MAX_ADJUSTMENT: u128 = 4;
+
+pub trait Trait: timestamp::Trait {
+	/// The average time between blocks the difficulty aims at.
+	type TargetBlockTime: Get<Self::Moment>;
+
+	/// The number of blocks the average block time is taken over.
+	type Window: Get<u32>;
+}
+
+decl_storage! {
+	trait Store for Module<T: Trait> as Difficulty {
+		/// Difficulty of the next block.
+		pub CurrentDifficulty get(difficulty) config(initial_difficulty): U256;
+
+		/// Timestamps and difficulties of the last `Window` blocks, oldest first.
+		pub PastBlocks get(past_blocks): Vec<(T::Moment, U256)>;
+	}
+}
+
+decl_module! {
+	pub struct Module<T: Trait> for enum Call where origin: T::Origin {
+		/// The average time between blocks the difficulty aims at.
+		const TargetBlockTime: T::Moment = T::TargetBlockTime::get();
+
+		/// The number of blocks the average block time is taken over.
+		const Window: u32 = T::Window::get();
+	}
+}
+
+impl<T: Trait> Module<T> {
+	/// The difficulty following the blocks of `past`, oldest first.
+	fn next_difficulty(past: &[(T::Moment, U256)]) -> Option<U256> {
+		let (first, last) = match (past.first(), past.last()) {
+			(Some(first), Some(last)) if past.len() > 1 => (first.0, last.0),
+			_ => return None,
+		};
+		// the first block only starts the window, the others were mined in it.
+		let blocks = past.len() as u128 - 1;
+
+		let target = T::TargetBlockTime::get().saturated_into::<u128>().max(1);
+		let elapsed = last.saturated_into::<u128>().saturating_sub(first.saturated_into::<u128>());
+		let block_time = (elapsed / blocks)
+			.max(target / MAX_ADJUSTMENT)
+			.min(target.saturating_mul(MAX_ADJUSTMENT))
+			.max(1);
+
+		let difficulty = past[1..].iter()
+			.fold(U256::zero(), |sum, (_, difficulty)| sum.saturating_add(*difficulty))
+			/ U256::from(blocks);
+
+		Some((difficulty.saturating_mul(U256::from(target)) / U256::from(block_time)).max(U256::one()))
+	}
+}
+
+impl<T: Trait> OnTimestampSet<T::Moment> for Module<T> {
+	fn on_timestamp_set(now: T::Moment) {
+		let mut past = Self::past_blocks();
+		past.push((now, Self::difficulty()));
+
+		let window = T::Window::get().max(2) as usize;
+		if past.len() > window {
+			past.drain(..past.len() - window);
+		}
+
+		if let Some(difficulty) = Self::next_difficulty(&past) {
+			<CurrentDifficulty>::put(difficulty);
+		}
+		<PastBlocks<T>>::put(past);
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	use support::{impl_outer_origin, parameter_types};
+	use runtime_io::TestExternalities;
+	use primitives::H256;
+	use sr_primitives::{Perbill, traits::{BlakeTwo256, IdentityLookup}, testing::Header};
+
+	impl_outer_origin! {
+		pub enum Origin for Test {}
+	}
+
+	#[derive(Clone, Eq, PartialEq)]
+	pub struct Test;
+	parameter_types! {
+		pub const BlockHashCount: u64 = 250;
+		pub const MaximumBlockWeight: u32 = 1024;
+		pub const MaximumBlockLength: u32 = 2 * 1024;
+		pub const AvailableBlockRatio: Perbill = Perbill::one();
+	}
+	impl system::Trait for Test {
+		type Origin = Origin;
+		type Index = u64;
+		type BlockNumber = u64;
+		type Call = ();
+		type Hash = H256;
+		type Hashing = BlakeTwo256;
+		type AccountId = u64;
+		type Lookup = IdentityLookup<Self::AccountId>;
+		type Header = Header;
+		type Event = ();
+		type BlockHashCount = BlockHashCount;
+		type MaximumBlockWeight = MaximumBlockWeight;
+		type AvailableBlockRatio = AvailableBlockRatio;
+		type MaximumBlockLength = MaximumBlockLength;
+		type Version = ();
+	}
+	parameter_types! {
+		pub const MinimumPeriod: u64 = 1;
+		pub const TargetBlockTime: u64 = 100;
+		pub const Window: u32 = 4;
+	}
+	impl timestamp::Trait for Test {
+		type Moment = u64;
+		type OnTimestampSet = Difficulty;
+		type MinimumPeriod = MinimumPeriod;
+	}
+	impl Trait for Test {
+		type TargetBlockTime = TargetBlockTime;
+		type Window = Window;
+	}
+	type Difficulty = Module<Test>;
+
+	fn new_test_ext(initial_difficulty: u64) -> TestExternalities {
+		let mut t = system::GenesisConfig::default().build_storage::<Test>().unwrap();
+		GenesisConfig {
+			initial_difficulty: initial_difficulty.into(),
+		}.assimilate_storage::<Test>(&mut t).unwrap();
+		t.into()
+	}
+
+	/// Set the timestamps of `blocks` blocks, the first at `start` and then every `block_time`.
+	fn mine(start: u64, block_time: u64, blocks: u64) {
+		for n in 0..blocks {
+			Difficulty::on_timestamp_set(start + n * block_time);
+		}
+	}
+
+	#[test]
+	fn blocks_at_target_keep_difficulty() {
+		new_test_ext(1_000).execute_with(|| {
+			mine(100, 100, 10);
+			assert_eq!(Difficulty::difficulty(), 1_000.into());
+		});
+	}
+
+	#[test]
+	fn fast_blocks_raise_difficulty() {
+		new_test_ext(1_000).execute_with(|| {
+			mine(100, 50, 2);
+			assert_eq!(Difficulty::difficulty(), 2_000.into());
+		});
+	}
+
+	#[test]
+	fn slow_blocks_lower_difficulty() {
+		new_test_ext(1_000).execute_with(|| {
+			mine(100, 200, 2);
+			assert_eq!(Difficulty::difficulty(), 500.into());
+		});
+	}
+
+	#[test]
+	fn difficulty_averages_over_the_window() {
+		new_test_ext(1_000).execute_with(|| {
+			// the two blocks of the window were mined at 1_000 and 2_000 in half the target.
+			mine(100, 50, 3);
+			assert_eq!(Difficulty::difficulty(), 3_000.into());
+		});
+	}
+
+	#[test]
+	fn adjustment_is_clamped() {
+		new_test_ext(1_000).execute_with(|| {
+			mine(100, 1, 2);
+			assert_eq!(Difficulty::difficulty(), 4_000.into());
+		});
+
+		new_test_ext(1_000).execute_with(|| {
+			mine(100, 10_000, 2);
+			assert_eq!(Difficulty::difficulty(), 250.into());
+		});
+	}
+
+	#[test]
+	fn difficulty_stays_above_one() {
+		new_test_ext(1).execute_with(|| {
+			mine(100, 1_000, 5);
+			assert_eq!(Difficulty::difficulty(), 1.into());
+		});
+	}
+
+	#[test]
+	fn window_keeps_last_blocks() {
+		new_test_ext(1_000).execute_with(|| {
+			mine(100, 100, 10);
+			let past = Difficulty::past_blocks();
+			assert_eq!(past.len(), 4);
+			assert_eq!(past.first().map(|b| b.0), Some(700));
+			assert_eq!(past.last().map(|b| b.0), Some(1_000));
+		});
+	}
+}
@@ -674,7 +674,7 @@ impl<T: Trait> Module<T> {
 	/// the `remaining` would be returned, else `Zero::zero()`.
 	/// NOTE: LOW-LEVEL: This will not attempt to maintain total issuance. It is expected that
 	/// the caller will do this.
-	fn repatriate_reserved(
+	pub fn repatriate_reserved(
 		asset_id: &T::AssetId,
 		who: &T::AccountId,
 		beneficiary: &T::AccountId,
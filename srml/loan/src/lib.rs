@@ -0,0 +1,1325 @@
+// Copyright 2019 Parity Technologies (UK) Ltd.
+// This file is part of Substrate.
+
+// Substrate is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Substrate is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Substrate.  If not, see <http://www.gnu.org/licenses/>.
+
+//! # Loan Module
+//!
+//! ## Overview
+//!
+//! A module that enables lending/borrowing with the currency in substrate.
+//! 
+//! ## Interface
+//!
+//! This module implements peer-to-peer loan and bond. 
+//!
+//! ### Dispatchable Functions
+//!
+//! - `request` - Request loan and get bond_id to get approval from others.
+//! - `approve` - Approver accepts loan request and lend money for the borrower
+//! - `redeem` - Borrower ends loan by paying back the borrowed money with interest.
+//! - `repay` - Borrower pays part of the debt back, settling interest before principal.
+//! - `repossess` - Lender takes collateral worth the debt from borrower once the grace period of the bond has passed, if the collateral has a price.
+//! - `liquidate` - Anyone pays the debt of an undercollateralised bond and takes its collateral.
+//! - `sell_bond` - Lender sets bond for sale
+//! - `cancel_ask` - Lender takes the bond off sale.
+//! - `buy_bond` - Other lender takses lender's bond for sale by paying it.
+//! - `place_bid` - Buyer offers a price for a bond, which is reserved until the bid ends.
+//! - `cancel_bid` - Buyer withdraws a bid and gets the price back.
+//! - `accept_bid` - Lender sells the bond to a bidder for the reserved price.
+//! - `cancel` - Borrower withdraws a loan request which is not approved yet.
+//! - `link_did` - Borrower ties a loan request to its DID.
+//! - `add_guarantor` - Borrower names a guarantor of a loan request for an amount.
+//! - `accept_guarantee` - Guarantor co-signs a loan request and reserves the amount it guarantees.
+//!
+//! Interest accrues every period at the annual rate of the bond, converted to the length of
+//! the period. The schedule of the bond decides which part of the debt is due every period.
+//! The scheduler dispatches `accrue` every period of an approved bond, and `mark_default` once
+//! its grace period is over.
+//!
+//! Collateral is held in asset tokens and valued with a price feed. A bond needs
+//! `InitialCollateralRatio` when it is approved, and the ratios of the active bonds are checked
+//! in turn against `MaintenanceRatio`, `CollateralChecks` bonds a block. Once it drops below, anyone can `liquidate` the bond
+//! by paying its debt, for collateral worth the debt plus `LiquidationBonus`. The rest of the
+//! collateral goes back to the borrower.
+//!
+//! Active bonds are traded on a small order book. The lender has at most one ask, and buyers
+//! place bids whose price is reserved from them until the bid is accepted or cancelled. The
+//! bids of a bond are refunded when it is redeemed, repossessed or liquidated.
+//!
+//! Borrowers who cannot post enough collateral can name guarantors for their request. Every
+//! guarantor co-signs with `accept_guarantee`, which reserves its share of the guarantee, and
+//! the request can only be approved once all of them have. The guarantees count towards the
+//! collateral ratio of the bond at par. When a bond in default is repossessed, the collateral
+//! of the borrower is seized first, and the debt it does not cover is drawn from the guarantors
+//! pro rata to their share. The rest of the guarantees is released when the bond ends. If the
+//! collateral asset has no recent price, the whole collateral is seized and the guarantors are
+//! released, so that a stale price feed does not keep lenders from recovering a bond.
+//!
+//! A loan can be tied to a DID of the borrower. Redemptions and repossessions of such loans
+//! are recorded in the credit history of the DID, which gives a credit score. Lenders can
+//! require a minimum score, or a DID attested by a given issuer, when approving a loan.
+//!
+//! Bonds are indexed by borrower, by lender and by whether they are for sale, so they can be
+//! listed through the `LoanApi` runtime API without scanning every bond.
+//!
+//! The borrower reserves a deposit for the storage of the bond when requesting a loan,
+//! which is refunded when the bond is redeemed, repossessed or cancelled.
+//!
+//! Loan requests and credit histories can be set at genesis with the `requests` and
+//! `credit_history` of the `GenesisConfig`.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use support::{decl_module, decl_storage, decl_event, dispatch::Result, ensure, traits::{Currency, Get, ReservableCurrency, schedule}};
+use primitives::H256;
+use sr_primitives::{Fixed64, Perbill};
+use sr_primitives::traits::{BlakeTwo256, Hash, Member, SaturatedConversion, Saturating, Zero};
+use sr_primitives::weights::{SimpleDispatchInfo, Weight, READ_WEIGHT, WRITE_WEIGHT};
+use codec::{Encode, Decode};
+use system::{ensure_root, ensure_signed};
+use support::dispatch::Parameter;
+use rstd::prelude::*;
+
+/// Maximum number of instalments of an amortised bond.
+const MAX_INSTALMENTS: u32 = 1_200;
+
+/// Maximum number of open bids on a bond.
+const MAX_BIDS: usize = 32;
+
+/// Maximum number of guarantors of a bond.
+const MAX_GUARANTORS: usize = 8;
+
+
+/// Tokens which loans are collateralised with.
+pub trait CollateralAssets<AccountId, AssetId, Balance> {
+    /// Move `amount` of `asset` from the free balance of `who` to its reserved balance.
+    fn reserve(asset: &AssetId, who: &AccountId, amount: Balance) -> Result;
+    /// Move up to `amount` of the reserved `asset` of `who` back to its free balance.
+    fn unreserve(asset: &AssetId, who: &AccountId, amount: Balance);
+    /// The reserved balance of `asset` of `who`.
+    fn reserved_balance(asset: &AssetId, who: &AccountId) -> Balance;
+    /// Move `amount` of the reserved `asset` of `from` to the free balance of `to`. Fails
+    /// without moving anything if less than `amount` is reserved.
+    fn repatriate_reserved(asset: &AssetId, from: &AccountId, to: &AccountId, amount: Balance) -> Result;
+}
+
+/// Prices of the assets which loans are collateralised with.
+pub trait PriceFeed<AssetId> {
+    /// Price of one unit of `asset` in units of the native currency, if a recent one is known.
+    fn price(asset: &AssetId) -> Option<Fixed64>;
+}
+
+/// Identities which loans are tied to.
+pub trait Identity<AccountId> {
+    /// Whether `who` owns the active identity `id`.
+    fn is_owner(id: &[u8], who: &AccountId) -> bool;
+    /// Whether `id` was issued by the active identity `issuer`, directly or through other
+    /// issuers.
+    fn is_issued_by(id: &[u8], issuer: &[u8]) -> bool;
+}
+
+/// Ties loans to the identifiers of the `did` module.
+impl<T: did::Trait> Identity<T::AccountId> for did::Module<T> {
+    fn is_owner(id: &[u8], who: &T::AccountId) -> bool {
+        Self::is_active(id.to_vec()) && Self::is_id_owner(id.to_vec(), who.clone())
+    }
+
+    fn is_issued_by(id: &[u8], issuer: &[u8]) -> bool {
+        Self::is_active(issuer.to_vec()) && Self::issuer_chain(id.to_vec()).iter().any(|i| i.as_slice() == issuer)
+    }
+}
+
+/// Collateral held in the currency `C` loans are made in, for chains without other assets.
+/// The asset id is ignored and the collateral is valued at par.
+pub struct NativeCollateral<C>(rstd::marker::PhantomData<C>);
+
+impl<AccountId, AssetId, C: ReservableCurrency<AccountId>> CollateralAssets<AccountId, AssetId, C::Balance> for NativeCollateral<C> {
+    fn reserve(_: &AssetId, who: &AccountId, amount: C::Balance) -> Result {
+        C::reserve(who, amount)
+    }
+
+    fn unreserve(_: &AssetId, who: &AccountId, amount: C::Balance) {
+        C::unreserve(who, amount);
+    }
+
+    fn reserved_balance(_: &AssetId, who: &AccountId) -> C::Balance {
+        C::reserved_balance(who)
+    }
+
+    fn repatriate_reserved(_: &AssetId, from: &AccountId, to: &AccountId, amount: C::Balance) -> Result {
+        ensure!(C::reserved_balance(from) >= amount, "Not enough collateral is reserved");
+        C::repatriate_reserved(from, to, amount).map(|_| ())
+    }
+}
+
+impl<AssetId, C> PriceFeed<AssetId> for NativeCollateral<C> {
+    fn price(_: &AssetId) -> Option<Fixed64> {
+        Some(Fixed64::from_natural(1))
+    }
+}
+
+/// How a bond is paid back.
+#[derive(Eq, PartialEq, Copy, Clone, Encode, Decode)]
+#[cfg_attr(feature = "std", derive(Debug, serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "std", serde(rename_all = "camelCase"))]
+pub enum Schedule {
+    /// The whole debt is paid when the bond expires.
+    Bullet,
+    /// Equal instalments of principal and interest are due every period.
+    Amortised,
+    /// The interest is due every period, and the principal when the bond expires.
+    InterestOnly,
+}
+
+impl Default for Schedule {
+    fn default() -> Self {
+        Schedule::Bullet
+    }
+}
+
+/// A bond of the `loan` module.
+#[derive(Eq, PartialEq, Clone, Encode, Decode)]
+#[cfg_attr(feature = "std", derive(Debug, serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "std", serde(rename_all = "camelCase"))]
+pub struct BondInfo<AccountId, Balance, BlockNumber, AssetId> {
+    /// Identifier of the bond.
+    pub id: H256,
+    /// The account which lends, or is asked to lend if the bond is not approved yet.
+    pub lender: AccountId,
+    /// The account which borrows.
+    pub borrower: AccountId,
+    /// Outstanding debt, the principal together with the interest accrued on it.
+    pub amount: Balance,
+    /// Outstanding principal.
+    pub principal: Balance,
+    /// The block the bond expires at.
+    pub expires_at: Option<BlockNumber>,
+    /// Annual interest rate, in parts per billion.
+    pub rate: u32,
+    /// How the bond is paid back.
+    pub schedule: Schedule,
+    /// Amount which is due under the schedule and not paid yet.
+    pub due: Balance,
+    /// Asset the collateral is held in.
+    pub collateral_asset: AssetId,
+    /// Amount of collateral.
+    pub collateral: Balance,
+    /// Number of blocks interest is accrued after.
+    pub period: BlockNumber,
+    /// Price of the ask of the lender, if the bond is for sale.
+    pub for_sale: Option<Balance>,
+    /// The block interest is accrued at next, or `None` if the bond is not approved yet.
+    pub next_increment: Option<BlockNumber>,
+    /// Number of blocks after expiry before the bond is in default.
+    pub grace_period: BlockNumber,
+    /// Annual rate of the penalty which accrues every block after expiry, in parts per billion.
+    pub penalty_rate: u32,
+}
+
+/// A bond together with the debt it will have at a block.
+#[derive(Eq, PartialEq, Clone, Encode, Decode)]
+#[cfg_attr(feature = "std", derive(Debug, serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "std", serde(rename_all = "camelCase"))]
+pub struct ProjectedBond<AccountId, Balance, BlockNumber, AssetId> {
+    /// The bond as it is stored.
+    pub bond: BondInfo<AccountId, Balance, BlockNumber, AssetId>,
+    /// The block the debt is projected at.
+    pub at: BlockNumber,
+    /// The outstanding debt at `at` if nothing is paid back before, with the late penalty.
+    pub debt: Balance,
+}
+
+/// Repayment history of a DID in the `loan` module.
+#[derive(Eq, PartialEq, Copy, Clone, Default, Encode, Decode)]
+#[cfg_attr(feature = "std", derive(Debug, serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "std", serde(rename_all = "camelCase"))]
+pub struct CreditRecord {
+    /// Bonds redeemed before they expired.
+    pub on_time: u32,
+    /// Bonds redeemed after they expired.
+    pub late: u32,
+    /// Bonds whose collateral was repossessed.
+    pub defaulted: u32,
+}
+
+impl CreditRecord {
+    /// Highest credit score.
+    pub const MAX_SCORE: u32 = 1_000;
+
+    /// Credit score of the record, between 0 and `MAX_SCORE`.
+    ///
+    /// This is the share of bonds redeemed on time, counting one bond on time and one not
+    /// on top of the history. A DID without history scores half of `MAX_SCORE`, and the
+    /// score moves towards the actual share as bonds end.
+    pub fn score(&self) -> u32 {
+        let ended = self.on_time as u64 + self.late as u64 + self.defaulted as u64;
+        (Self::MAX_SCORE as u64 * (self.on_time as u64 + 1) / (ended + 2)) as u32
+    }
+}
+
+/// A credit record together with its score.
+#[derive(Eq, PartialEq, Clone, Encode, Decode)]
+#[cfg_attr(feature = "std", derive(Debug, serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "std", serde(rename_all = "camelCase"))]
+pub struct CreditScore {
+    /// The repayment history.
+    pub record: CreditRecord,
+    /// The score of the history, between 0 and `CreditRecord::MAX_SCORE`.
+    pub score: u32,
+}
+
+/// The listing of a bond by its lender.
+#[derive(Eq, PartialEq, Clone, Encode, Decode)]
+#[cfg_attr(feature = "std", derive(Debug, serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "std", serde(rename_all = "camelCase"))]
+pub struct Ask<AccountId, Balance> {
+    /// The lender selling the bond.
+    pub seller: AccountId,
+    /// The price the bond is sold for.
+    pub price: Balance,
+}
+
+/// An offer to buy a bond, whose price is reserved from the bidder.
+#[derive(Eq, PartialEq, Clone, Encode, Decode)]
+#[cfg_attr(feature = "std", derive(Debug, serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "std", serde(rename_all = "camelCase"))]
+pub struct Bid<AccountId, Balance> {
+    /// Identifier of the bid.
+    pub id: u64,
+    /// The account offering to buy the bond.
+    pub bidder: AccountId,
+    /// The price offered for the bond.
+    pub price: Balance,
+}
+
+/// The open orders of a bond.
+#[derive(Eq, PartialEq, Clone, Encode, Decode)]
+#[cfg_attr(feature = "std", derive(Debug, serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "std", serde(rename_all = "camelCase"))]
+pub struct OrderBook<AccountId, Balance> {
+    /// The listing of the lender, if the bond is for sale.
+    pub ask: Option<Ask<AccountId, Balance>>,
+    /// The open bids, in the order they were placed.
+    pub bids: Vec<Bid<AccountId, Balance>>,
+}
+
+#[derive(Encode, Decode, Copy, Clone, Eq, PartialEq, Default)]
+#[cfg_attr(feature = "std", derive(Debug))]
+pub struct Bond<Balance: Parameter, AccountId: Parameter, BlockNumber: Parameter, AssetId: Parameter> {
+    lender: AccountId,
+    borrower: AccountId,
+    /// outstanding debt, the principal together with the interest accrued on it
+    amount: Balance,
+    /// outstanding principal
+    principal: Balance,
+    expires_at: Option<BlockNumber>,
+    /// annual interest rate
+    rate: Perbill,
+    schedule: Schedule,
+    /// amount due every period of an amortised bond
+    instalment: Balance,
+    /// amount which is due under the schedule and not paid yet
+    due: Balance,
+    /// asset the collateral is held in
+    collateral_asset: AssetId,
+    collateral: Balance,
+    period: BlockNumber,
+    for_sale: Option<Balance>,
+    next_increment: BlockNumber,
+    /// blocks after expiry before the bond is in default
+    grace_period: BlockNumber,
+    /// annual rate of the penalty which accrues every block after expiry
+    penalty_rate: Perbill,
+    /// block up to which the penalty is added to the debt
+    penalised_until: BlockNumber,
+}
+
+/// A guarantor named for a bond, and the share of the debt it guarantees.
+#[derive(Encode, Decode, Clone, Eq, PartialEq)]
+#[cfg_attr(feature = "std", derive(Debug))]
+pub struct Guarantee<AccountId, Balance> {
+    pub guarantor: AccountId,
+    /// amount reserved from the guarantor once it accepts
+    pub amount: Balance,
+    pub accepted: bool,
+}
+
+impl<Balance: Parameter + Default, AccountId: Parameter, BlockNumber: Parameter + Default, AssetId: Parameter> Bond<Balance, AccountId, BlockNumber, AssetId> {
+    pub fn new (lender: AccountId, borrower: AccountId, amount: Balance, expires_at: Option<BlockNumber>, rate: Perbill, schedule: Schedule, collateral_asset: AssetId, collateral: Balance, period: BlockNumber, next_increment: BlockNumber, grace_period: BlockNumber, penalty_rate: Perbill) -> Self {
+        Bond {
+            lender,
+            borrower,
+            amount: amount.clone(),
+            principal: amount,
+            expires_at,
+            rate,
+            schedule,
+            instalment: Balance::default(),
+            due: Balance::default(),
+            collateral_asset,
+            collateral,
+            period,
+            for_sale: None,
+            next_increment,
+            grace_period,
+            penalty_rate,
+            penalised_until: BlockNumber::default(),
+        }
+    }
+
+    pub fn approve(&mut self, lender: AccountId) {
+        self.lender = lender;
+    }
+
+    pub fn sender_is_lender(&self, lender: AccountId) -> bool {
+        self.lender == lender && self.borrower != lender   
+    }
+
+    pub fn sender_is_borrower(&self, borrower: AccountId) -> bool {
+        self.borrower == borrower && self.lender != borrower
+    }
+
+    pub fn request_transfer(&mut self, price: Balance) {
+        self.for_sale = Some(price);
+    }
+    pub fn approve_transfer(&mut self, approver: AccountId) {
+        self.lender = approver;
+        self.for_sale = None;
+    }
+
+    pub fn cancel_transfer(&mut self) {
+        self.for_sale = None;
+    }
+}
+
+// Module's function and Methods of custom struct to be placed here
+impl<T: Trait> Module<T> {
+
+    /// Interest rate of a single period of `bond`, from its annual rate.
+    pub fn period_rate(bond: &BondOf<T>) -> Fixed64 {
+        let period = bond.period.saturated_into::<u32>() as i64;
+        let blocks_per_year = T::BlocksPerYear::get().saturated_into::<u32>() as u64;
+        Fixed64::from_rational(
+            bond.rate.deconstruct() as i64 * period,
+            Perbill::accuracy() as u64 * blocks_per_year,
+        )
+    }
+
+    /// Instalment which pays `principal` back with the interest at `rate` per period in
+    /// `periods` equal payments.
+    pub fn amortised_instalment(principal: BalanceOf<T>, rate: Fixed64, periods: u32) -> BalanceOf<T> {
+        if periods == 0 {
+            return principal;
+        }
+        // (1 + rate) ^ periods, in parts of `Fixed64::accuracy()`
+        let one = Fixed64::accuracy() as u64;
+        let growth = (0..periods).fold(one, |g, _| rate.saturated_multiply_accumulate(g));
+        if growth <= one {
+            return principal / BalanceOf::<T>::from(periods);
+        }
+        // principal * rate * growth / (growth - 1)
+        let scaled = Fixed64::from_rational(one as i64, growth - one).saturated_multiply_accumulate(principal);
+        rate.saturated_multiply_accumulate(scaled).saturating_sub(scaled)
+    }
+
+    /// Accrue the interest of one period on `bond` and add the payment of the period to
+    /// what is due.
+    pub fn accrue_interest(mut bond: BondOf<T>, current: T::BlockNumber) -> BondOf<T> {
+        let before = bond.amount;
+        bond.amount = Self::period_rate(&bond).saturated_multiply_accumulate(bond.amount);
+        let interest = bond.amount - before;
+        bond.due = match bond.schedule {
+            Schedule::Bullet => bond.due,
+            Schedule::Amortised => bond.due.saturating_add(bond.instalment).min(bond.amount),
+            Schedule::InterestOnly => bond.due.saturating_add(interest),
+        };
+        bond.next_increment = current + bond.period;
+        bond
+    }
+
+    /// Add the penalty which accrued on `bond` after its expiry up to `current` to its debt.
+    pub fn accrue_penalty(mut bond: BondOf<T>, current: T::BlockNumber) -> BondOf<T> {
+        let expires_at = match bond.expires_at {
+            Some(expires_at) => expires_at,
+            None => return bond,
+        };
+        let since = expires_at.max(bond.penalised_until);
+        if current <= since {
+            return bond;
+        }
+        let blocks = (current - since).saturated_into::<u32>() as i64;
+        let blocks_per_year = T::BlocksPerYear::get().saturated_into::<u32>() as u64;
+        let penalty = Fixed64::from_rational(
+            bond.penalty_rate.deconstruct() as i64 * blocks,
+            Perbill::accuracy() as u64 * blocks_per_year,
+        );
+        bond.amount = penalty.saturated_multiply_accumulate(bond.amount);
+        bond.due = bond.amount;
+        bond.penalised_until = current;
+        bond
+    }
+
+    /// The block after which `bond` is in default if it is not paid back.
+    pub fn default_at(bond: &BondOf<T>) -> Option<T::BlockNumber> {
+        bond.expires_at.map(|expires_at| expires_at.saturating_add(bond.grace_period))
+    }
+
+    /// Mark `bond_id` as in default if it is still active once its grace period is over.
+    fn check_default(bond_id: H256, current_block: T::BlockNumber) -> Result {
+        ensure!(<Bonds<T>>::exists(bond_id), "bond does not exist");
+        ensure!(!<Defaulted<T>>::exists(bond_id), "The bond is already in default");
+        let bond = Self::accrue_penalty(Self::bond(bond_id), current_block);
+        let default_at = Self::default_at(&bond).ok_or("The bond does not expire")?;
+        ensure!(default_at <= current_block, "The grace period of the bond is not over yet");
+        <Defaulted<T>>::insert(bond_id, current_block);
+        Self::deposit_event(RawEvent::LoanDefaulted(bond_id, bond.lender, bond.borrower, bond.amount));
+        for guarantee in Self::guarantees(bond_id).into_iter().filter(|g| g.accepted) {
+            Self::deposit_event(RawEvent::GuaranteedLoanDefaulted(bond_id, guarantee.guarantor));
+        }
+        Ok(())
+    }
+
+    /// Accrue the interest of the period of `bond_id` which ended, and of the penalty since
+    /// its expiry.
+    pub fn process_bond(bond_id: H256, current_block: T::BlockNumber) -> Result {
+        ensure!(<Bonds<T>>::exists(bond_id), "bond does not exist");
+        let bond = Self::bond(bond_id);
+        ensure!(!bond.next_increment.is_zero(), "The loan is not approved yet");
+        ensure!(bond.next_increment <= current_block, "The period of the bond is not over yet");
+        let before = bond.amount;
+        let bond = Self::accrue_penalty(bond, current_block);
+        let updated_bond = Self::accrue_interest(bond, current_block);
+        let next = updated_bond.next_increment;
+        <Bonds<T>>::insert(bond_id, &updated_bond);
+        Self::deposit_event(RawEvent::LoanAmountIncreased(bond_id, before, updated_bond.amount, next));
+        Ok(())
+	}
+
+    /// Id the interest accrual of `bond_id` is scheduled under.
+    fn accrual_task(bond_id: H256) -> Vec<u8> {
+        (b"loan/accrue", bond_id).encode()
+    }
+
+    /// Id the default check of `bond_id` is scheduled under.
+    fn default_task(bond_id: H256) -> Vec<u8> {
+        (b"loan/default", bond_id).encode()
+    }
+
+    /// Schedule the interest accrual of the approved `bond` every period from its
+    /// `next_increment`, and its default check at `default_at`.
+    fn schedule_tasks(bond_id: H256, bond: &BondOf<T>, default_at: T::BlockNumber) -> Result {
+        T::Scheduler::schedule_named(
+            Self::accrual_task(bond_id), bond.next_increment, Some(bond.period), Call::<T>::accrue(bond_id).into(),
+        )?;
+        let scheduled = T::Scheduler::schedule_named(
+            Self::default_task(bond_id), default_at, None, Call::<T>::mark_default(bond_id).into(),
+        );
+        if let Err(e) = scheduled {
+            let _ = T::Scheduler::cancel_named(Self::accrual_task(bond_id));
+            return Err(e);
+        }
+        Ok(())
+    }
+
+    /// Cancel the scheduled interest accrual and default check of `bond_id`.
+    fn cancel_tasks(bond_id: H256) {
+        // the default check is no longer scheduled once it is dispatched
+        let _ = T::Scheduler::cancel_named(Self::accrual_task(bond_id));
+        let _ = T::Scheduler::cancel_named(Self::default_task(bond_id));
+    }
+
+    /// Value of the collateral of `bond` in the native currency, if its price is known.
+    pub fn collateral_value(bond: &BondOf<T>) -> Option<BalanceOf<T>> {
+        let price = T::PriceFeed::price(&bond.collateral_asset)?;
+        Some(price.saturated_multiply_accumulate(bond.collateral).saturating_sub(bond.collateral))
+    }
+
+    /// Amount guaranteed for `bond_id` by the guarantors who accepted.
+    pub fn guaranteed(bond_id: H256) -> BalanceOf<T> {
+        Self::guarantees(bond_id).iter()
+            .filter(|g| g.accepted)
+            .fold(Zero::zero(), |total: BalanceOf<T>, g| total.saturating_add(g.amount))
+    }
+
+    /// Whether the collateral of `bond` together with its guarantees is worth less than
+    /// `ratio` times its debt, or `None` if the price of the collateral is not known.
+    pub fn is_below_ratio(bond_id: H256, bond: &BondOf<T>, ratio: Fixed64) -> Option<bool> {
+        let value = Self::collateral_value(bond)?.saturating_add(Self::guaranteed(bond_id));
+        let required = ratio.saturated_multiply_accumulate(bond.amount).saturating_sub(bond.amount);
+        Some(value < required)
+    }
+
+    /// Check the collateral ratio of the next `CollateralChecks` active bonds, and flag the
+    /// ones below the maintenance ratio as liquidatable. Returns the weight of the checks.
+    fn check_collateral(current_block: T::BlockNumber) -> Weight {
+        let bonds = Self::active_bonds();
+        if bonds.is_empty() {
+            return READ_WEIGHT;
+        }
+        let checks = (T::CollateralChecks::get() as usize).min(bonds.len());
+        let start = Self::collateral_cursor() as usize % bonds.len();
+        <CollateralCursor>::put(((start + checks) % bonds.len()) as u32);
+        for bond_id in bonds.iter().cycle().skip(start).take(checks).cloned() {
+            let bond = Self::bond(bond_id);
+            let below = Self::is_below_ratio(bond_id, &bond, T::MaintenanceRatio::get()).unwrap_or(false);
+            let flagged = <Undercollateralised<T>>::exists(bond_id);
+            if below && !flagged {
+                <Undercollateralised<T>>::insert(bond_id, current_block);
+                Self::deposit_event(RawEvent::CollateralRatioLow(bond_id, bond.lender, bond.borrower));
+            } else if !below && flagged {
+                <Undercollateralised<T>>::remove(bond_id);
+            }
+        }
+        // the bonds and the cursor, then per bond its guarantees, flag and collateral price
+        2 * READ_WEIGHT + WRITE_WEIGHT + checks as Weight * (4 * READ_WEIGHT + WRITE_WEIGHT)
+    }
+
+    /// Draw `debt` from the guarantors of `bond_id` to the lender of `bond`, pro rata to the
+    /// amount each of them guarantees. Returns the amount drawn.
+    fn draw_guarantees(bond_id: H256, bond: &BondOf<T>, debt: BalanceOf<T>) -> BalanceOf<T> {
+        let total = Self::guaranteed(bond_id);
+        if total.is_zero() || debt.is_zero() {
+            return Zero::zero();
+        }
+        let share = if debt >= total { Perbill::one() } else { Perbill::from_rational_approximation(debt, total) };
+        let mut drawn = BalanceOf::<T>::zero();
+        let mut guarantees = Self::guarantees(bond_id);
+        for guarantee in guarantees.iter_mut().filter(|g| g.accepted) {
+            let amount = share * guarantee.amount;
+            if amount.is_zero() {
+                continue;
+            }
+            // the guarantor may hold less than it reserved if its reserve was slashed
+            let paid = match T::Currency::repatriate_reserved(&guarantee.guarantor, &bond.lender, amount) {
+                Ok(left) => amount - left,
+                Err(_) => continue,
+            };
+            guarantee.amount -= paid;
+            drawn = drawn.saturating_add(paid);
+            Self::deposit_event(RawEvent::GuaranteeDrawn(bond_id, guarantee.guarantor.clone(), paid));
+        }
+        <Guarantees<T>>::insert(bond_id, guarantees);
+        drawn
+    }
+
+    /// Transfer `amount` from the borrower of `bond` to its lender.
+    fn pay_lender(bond: &BondOf<T>, amount: BalanceOf<T>) -> Result {
+        T::Currency::transfer(&bond.borrower, &bond.lender, amount)
+    }
+
+    /// Remove an approved bond with its scheduled interest, and give the collateral which is
+    /// left back to the borrower.
+    fn close_bond(bond_id: H256, bond: &BondOf<T>, collateral_left: BalanceOf<T>) {
+        T::Assets::unreserve(&bond.collateral_asset, &bond.borrower, collateral_left);
+        Self::cancel_tasks(bond_id);
+        <ActiveBonds>::mutate(|bonds| bonds.retain(|id| *id != bond_id));
+        <Undercollateralised<T>>::remove(bond_id);
+        <Defaulted<T>>::remove(bond_id);
+        Self::remove_bond(bond_id, bond);
+    }
+
+    /// Identifier of the loan request `bond` which `borrower` makes with its request `nonce`.
+    ///
+    /// This is the blake2-256 hash of `("loan", borrower, nonce, (lender, amount, expires_at,
+    /// rate, schedule, collateral_asset, collateral, period, grace_period, penalty_rate))` in
+    /// SCALE encoding, so clients can compute it from the `Nonces` of the borrower before the
+    /// request is included.
+    pub fn bond_id(borrower: &T::AccountId, nonce: u64, bond: &BondOf<T>) -> H256 {
+        let params = (
+            &bond.lender, bond.amount, bond.expires_at.unwrap_or_default(), bond.rate,
+            bond.schedule, bond.collateral_asset, bond.collateral, bond.period,
+            bond.grace_period, bond.penalty_rate,
+        );
+        BlakeTwo256::hash_of(&(b"loan", borrower, nonce, params))
+    }
+
+    /// The credit history of the DID `id` with its score.
+    pub fn credit_score(id: Vec<u8>) -> CreditScore {
+        let record = Self::credit_history(id);
+        CreditScore { record, score: record.score() }
+    }
+
+    /// Record the end of `bond_id` in the credit history of the DID it is tied to.
+    fn record_credit<F: FnOnce(&mut CreditRecord)>(bond_id: H256, f: F) {
+        if let Some(id) = Self::bond_did(bond_id) {
+            <CreditHistory>::mutate(id, f);
+        }
+    }
+
+    /// Record the redemption of `bond` at `current_block`, which is on time unless the bond
+    /// expired before.
+    fn record_redemption(bond_id: H256, bond: &BondOf<T>, current_block: T::BlockNumber) {
+        let on_time = bond.expires_at.map_or(true, |expires_at| current_block <= expires_at);
+        Self::record_credit(bond_id, |r| if on_time {
+            r.on_time = r.on_time.saturating_add(1)
+        } else {
+            r.late = r.late.saturating_add(1)
+        });
+    }
+
+    /// Deposit needed for storing `bond` under `bond_id`.
+    pub fn deposit_for(bond_id: &H256, bond: &BondOf<T>) -> BalanceOf<T> {
+        let bytes = (bond_id, bond).encode().len();
+        T::ByteDeposit::get().saturating_mul(BalanceOf::<T>::from(bytes as u32))
+    }
+
+    /// `bond` stored under `bond_id` in the form the runtime API returns it.
+    pub fn bond_info(bond_id: H256, bond: BondOf<T>) -> BondInfo<T::AccountId, BalanceOf<T>, T::BlockNumber, T::AssetId> {
+        BondInfo {
+            id: bond_id,
+            lender: bond.lender,
+            borrower: bond.borrower,
+            amount: bond.amount,
+            principal: bond.principal,
+            expires_at: bond.expires_at,
+            rate: bond.rate.deconstruct(),
+            schedule: bond.schedule,
+            due: bond.due,
+            collateral_asset: bond.collateral_asset,
+            collateral: bond.collateral,
+            period: bond.period,
+            for_sale: bond.for_sale,
+            next_increment: if bond.next_increment.is_zero() { None } else { Some(bond.next_increment) },
+            grace_period: bond.grace_period,
+            penalty_rate: bond.penalty_rate.deconstruct(),
+        }
+    }
+
+    fn bond_infos(bond_ids: Vec<H256>) -> Vec<BondInfo<T::AccountId, BalanceOf<T>, T::BlockNumber, T::AssetId>> {
+        bond_ids.into_iter().map(|id| Self::bond_info(id, Self::bond(id))).collect()
+    }
+
+    /// The bonds `borrower` borrows with, including requests which are not approved yet.
+    pub fn bonds_by_borrower(borrower: T::AccountId) -> Vec<BondInfo<T::AccountId, BalanceOf<T>, T::BlockNumber, T::AssetId>> {
+        Self::bond_infos(Self::borrower_bonds(borrower))
+    }
+
+    /// The bonds `lender` holds or is asked to lend with.
+    pub fn bonds_by_lender(lender: T::AccountId) -> Vec<BondInfo<T::AccountId, BalanceOf<T>, T::BlockNumber, T::AssetId>> {
+        Self::bond_infos(Self::lender_bonds(lender))
+    }
+
+    /// The bonds which are for sale.
+    pub fn bonds_for_sale() -> Vec<BondInfo<T::AccountId, BalanceOf<T>, T::BlockNumber, T::AssetId>> {
+        Self::bond_infos(Self::for_sale())
+    }
+
+    /// `bond_id` with the debt it has at block `at` if nothing is paid back before. Interest
+    /// is projected for at most `MAX_INSTALMENTS` periods.
+    pub fn projected_bond(bond_id: H256, at: T::BlockNumber) -> Option<ProjectedBond<T::AccountId, BalanceOf<T>, T::BlockNumber, T::AssetId>> {
+        if !<Bonds<T>>::exists(bond_id) {
+            return None;
+        }
+        let bond = Self::bond(bond_id);
+        let mut projected = bond.clone();
+        if !projected.next_increment.is_zero() {
+            let mut periods = 0;
+            while projected.next_increment <= at && periods < MAX_INSTALMENTS {
+                let current = projected.next_increment;
+                projected = Self::accrue_penalty(projected, current);
+                projected = Self::accrue_interest(projected, current);
+                periods += 1;
+            }
+            projected = Self::accrue_penalty(projected, at);
+        }
+        Some(ProjectedBond { bond: Self::bond_info(bond_id, bond), at, debt: projected.amount })
+    }
+
+    /// Store the loan request `bond` and reserve the deposit for it from the borrower.
+    /// Returns the id of the bond.
+    fn insert_request(bond: BondOf<T>) -> rstd::result::Result<H256, &'static str> {
+        ensure!(bond.borrower != bond.lender, "You cannot just borrow money from oneself");
+        ensure!(!bond.period.is_zero(), "The period should be longer than zero");
+        let nonce = Self::nonce(&bond.borrower);
+        let bond_hash = Self::bond_id(&bond.borrower, nonce, &bond);
+        ensure!(!<Bonds<T>>::exists(bond_hash), "Hash collision!");
+        let deposit = Self::deposit_for(&bond_hash, &bond);
+        T::Currency::reserve(&bond.borrower, deposit)?;
+        <Deposits<T>>::insert(bond_hash, deposit);
+        <BorrowerBonds<T>>::mutate(&bond.borrower, |ids| ids.push(bond_hash));
+        <LenderBonds<T>>::mutate(&bond.lender, |ids| ids.push(bond_hash));
+        <Nonces<T>>::insert(&bond.borrower, nonce + 1);
+        <Bonds<T>>::insert(bond_hash, bond);
+        Ok(bond_hash)
+    }
+
+    /// Make `buyer` the lender of `bond`, and take the bond off sale.
+    fn transfer_bond(bond_id: H256, bond: &mut BondOf<T>, buyer: T::AccountId) {
+        <LenderBonds<T>>::mutate(&bond.lender, |ids| ids.retain(|id| *id != bond_id));
+        <LenderBonds<T>>::mutate(&buyer, |ids| ids.push(bond_id));
+        <BondsForSale>::mutate(|ids| ids.retain(|id| *id != bond_id));
+        bond.approve_transfer(buyer);
+    }
+
+    /// The ask and the open bids of `bond_id`, or `None` if the bond does not exist.
+    pub fn orders(bond_id: H256) -> Option<OrderBook<T::AccountId, BalanceOf<T>>> {
+        if !<Bonds<T>>::exists(bond_id) {
+            return None;
+        }
+        let bond = Self::bond(bond_id);
+        Some(OrderBook {
+            ask: bond.for_sale.map(|price| Ask { seller: bond.lender, price }),
+            bids: Self::bids(bond_id),
+        })
+    }
+
+    /// Remove the bids of `bond_id` matching `filter` and give their price back to the bidders.
+    fn refund_bids<F: Fn(&Bid<T::AccountId, BalanceOf<T>>) -> bool>(bond_id: H256, filter: F) {
+        <Bids<T>>::mutate(bond_id, |bids| bids.retain(|bid| {
+            if filter(bid) {
+                T::Currency::unreserve(&bid.bidder, bid.price);
+                false
+            } else {
+                true
+            }
+        }));
+    }
+
+    /// Remove `bond_id` with its open bids, guarantees and indexes, and refund the deposit of
+    /// its borrower.
+    fn remove_bond(bond_id: H256, bond: &BondOf<T>) {
+        Self::refund_bids(bond_id, |_| true);
+        for guarantee in <Guarantees<T>>::take(bond_id).into_iter().filter(|g| g.accepted) {
+            T::Currency::unreserve(&guarantee.guarantor, guarantee.amount);
+            Self::deposit_event(RawEvent::GuaranteeReleased(bond_id, guarantee.guarantor, guarantee.amount));
+        }
+        <Bids<T>>::remove(bond_id);
+        <Bonds<T>>::remove(bond_id);
+        <BorrowerBonds<T>>::mutate(&bond.borrower, |ids| ids.retain(|id| *id != bond_id));
+        <LenderBonds<T>>::mutate(&bond.lender, |ids| ids.retain(|id| *id != bond_id));
+        <BondsForSale>::mutate(|ids| ids.retain(|id| *id != bond_id));
+        <BondDids>::remove(bond_id);
+        let deposit = <Deposits<T>>::take(bond_id);
+        T::Currency::unreserve(&bond.borrower, deposit);
+    }
+}
+
+/// The module's configuration trait.
+pub trait Trait: system::Trait {
+	/// The overarching event type.
+	type Event: From<Event<Self>> + Into<<Self as system::Trait>::Event>;
+
+	/// The currency loans are made in, which deposits, bids and guarantees are reserved in.
+	type Currency: ReservableCurrency<Self::AccountId>;
+
+	/// The deposit reserved per byte of storage used by a bond.
+	type ByteDeposit: Get<BalanceOf<Self>>;
+
+	/// The number of blocks in a year, which annual interest rates are converted with.
+	type BlocksPerYear: Get<Self::BlockNumber>;
+
+	/// Identifier of the assets loans are collateralised with.
+	type AssetId: Parameter + Member + Copy + Default;
+
+	/// The tokens collateral is held in.
+	type Assets: CollateralAssets<Self::AccountId, Self::AssetId, BalanceOf<Self>>;
+
+	/// Prices of the collateral assets in the native currency.
+	type PriceFeed: PriceFeed<Self::AssetId>;
+
+	/// The ratio of collateral value to debt a bond needs to be approved.
+	type InitialCollateralRatio: Get<Fixed64>;
+
+	/// The ratio of collateral value to debt below which a bond can be liquidated.
+	type MaintenanceRatio: Get<Fixed64>;
+
+	/// The share of the debt the liquidator receives in collateral on top of the debt.
+	type LiquidationBonus: Get<Perbill>;
+
+	/// The number of active bonds whose collateral ratio is checked every block.
+	type CollateralChecks: Get<u32>;
+
+	/// The identities of the borrowers which loans are tied to.
+	type Identity: Identity<Self::AccountId>;
+
+	/// The calls of this module, as the scheduler dispatches them.
+	type Call: From<Call<Self>>;
+
+	/// The scheduler which accrues the interest of bonds and checks them for default.
+	type Scheduler: schedule::Named<Self::BlockNumber, <Self as Trait>::Call>;
+}
+
+pub type BalanceOf<T> = <<T as Trait>::Currency as Currency<<T as system::Trait>::AccountId>>::Balance;
+
+pub type BondOf<T> = Bond<BalanceOf<T>, <T as system::Trait>::AccountId, <T as system::Trait>::BlockNumber, <T as Trait>::AssetId>;
+
+
+// This module's storage items.
+decl_storage! {
+	trait Store for Module<T: Trait> as Loan {
+        pub Bonds get(bond): map H256 => BondOf<T>;
+        pub Deposits get(deposit): map H256 => BalanceOf<T>;
+        /// Approved bonds, whose collateral ratios are checked in turn.
+        pub ActiveBonds get(active_bonds): Vec<H256>;
+        /// Index in `ActiveBonds` of the next bond whose collateral ratio is checked.
+        pub CollateralCursor get(collateral_cursor): u32;
+        /// Bonds below the maintenance ratio, with the block they were found at.
+        pub Undercollateralised get(undercollateralised): map H256 => Option<T::BlockNumber>;
+        /// Bonds in default, with the block their grace period was over at.
+        pub Defaulted get(defaulted_at): map H256 => Option<T::BlockNumber>;
+        /// Open bids on a bond, in the order they were placed.
+        pub Bids get(bids): map H256 => Vec<Bid<T::AccountId, BalanceOf<T>>>;
+        pub NextBidId get(next_bid_id): u64;
+        /// Number of loans each borrower has requested, which bond ids are derived from.
+        pub Nonces get(nonce): map T::AccountId => u64;
+        /// DID of the borrower each bond is tied to.
+        pub BondDids get(bond_did): map H256 => Option<Vec<u8>>;
+        /// Repayment history of each DID.
+        pub CreditHistory get(credit_history): map Vec<u8> => CreditRecord;
+        /// Bonds of each borrower, including requests which are not approved yet.
+        pub BorrowerBonds get(borrower_bonds): map T::AccountId => Vec<H256>;
+        /// Bonds held by each lender, or requested from it if they are not approved yet.
+        pub LenderBonds get(lender_bonds): map T::AccountId => Vec<H256>;
+        /// Bonds with an ask of their lender.
+        pub BondsForSale get(for_sale): Vec<H256>;
+        /// Guarantors named by the borrower of each bond.
+        pub Guarantees get(guarantees): map H256 => Vec<Guarantee<T::AccountId, BalanceOf<T>>>;
+    }
+    add_extra_genesis {
+        // `(borrower, lender, amount, expires_at, rate, schedule, collateral_asset, collateral,
+        // period, grace_period, penalty_rate)`, requested as `request` does.
+        config(requests): Vec<(T::AccountId, T::AccountId, BalanceOf<T>, T::BlockNumber, Perbill, Schedule, T::AssetId, BalanceOf<T>, T::BlockNumber, T::BlockNumber, Perbill)>;
+        config(credit_history): Vec<(Vec<u8>, CreditRecord)>;
+        build(|config: &GenesisConfig<T>| {
+            for (borrower, lender, amount, expires_at, rate, schedule, asset, collateral, period, grace_period, penalty_rate) in config.requests.iter().cloned() {
+                let bond = Bond::new(lender, borrower, amount, Some(expires_at), rate, schedule, asset, collateral, period, T::BlockNumber::from(0), grace_period, penalty_rate);
+                <Module<T>>::insert_request(bond).expect("genesis loan request is invalid");
+            }
+            for (id, record) in &config.credit_history {
+                <CreditHistory>::insert(id, record);
+            }
+        });
+    }
+}
+
+decl_module! {
+	
+	pub struct Module<T: Trait> for enum Call where origin: T::Origin {
+		// Initializing events
+		fn deposit_event() = default;
+
+        /// The deposit reserved per byte of storage used by a bond.
+        const ByteDeposit: BalanceOf<T> = T::ByteDeposit::get();
+
+        /// The number of blocks in a year, which annual interest rates are converted with.
+        const BlocksPerYear: T::BlockNumber = T::BlocksPerYear::get();
+
+        /// The ratio of collateral value to debt a bond needs to be approved.
+        const InitialCollateralRatio: Fixed64 = T::InitialCollateralRatio::get();
+
+        /// The ratio of collateral value to debt below which a bond can be liquidated.
+        const MaintenanceRatio: Fixed64 = T::MaintenanceRatio::get();
+
+        /// The share of the debt the liquidator receives in collateral on top of the debt.
+        const LiquidationBonus: Perbill = T::LiquidationBonus::get();
+
+        /// The number of active bonds whose collateral ratio is checked every block.
+        const CollateralChecks: u32 = T::CollateralChecks::get();
+
+        fn on_initialize(block_number: T::BlockNumber) {
+			let weight = Self::check_collateral(block_number);
+			<system::Module<T>>::register_extra_weight_unchecked(weight);
+		}
+
+        /// Request `amount` from `lender` at the annual interest `rate`, accrued every `period`
+        /// blocks and paid back according to `schedule` by `expires_at`, against `collateral`
+        /// of `collateral_asset`. After expiry a penalty at the annual `penalty_rate` accrues
+        /// every block, and the bond is in default once `grace_period` blocks have passed.
+        #[weight = SimpleDispatchInfo::FixedNormal(6 * READ_WEIGHT + 7 * WRITE_WEIGHT)]
+        pub fn request(origin, lender: T::AccountId, amount: BalanceOf<T>, expires_at: T::BlockNumber, rate: Perbill, schedule: Schedule, collateral_asset: T::AssetId, collateral: BalanceOf<T>, period: T::BlockNumber, grace_period: T::BlockNumber, penalty_rate: Perbill) -> Result {
+            let borrower = ensure_signed(origin)?;
+            ensure!(expires_at > <system::Module<T>>::block_number(), "The bond is already expired");
+            let new_bond = Bond::new(lender, borrower, amount, Some(expires_at), rate, schedule, collateral_asset, collateral, period, T::BlockNumber::from(0), grace_period, penalty_rate);
+            let bond_hash = Self::insert_request(new_bond.clone())?;
+            Self::deposit_event(RawEvent::LoanRequested(new_bond.lender, new_bond.borrower, bond_hash));
+            Ok(())
+        }
+
+        /// Lend to the borrower of `bond_id`. With `min_score` or `issuer`, the loan has to be
+        /// tied to a DID of the borrower with at least that credit score, or attested by that
+        /// issuer.
+        #[weight = SimpleDispatchInfo::FixedNormal(8 * READ_WEIGHT + 6 * WRITE_WEIGHT)]
+        pub fn approve(origin, bond_id: H256, min_score: Option<u32>, issuer: Option<Vec<u8>>) -> Result {
+            let approver = ensure_signed(origin)?;
+            ensure!(<Bonds<T>>::exists(bond_id.clone()), "bond does not exist");
+            let mut the_bond = Self::bond(bond_id.clone());
+            ensure!(the_bond.sender_is_lender(approver.clone()), "You are not the lender for this borrower");
+            ensure!(the_bond.next_increment.is_zero(), "The loan is already approved");
+            if min_score.is_some() || issuer.is_some() {
+                let id = Self::bond_did(bond_id).ok_or("The loan is not tied to a DID")?;
+                ensure!(T::Identity::is_owner(&id, &the_bond.borrower), "The borrower does not own the DID of the loan");
+                if let Some(min_score) = min_score {
+                    ensure!(Self::credit_history(&id).score() >= min_score, "The credit score of the borrower is too low");
+                }
+                if let Some(issuer) = issuer {
+                    ensure!(T::Identity::is_issued_by(&id, &issuer), "The DID of the borrower is not attested by the issuer");
+                }
+            }
+            let current_block = <system::Module<T>>::block_number();
+            let expires_at = the_bond.expires_at.unwrap_or(current_block);
+            ensure!(expires_at > current_block, "The bond is already expired");
+            if the_bond.schedule == Schedule::Amortised {
+                let periods = ((expires_at - current_block) / the_bond.period).saturated_into::<u32>();
+                ensure!(periods > 0, "The bond expires before its first period ends");
+                ensure!(periods <= MAX_INSTALMENTS, "The bond has too many instalments");
+                the_bond.instalment = Self::amortised_instalment(the_bond.principal, Self::period_rate(&the_bond), periods);
+            }
+            let guarantees = Self::guarantees(bond_id);
+            ensure!(guarantees.iter().all(|g| g.accepted), "A guarantor has not accepted the guarantee yet");
+            let below = Self::is_below_ratio(bond_id, &the_bond, T::InitialCollateralRatio::get()).ok_or("The collateral asset has no price")?;
+            ensure!(!below, "The collateral does not cover the loan");
+            the_bond.next_increment = current_block + the_bond.period;
+            Self::schedule_tasks(bond_id, &the_bond, expires_at.saturating_add(the_bond.grace_period))?;
+            if let Err(e) = T::Assets::reserve(&the_bond.collateral_asset, &the_bond.borrower, the_bond.collateral) {
+                Self::cancel_tasks(bond_id);
+                return Err(e);
+            }
+            if let Err(e) = T::Currency::transfer(&the_bond.lender, &the_bond.borrower, the_bond.amount) {
+                T::Assets::unreserve(&the_bond.collateral_asset, &the_bond.borrower, the_bond.collateral);
+                Self::cancel_tasks(bond_id);
+                return Err(e);
+            }
+            the_bond.approve(approver);
+            <Bonds<T>>::insert(bond_id, the_bond.clone());
+            <ActiveBonds>::mutate(|bonds| bonds.push(bond_id));
+            Self::deposit_event(RawEvent::LoanApproved(the_bond.clone().lender, the_bond.clone().borrower, current_block.clone()));
+            for guarantee in guarantees {
+                Self::deposit_event(RawEvent::GuaranteedLoanApproved(bond_id, guarantee.guarantor));
+            }
+            Ok(())
+        }
+
+        #[weight = SimpleDispatchInfo::FixedNormal(9 * READ_WEIGHT + 12 * WRITE_WEIGHT)]
+        pub fn redeem(origin, bond_id: H256) -> Result {
+            let redeemer = ensure_signed(origin)?;
+            ensure!(<Bonds<T>>::exists(bond_id.clone()), "Loan does not exist");
+            let current_block = <system::Module<T>>::block_number();
+            let the_bond = Self::accrue_penalty(Self::bond(bond_id.clone()), current_block);
+            ensure!(the_bond.clone().sender_is_borrower(redeemer), "You are not the redeemer for this loan");
+            ensure!(!the_bond.next_increment.is_zero(), "The loan is not approved yet");
+            Self::pay_lender(&the_bond, the_bond.amount)?;
+            Self::record_redemption(bond_id, &the_bond, current_block);
+            Self::close_bond(bond_id, &the_bond, the_bond.collateral);
+            Self::deposit_event(RawEvent::LoanRedeemed(the_bond.clone().lender, the_bond.clone().borrower, the_bond.clone().amount));
+            Ok(())
+        }
+
+        /// Pay `amount` of the debt back. Accrued interest is settled before principal, and
+        /// the loan ends once the whole debt is paid.
+        #[weight = SimpleDispatchInfo::FixedNormal(9 * READ_WEIGHT + 12 * WRITE_WEIGHT)]
+        pub fn repay(origin, bond_id: H256, amount: BalanceOf<T>) -> Result {
+            let redeemer = ensure_signed(origin)?;
+            ensure!(<Bonds<T>>::exists(bond_id.clone()), "Loan does not exist");
+            let current_block = <system::Module<T>>::block_number();
+            let mut the_bond = Self::accrue_penalty(Self::bond(bond_id.clone()), current_block);
+            ensure!(the_bond.sender_is_borrower(redeemer), "You are not the redeemer for this loan");
+            ensure!(!the_bond.next_increment.is_zero(), "The loan is not approved yet");
+            let paid = amount.min(the_bond.amount);
+            ensure!(!paid.is_zero(), "Nothing to repay");
+            let closing = paid == the_bond.amount;
+            Self::pay_lender(&the_bond, paid)?;
+            if closing {
+                Self::record_redemption(bond_id, &the_bond, current_block);
+                Self::close_bond(bond_id, &the_bond, the_bond.collateral);
+                Self::deposit_event(RawEvent::LoanRedeemed(the_bond.lender, the_bond.borrower, paid));
+                return Ok(());
+            }
+            let interest = the_bond.amount - the_bond.principal;
+            the_bond.principal = the_bond.principal.saturating_sub(paid.saturating_sub(interest));
+            the_bond.amount -= paid;
+            the_bond.due = the_bond.due.saturating_sub(paid);
+            <Bonds<T>>::insert(bond_id, the_bond.clone());
+            Self::deposit_event(RawEvent::LoanRepaid(bond_id, paid, the_bond.amount));
+            Ok(())
+        }
+
+        #[weight = SimpleDispatchInfo::FixedNormal(9 * READ_WEIGHT + 12 * WRITE_WEIGHT)]
+        pub fn repossess(origin, bond_id: H256) -> Result {
+            let lender = ensure_signed(origin)?;
+            ensure!(<Bonds<T>>::exists(bond_id.clone()), "Loan does not exist");
+            let current_block = <system::Module<T>>::block_number();
+            let the_bond = Self::accrue_penalty(Self::bond(bond_id.clone()), current_block);
+            ensure!(the_bond.clone().sender_is_lender(lender), "You are not the lender for this loan");
+            ensure!(!the_bond.next_increment.is_zero(), "The loan is not approved yet");
+            let default_at = Self::default_at(&the_bond).ok_or("The bond does not expire")?;
+            ensure!(default_at <= current_block, "The grace period of the bond is not over yet");
+            let value = Self::collateral_value(&the_bond).ok_or("The collateral asset has no price")?;
+            let (seized, unpaid) = if the_bond.amount >= value {
+                (the_bond.collateral, the_bond.amount - value)
+            } else {
+                (Perbill::from_rational_approximation(the_bond.amount, value) * the_bond.collateral, Zero::zero())
+            };
+            T::Assets::repatriate_reserved(&the_bond.collateral_asset, &the_bond.borrower, &the_bond.lender, seized)?;
+            // the guarantors pay the debt the collateral does not cover
+            Self::draw_guarantees(bond_id, &the_bond, unpaid);
+            Self::record_credit(bond_id, |r| r.defaulted = r.defaulted.saturating_add(1));
+            Self::close_bond(bond_id, &the_bond, the_bond.collateral - seized);
+            Self::deposit_event(RawEvent::LoanRepossessed(the_bond.clone().lender, the_bond.clone().borrower, seized));
+            Ok(())
+        }
+
+        /// Pay the debt of a bond whose collateral ratio is below the maintenance ratio, and
+        /// take its collateral worth the debt plus the liquidation bonus. The rest of the
+        /// collateral goes back to the borrower.
+        #[weight = SimpleDispatchInfo::FixedNormal(10 * READ_WEIGHT + 13 * WRITE_WEIGHT)]
+        pub fn liquidate(origin, bond_id: H256) -> Result {
+            let liquidator = ensure_signed(origin)?;
+            ensure!(<Bonds<T>>::exists(bond_id.clone()), "Loan does not exist");
+            let the_bond = Self::accrue_penalty(Self::bond(bond_id.clone()), <system::Module<T>>::block_number());
+            ensure!(!the_bond.next_increment.is_zero(), "The loan is not approved yet");
+            let below = Self::is_below_ratio(bond_id, &the_bond, T::MaintenanceRatio::get()).ok_or("The collateral asset has no price")?;
+            ensure!(below, "The bond is above the maintenance ratio");
+            let value = Self::collateral_value(&the_bond).ok_or("The collateral asset has no price")?;
+            let claim = the_bond.amount.saturating_add(T::LiquidationBonus::get() * the_bond.amount);
+            let seized = if claim >= value {
+                the_bond.collateral
+            } else {
+                Perbill::from_rational_approximation(claim, value) * the_bond.collateral
+            };
+            ensure!(
+                T::Assets::reserved_balance(&the_bond.collateral_asset, &the_bond.borrower) >= seized,
+                "Not enough collateral is reserved"
+            );
+            T::Currency::transfer(&liquidator, &the_bond.lender, the_bond.amount)?;
+            T::Assets::repatriate_reserved(&the_bond.collateral_asset, &the_bond.borrower, &liquidator, seized)?;
+            Self::close_bond(bond_id, &the_bond, the_bond.collateral - seized);
+            Self::deposit_event(RawEvent::LoanLiquidated(bond_id, liquidator, the_bond.amount, seized));
+            Ok(())
+        }
+
+        #[weight = SimpleDispatchInfo::FixedNormal(2 * READ_WEIGHT + 2 * WRITE_WEIGHT)]
+        pub fn sell_bond(origin, bond_id: H256, price: BalanceOf<T> ) -> Result {
+            let sender = ensure_signed(origin)?;
+            ensure!(<Bonds<T>>::exists(bond_id.clone()), "bond does not exist");
+            let mut the_bond = Self::bond(bond_id.clone());
+            ensure!(the_bond.clone().sender_is_lender(sender.clone()), "You are not the lender for this bond");
+            ensure!(!the_bond.next_increment.is_zero(), "The loan is not approved yet");
+            if the_bond.for_sale.is_none() {
+                <BondsForSale>::mutate(|ids| ids.push(bond_id));
+            }
+            the_bond.request_transfer(price.clone());
+            <Bonds<T>>::mutate(bond_id, |b| {*b = the_bond});
+            Self::deposit_event(RawEvent::BondTransferRequested(bond_id.clone(), sender.clone(), price));
+            Ok(())
+        }
+
+        #[weight = SimpleDispatchInfo::FixedNormal(6 * READ_WEIGHT + 6 * WRITE_WEIGHT)]
+        pub fn buy_bond(origin, bond_id: H256) -> Result {
+            let sender = ensure_signed(origin)?;
+            ensure!(<Bonds<T>>::exists(bond_id.clone()), "bond does not exist");
+            let mut the_bond = Self::bond(bond_id.clone());
+            ensure!(!the_bond.next_increment.is_zero(), "The loan is not approved yet");
+            ensure!(the_bond.borrower != sender, "You cannot buy your own debt");
+            let price = the_bond.for_sale.ok_or("The bond is not open for sale")?;
+            T::Currency::transfer(&sender, &the_bond.lender, price)?;
+            Self::transfer_bond(bond_id, &mut the_bond, sender.clone());
+            <Bonds<T>>::mutate(bond_id, |b| {*b = the_bond});
+            Self::refund_bids(bond_id, |bid| bid.bidder == sender);
+            Self::deposit_event(RawEvent::BondTransferApproved(bond_id.clone(), sender.clone()));
+            Ok(())
+        }
+
+        /// Take the bond off sale.
+        #[weight = SimpleDispatchInfo::FixedNormal(2 * READ_WEIGHT + 2 * WRITE_WEIGHT)]
+        pub fn cancel_ask(origin, bond_id: H256) -> Result {
+            let sender = ensure_signed(origin)?;
+            ensure!(<Bonds<T>>::exists(bond_id), "bond does not exist");
+            let mut the_bond = Self::bond(bond_id);
+            ensure!(the_bond.sender_is_lender(sender), "You are not the lender for this bond");
+            ensure!(the_bond.for_sale.is_some(), "The bond is not open for sale");
+            the_bond.cancel_transfer();
+            <BondsForSale>::mutate(|ids| ids.retain(|id| *id != bond_id));
+            <Bonds<T>>::insert(bond_id, the_bond);
+            Self::deposit_event(RawEvent::AskCancelled(bond_id));
+            Ok(())
+        }
+
+        /// Offer `price` for an active bond. The price is reserved until the bid is accepted
+        /// or cancelled.
+        #[weight = SimpleDispatchInfo::FixedNormal(3 * READ_WEIGHT + 3 * WRITE_WEIGHT)]
+        pub fn place_bid(origin, bond_id: H256, price: BalanceOf<T>) -> Result {
+            let bidder = ensure_signed(origin)?;
+            ensure!(<Bonds<T>>::exists(bond_id), "bond does not exist");
+            let the_bond = Self::bond(bond_id);
+            ensure!(!the_bond.next_increment.is_zero(), "The loan is not approved yet");
+            ensure!(the_bond.lender != bidder, "You already hold this bond");
+            ensure!(the_bond.borrower != bidder, "You cannot buy your own debt");
+            ensure!(!price.is_zero(), "The price should be more than zero");
+            let mut bids = Self::bids(bond_id);
+            ensure!(bids.len() < MAX_BIDS, "The bond has too many open bids");
+            T::Currency::reserve(&bidder, price)?;
+            let id = Self::next_bid_id();
+            bids.push(Bid { id, bidder: bidder.clone(), price });
+            <Bids<T>>::insert(bond_id, bids);
+            NextBidId::put(id + 1);
+            Self::deposit_event(RawEvent::BidPlaced(bond_id, bidder, id, price));
+            Ok(())
+        }
+
+        /// Withdraw a bid and get its price back.
+        #[weight = SimpleDispatchInfo::FixedNormal(2 * READ_WEIGHT + 2 * WRITE_WEIGHT)]
+        pub fn cancel_bid(origin, bond_id: H256, bid_id: u64) -> Result {
+            let bidder = ensure_signed(origin)?;
+            let bid = Self::bids(bond_id).into_iter().find(|b| b.id == bid_id).ok_or("bid does not exist")?;
+            ensure!(bid.bidder == bidder, "You are not the bidder of this bid");
+            Self::refund_bids(bond_id, |b| b.id == bid_id);
+            Self::deposit_event(RawEvent::BidCancelled(bond_id, bid_id));
+            Ok(())
+        }
+
+        /// Sell the bond to the bidder of `bid_id` for the reserved price.
+        #[weight = SimpleDispatchInfo::FixedNormal(7 * READ_WEIGHT + 8 * WRITE_WEIGHT)]
+        pub fn accept_bid(origin, bond_id: H256, bid_id: u64) -> Result {
+            let sender = ensure_signed(origin)?;
+            ensure!(<Bonds<T>>::exists(bond_id), "bond does not exist");
+            let mut the_bond = Self::bond(bond_id);
+            ensure!(the_bond.sender_is_lender(sender.clone()), "You are not the lender for this bond");
+            ensure!(!the_bond.next_increment.is_zero(), "The loan is not approved yet");
+            let mut bids = Self::bids(bond_id);
+            let index = bids.iter().position(|b| b.id == bid_id).ok_or("bid does not exist")?;
+            let bid = bids.remove(index);
+            ensure!(
+                T::Currency::reserved_balance(&bid.bidder) >= bid.price,
+                "The bidder does not hold the price of the bid anymore"
+            );
+            T::Currency::repatriate_reserved(&bid.bidder, &sender, bid.price)?;
+            <Bids<T>>::insert(bond_id, bids);
+            Self::transfer_bond(bond_id, &mut the_bond, bid.bidder.clone());
+            <Bonds<T>>::insert(bond_id, the_bond);
+            Self::refund_bids(bond_id, |b| b.bidder == bid.bidder);
+            Self::deposit_event(RawEvent::BidAccepted(bond_id, bid_id, bid.bidder, bid.price));
+            Ok(())
+        }
+
+        /// Withdraw a loan request which is not approved yet and refund its deposit.
+        #[weight = SimpleDispatchInfo::FixedNormal(5 * READ_WEIGHT + 6 * WRITE_WEIGHT)]
+        pub fn cancel(origin, bond_id: H256) -> Result {
+            let borrower = ensure_signed(origin)?;
+            ensure!(<Bonds<T>>::exists(bond_id.clone()), "Loan does not exist");
+            let the_bond = Self::bond(bond_id.clone());
+            ensure!(the_bond.borrower == borrower, "You are not the borrower for this loan");
+            ensure!(the_bond.next_increment.is_zero(), "The loan is already approved");
+            Self::remove_bond(bond_id, &the_bond);
+            Self::deposit_event(RawEvent::LoanCancelled(the_bond.lender, borrower, bond_id));
+            Ok(())
+        }
+
+        /// Tie a loan request which is not approved yet to the DID `id` of the borrower.
+        #[weight = SimpleDispatchInfo::FixedNormal(3 * READ_WEIGHT + WRITE_WEIGHT)]
+        pub fn link_did(origin, bond_id: H256, id: Vec<u8>) -> Result {
+            let borrower = ensure_signed(origin)?;
+            ensure!(<Bonds<T>>::exists(bond_id), "Loan does not exist");
+            let the_bond = Self::bond(bond_id);
+            ensure!(the_bond.borrower == borrower, "You are not the borrower for this loan");
+            ensure!(the_bond.next_increment.is_zero(), "The loan is already approved");
+            ensure!(T::Identity::is_owner(&id, &borrower), "You do not own this DID");
+            <BondDids>::insert(bond_id, id.clone());
+            Self::deposit_event(RawEvent::DidLinked(bond_id, id));
+            Ok(())
+        }
+
+        /// Name `guarantor` for `amount` of a loan request which is not approved yet. The
+        /// request can only be approved once the guarantor accepts.
+        #[weight = SimpleDispatchInfo::FixedNormal(2 * READ_WEIGHT + WRITE_WEIGHT)]
+        pub fn add_guarantor(origin, bond_id: H256, guarantor: T::AccountId, amount: BalanceOf<T>) -> Result {
+            let borrower = ensure_signed(origin)?;
+            ensure!(<Bonds<T>>::exists(bond_id), "Loan does not exist");
+            let the_bond = Self::bond(bond_id);
+            ensure!(the_bond.borrower == borrower, "You are not the borrower for this loan");
+            ensure!(the_bond.next_increment.is_zero(), "The loan is already approved");
+            ensure!(guarantor != the_bond.borrower && guarantor != the_bond.lender, "The guarantor cannot be a party of the loan");
+            ensure!(!amount.is_zero(), "The guarantee should be more than zero");
+            let mut guarantees = Self::guarantees(bond_id);
+            ensure!(guarantees.len() < MAX_GUARANTORS, "The bond has too many guarantors");
+            ensure!(guarantees.iter().all(|g| g.guarantor != guarantor), "The guarantor is already named");
+            guarantees.push(Guarantee { guarantor: guarantor.clone(), amount, accepted: false });
+            <Guarantees<T>>::insert(bond_id, guarantees);
+            Self::deposit_event(RawEvent::GuarantorAdded(bond_id, guarantor, amount));
+            Ok(())
+        }
+
+        /// Co-sign a loan request as one of its guarantors. The guaranteed amount is reserved
+        /// until the bond ends, and drawn from if the bond is repossessed.
+        #[weight = SimpleDispatchInfo::FixedNormal(2 * READ_WEIGHT + 2 * WRITE_WEIGHT)]
+        pub fn accept_guarantee(origin, bond_id: H256) -> Result {
+            let guarantor = ensure_signed(origin)?;
+            ensure!(<Bonds<T>>::exists(bond_id), "Loan does not exist");
+            ensure!(Self::bond(bond_id).next_increment.is_zero(), "The loan is already approved");
+            let mut guarantees = Self::guarantees(bond_id);
+            let guarantee = guarantees.iter_mut()
+                .find(|g| g.guarantor == guarantor)
+                .ok_or("You are not a guarantor of this loan")?;
+            ensure!(!guarantee.accepted, "You already accepted this guarantee");
+            T::Currency::reserve(&guarantor, guarantee.amount)?;
+            guarantee.accepted = true;
+            let amount = guarantee.amount;
+            <Guarantees<T>>::insert(bond_id, guarantees);
+            Self::deposit_event(RawEvent::GuaranteeAccepted(bond_id, guarantor, amount));
+            Ok(())
+        }
+
+        /// Accrue the interest of the period of `bond_id` which ended. The scheduler
+        /// dispatches this as root every period of an approved bond.
+        #[weight = SimpleDispatchInfo::FixedOperational(2 * READ_WEIGHT + WRITE_WEIGHT)]
+        fn accrue(origin, bond_id: H256) -> Result {
+            ensure_root(origin)?;
+            Self::process_bond(bond_id, <system::Module<T>>::block_number())
+        }
+
+        /// Mark `bond_id` as in default once its grace period is over. The scheduler
+        /// dispatches this as root at the end of the grace period of an approved bond.
+        #[weight = SimpleDispatchInfo::FixedOperational(3 * READ_WEIGHT + WRITE_WEIGHT)]
+        fn mark_default(origin, bond_id: H256) -> Result {
+            ensure_root(origin)?;
+            Self::check_default(bond_id, <system::Module<T>>::block_number())
+        }
+
+	}
+}
+
+
+decl_event!(
+	pub enum Event<T> where Time = <T as system::Trait>::BlockNumber, Price = BalanceOf<T>, Lender  = <T as system::Trait>::AccountId, Borrower = <T as system::Trait>::AccountId, Liquidator = <T as system::Trait>::AccountId, Bidder = <T as system::Trait>::AccountId, Guarantor = <T as system::Trait>::AccountId, Redeemed = BalanceOf<T>, Before = BalanceOf<T>, After = BalanceOf<T> {
+        LoanRequested(Lender, Borrower, H256),
+        LoanApproved(Lender, Borrower, Time),
+        LoanRedeemed(Lender, Borrower, Redeemed),
+        LoanRepaid(H256, Redeemed, After),
+        LoanRepossessed(Lender, Borrower, Price),
+        LoanAmountIncreased(H256, Before, After, Time),
+        BondTransferRequested(H256, Lender, Price),
+        BondTransferApproved(H256, Lender),
+        LoanCancelled(Lender, Borrower, H256),
+        CollateralRatioLow(H256, Lender, Borrower),
+        LoanLiquidated(H256, Liquidator, Redeemed, Price),
+        AskCancelled(H256),
+        BidPlaced(H256, Bidder, u64, Price),
+        BidCancelled(H256, u64),
+        BidAccepted(H256, u64, Lender, Price),
+        DidLinked(H256, Vec<u8>),
+        LoanDefaulted(H256, Lender, Borrower, After),
+        GuarantorAdded(H256, Guarantor, Price),
+        GuaranteeAccepted(H256, Guarantor, Price),
+        GuaranteedLoanApproved(H256, Guarantor),
+        GuaranteedLoanDefaulted(H256, Guarantor),
+        GuaranteeDrawn(H256, Guarantor, Price),
+        GuaranteeReleased(H256, Guarantor, Price),
+	}
+);
+
+#[cfg(test)]
+mod mock;
+
+#[cfg(test)]
+mod tests;
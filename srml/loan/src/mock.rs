@@ -0,0 +1,200 @@
+// Copyright 2019 Parity Technologies (UK) Ltd.
+// This file is part of Substrate.
+
+// Substrate is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Substrate is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Substrate.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Test utilities
+
+use super::*;
+
+use std::cell::RefCell;
+use support::parameter_types;
+use sr_primitives::{
+	BuildStorage, traits::{BlakeTwo256, IdentityLookup}, testing::Header, weights::Weight,
+};
+use crate as loan;
+
+parameter_types! {
+	pub const BlockHashCount: u64 = 250;
+	pub const MaximumBlockWeight: u32 = 1024;
+	pub const MaximumBlockLength: u32 = 2 * 1024;
+	pub const AvailableBlockRatio: Perbill = Perbill::one();
+
+	pub const ExistentialDeposit: u64 = 0;
+	pub const TransferFee: u64 = 0;
+	pub const CreationFee: u64 = 0;
+
+	pub const ByteDeposit: u64 = 1;
+	pub const BlocksPerYear: u64 = 1_000;
+	pub const InitialCollateralRatio: Fixed64 = Fixed64::from_rational(3, 2);
+	pub const MaintenanceRatio: Fixed64 = Fixed64::from_rational(6, 5);
+	pub const LiquidationBonus: Perbill = Perbill::from_percent(5);
+	pub const CollateralChecks: u32 = 1;
+
+	pub const MaximumSchedulerWeight: Weight = 1_000_000;
+}
+
+impl system::Trait for Test {
+	type Origin = Origin;
+	type Index = u64;
+	type BlockNumber = u64;
+	type Call = Call;
+	type Hash = H256;
+	type Hashing = BlakeTwo256;
+	type AccountId = u64;
+	type Lookup = IdentityLookup<Self::AccountId>;
+	type Header = Header;
+	type Event = Event;
+	type BlockHashCount = BlockHashCount;
+	type MaximumBlockWeight = MaximumBlockWeight;
+	type MaximumBlockLength = MaximumBlockLength;
+	type AvailableBlockRatio = AvailableBlockRatio;
+	type Version = ();
+}
+
+impl balances::Trait for Test {
+	type Balance = u64;
+	type OnFreeBalanceZero = ();
+	type OnNewAccount = ();
+	type Event = Event;
+	type TransferPayment = ();
+	type DustRemoval = ();
+	type ExistentialDeposit = ExistentialDeposit;
+	type TransferFee = TransferFee;
+	type CreationFee = CreationFee;
+}
+
+impl scheduler::Trait for Test {
+	type Event = Event;
+	type Call = Call;
+	type MaximumWeight = MaximumSchedulerWeight;
+}
+
+thread_local! {
+	static PRICE: RefCell<Option<Fixed64>> = RefCell::new(Some(Fixed64::from_natural(1)));
+}
+
+/// Price feed whose price is set by the tests, one unit of the native currency by default.
+pub struct TestPriceFeed;
+impl PriceFeed<u32> for TestPriceFeed {
+	fn price(_: &u32) -> Option<Fixed64> {
+		PRICE.with(|p| *p.borrow())
+	}
+}
+
+pub fn set_price(price: Option<Fixed64>) {
+	PRICE.with(|p| *p.borrow_mut() = price);
+}
+
+/// The DID owned by account 1, which is issued by `ISSUER`.
+pub const BORROWER_DID: &[u8] = b"did:vault:1";
+pub const ISSUER: &[u8] = b"did:vault:issuer";
+
+pub struct TestIdentity;
+impl Identity<u64> for TestIdentity {
+	fn is_owner(id: &[u8], who: &u64) -> bool {
+		id == BORROWER_DID && *who == 1
+	}
+
+	fn is_issued_by(id: &[u8], issuer: &[u8]) -> bool {
+		id == BORROWER_DID && issuer == ISSUER
+	}
+}
+
+impl Trait for Test {
+	type Event = Event;
+	type Currency = balances::Module<Self>;
+	type ByteDeposit = ByteDeposit;
+	type BlocksPerYear = BlocksPerYear;
+	type AssetId = u32;
+	type Assets = NativeCollateral<balances::Module<Self>>;
+	type PriceFeed = TestPriceFeed;
+	type InitialCollateralRatio = InitialCollateralRatio;
+	type MaintenanceRatio = MaintenanceRatio;
+	type LiquidationBonus = LiquidationBonus;
+	type CollateralChecks = CollateralChecks;
+	type Identity = TestIdentity;
+	type Call = Call;
+	type Scheduler = scheduler::Module<Self>;
+}
+
+pub type Block = sr_primitives::generic::Block<Header, UncheckedExtrinsic>;
+pub type UncheckedExtrinsic = sr_primitives::generic::UncheckedExtrinsic<u32, u64, Call, ()>;
+
+support::construct_runtime!(
+	pub enum Test where
+		Block = Block,
+		NodeBlock = Block,
+		UncheckedExtrinsic = UncheckedExtrinsic
+	{
+		System: system::{Module, Call, Event},
+		Balances: balances::{Module, Call, Storage, Config<T>, Event<T>},
+		Scheduler: scheduler::{Module, Call, Storage, Event<T>},
+		Loan: loan::{Module, Call, Storage, Event<T>, Config<T>},
+	}
+);
+
+/// Account 1 borrows from account 2, accounts 3 and 4 trade bonds and liquidate.
+pub fn new_test_ext() -> runtime_io::TestExternalities {
+	set_price(Some(Fixed64::from_natural(1)));
+	let mut ext: runtime_io::TestExternalities = GenesisConfig {
+		balances: Some(balances::GenesisConfig {
+			balances: vec![(1, 10_000), (2, 10_000), (3, 10_000), (4, 10_000), (99, 1)],
+			vesting: vec![],
+		}),
+		loan: Some(loan::GenesisConfig {
+			requests: vec![
+				(1, 3, 500, 100, Perbill::from_percent(10), Schedule::Bullet, 0, 1_000, 10, 0, Perbill::zero()),
+			],
+			credit_history: vec![
+				(BORROWER_DID.to_vec(), CreditRecord { on_time: 3, late: 0, defaulted: 0 }),
+			],
+		}),
+	}.build_storage().unwrap().into();
+	ext.execute_with(|| System::set_block_number(1));
+	ext
+}
+
+/// Request a loan of 1_000 from account 2 for account 1 against 2_000 of collateral,
+/// expiring at block 100 with a grace period of 10 blocks, and return its id.
+pub fn request_loan(schedule: Schedule) -> H256 {
+	let nonce = Loan::nonce(1);
+	assert_eq!(Loan::request(
+		Origin::signed(1), 2, 1_000, 100, Perbill::from_percent(10), schedule, 0, 2_000, 10, 10,
+		Perbill::from_percent(50),
+	), Ok(()));
+	let id = Loan::borrower_bonds(1).last().cloned().unwrap();
+	assert_eq!(id, Loan::bond_id(&1, nonce, &Loan::bond(id)));
+	id
+}
+
+/// Advance to block `n`, initializing every block on the way.
+pub fn run_to_block(n: u64) {
+	use sr_primitives::traits::OnInitialize;
+	while System::block_number() < n {
+		System::set_block_number(System::block_number() + 1);
+		Scheduler::on_initialize(System::block_number());
+		Loan::on_initialize(System::block_number());
+	}
+}
+
+/// The events deposited by the loan module so far.
+pub fn loan_events() -> Vec<RawEvent<u64, u64, u64, u64, u64, u64, u64, u64, u64, u64>> {
+	System::events().into_iter()
+		.filter_map(|record| match record.event {
+			Event::loan(event) => Some(event),
+			_ => None,
+		})
+		.collect()
+}
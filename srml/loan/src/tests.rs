@@ -0,0 +1,368 @@
+// Copyright 2019 Parity Technologies (UK) Ltd.
+// This file is part of Substrate.
+
+// Substrate is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Substrate is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Substrate.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Tests for the module.
+
+use crate::{RawEvent, Schedule};
+use mock::*;
+
+use support::{assert_ok, assert_noop};
+use sr_primitives::{Fixed64, Perbill};
+
+#[test]
+fn genesis_requests_and_credit_history_are_stored() {
+	new_test_ext().execute_with(|| {
+		let bonds = Loan::borrower_bonds(1);
+		assert_eq!(bonds.len(), 1);
+		assert_eq!(Loan::lender_bonds(3), bonds);
+		assert_eq!(Loan::nonce(1), 1);
+		let bond = Loan::bond(bonds[0]);
+		assert_eq!(bonds[0], Loan::bond_id(&1, 0, &bond));
+		assert_eq!(Balances::reserved_balance(&1), Loan::deposit(bonds[0]));
+		assert_eq!(Loan::credit_score(BORROWER_DID.to_vec()).score, 800);
+	});
+}
+
+#[test]
+fn request_and_cancel_refund_deposit() {
+	new_test_ext().execute_with(|| {
+		let reserved = Balances::reserved_balance(&1);
+		assert_noop!(
+			Loan::request(Origin::signed(1), 1, 1_000, 100, Perbill::zero(), Schedule::Bullet, 0, 2_000, 10, 10, Perbill::zero()),
+			"You cannot just borrow money from oneself"
+		);
+		assert_noop!(
+			Loan::request(Origin::signed(1), 2, 1_000, 1, Perbill::zero(), Schedule::Bullet, 0, 2_000, 10, 10, Perbill::zero()),
+			"The bond is already expired"
+		);
+		let id = request_loan(Schedule::Bullet);
+		assert_eq!(Balances::reserved_balance(&1), reserved + Loan::deposit(id));
+		assert_eq!(loan_events(), vec![RawEvent::LoanRequested(2, 1, id)]);
+		assert_noop!(Loan::cancel(Origin::signed(2), id), "You are not the borrower for this loan");
+		assert_ok!(Loan::cancel(Origin::signed(1), id));
+		assert_eq!(Balances::reserved_balance(&1), reserved);
+		assert!(Loan::orders(id).is_none());
+		assert_eq!(Loan::borrower_bonds(1).len(), 1);
+		assert!(Loan::lender_bonds(2).is_empty());
+	});
+}
+
+#[test]
+fn approve_lends_against_collateral() {
+	new_test_ext().execute_with(|| {
+		let id = request_loan(Schedule::Bullet);
+		let reserved = Balances::reserved_balance(&1);
+		assert_noop!(
+			Loan::approve(Origin::signed(3), id, None, None),
+			"You are not the lender for this borrower"
+		);
+		assert_ok!(Loan::approve(Origin::signed(2), id, None, None));
+		assert_eq!(Balances::free_balance(&2), 9_000);
+		assert_eq!(Balances::reserved_balance(&1), reserved + 2_000);
+		assert_eq!(Loan::active_bonds(), vec![id]);
+		assert_eq!(Loan::bond(id).next_increment, 11);
+		assert_noop!(Loan::approve(Origin::signed(2), id, None, None), "The loan is already approved");
+	});
+}
+
+#[test]
+fn approve_checks_collateral_ratio() {
+	new_test_ext().execute_with(|| {
+		let id = request_loan(Schedule::Bullet);
+		set_price(None);
+		assert_noop!(Loan::approve(Origin::signed(2), id, None, None), "The collateral asset has no price");
+		// 2_000 of collateral is worth 1_400, below 150% of the loan
+		set_price(Some(Fixed64::from_rational(7, 10)));
+		assert_noop!(Loan::approve(Origin::signed(2), id, None, None), "The collateral does not cover the loan");
+		set_price(Some(Fixed64::from_rational(3, 4)));
+		assert_ok!(Loan::approve(Origin::signed(2), id, None, None));
+	});
+}
+
+#[test]
+fn approve_checks_credit_of_did() {
+	new_test_ext().execute_with(|| {
+		let id = request_loan(Schedule::Bullet);
+		assert_noop!(
+			Loan::approve(Origin::signed(2), id, Some(500), None),
+			"The loan is not tied to a DID"
+		);
+		assert_noop!(
+			Loan::link_did(Origin::signed(1), id, b"did:vault:2".to_vec()),
+			"You do not own this DID"
+		);
+		assert_ok!(Loan::link_did(Origin::signed(1), id, BORROWER_DID.to_vec()));
+		assert_noop!(
+			Loan::approve(Origin::signed(2), id, Some(900), None),
+			"The credit score of the borrower is too low"
+		);
+		assert_noop!(
+			Loan::approve(Origin::signed(2), id, None, Some(b"did:vault:other".to_vec())),
+			"The DID of the borrower is not attested by the issuer"
+		);
+		assert_ok!(Loan::approve(Origin::signed(2), id, Some(800), Some(ISSUER.to_vec())));
+	});
+}
+
+#[test]
+fn interest_accrues_every_period() {
+	new_test_ext().execute_with(|| {
+		let id = request_loan(Schedule::Bullet);
+		assert_ok!(Loan::approve(Origin::signed(2), id, None, None));
+		// 10% a year of 1_000 blocks is 0.1% a period of 10 blocks
+		run_to_block(12);
+		assert_eq!(Loan::bond(id).amount, 1_001);
+		assert_eq!(Loan::bond(id).next_increment, 21);
+		assert_eq!(Loan::projected_bond(id, 31).unwrap().debt, 1_003);
+	});
+}
+
+#[test]
+fn closing_a_bond_keeps_the_interest_of_others() {
+	new_test_ext().execute_with(|| {
+		let first = request_loan(Schedule::Bullet);
+		let second = request_loan(Schedule::Bullet);
+		assert_ok!(Loan::approve(Origin::signed(2), first, None, None));
+		assert_ok!(Loan::approve(Origin::signed(2), second, None, None));
+		assert_ok!(Loan::redeem(Origin::signed(1), first));
+		run_to_block(12);
+		assert_eq!(Loan::bond(second).amount, 1_001);
+		assert_noop!(Loan::accrue(Origin::signed(2), second), "RequireRootOrigin");
+		assert_noop!(Loan::accrue(Origin::ROOT, second), "The period of the bond is not over yet");
+	});
+}
+
+#[test]
+fn repay_settles_interest_before_principal() {
+	new_test_ext().execute_with(|| {
+		let id = request_loan(Schedule::Bullet);
+		assert_ok!(Loan::approve(Origin::signed(2), id, None, None));
+		run_to_block(12);
+		assert_noop!(Loan::repay(Origin::signed(2), id, 100), "You are not the redeemer for this loan");
+		assert_ok!(Loan::repay(Origin::signed(1), id, 501));
+		let bond = Loan::bond(id);
+		assert_eq!(bond.amount, 500);
+		assert_eq!(bond.principal, 500);
+		assert_eq!(Balances::free_balance(&2), 9_501);
+	});
+}
+
+#[test]
+fn redeem_returns_collateral_and_records_credit() {
+	new_test_ext().execute_with(|| {
+		let genesis_deposit = Balances::reserved_balance(&1);
+		let id = request_loan(Schedule::Bullet);
+		assert_ok!(Loan::link_did(Origin::signed(1), id, BORROWER_DID.to_vec()));
+		assert_ok!(Loan::approve(Origin::signed(2), id, None, None));
+		assert_ok!(Loan::redeem(Origin::signed(1), id));
+		assert_eq!(Balances::reserved_balance(&1), genesis_deposit);
+		assert_eq!(Balances::free_balance(&2), 10_000);
+		assert!(Loan::active_bonds().is_empty());
+		assert_eq!(Loan::credit_history(BORROWER_DID.to_vec()).on_time, 4);
+	});
+}
+
+#[test]
+fn repossess_after_grace_period_seizes_collateral_worth_the_debt() {
+	new_test_ext().execute_with(|| {
+		let genesis_deposit = Balances::reserved_balance(&1);
+		let id = request_loan(Schedule::Bullet);
+		assert_ok!(Loan::link_did(Origin::signed(1), id, BORROWER_DID.to_vec()));
+		assert_ok!(Loan::approve(Origin::signed(2), id, None, None));
+		run_to_block(105);
+		assert_noop!(Loan::repossess(Origin::signed(2), id), "The grace period of the bond is not over yet");
+		// the bond can be repossessed from the block it defaults at
+		run_to_block(110);
+		assert_eq!(Loan::defaulted_at(id), Some(110));
+		assert!(loan_events().iter().any(|e| match e {
+			RawEvent::LoanDefaulted(bond_id, 2, 1, _) => *bond_id == id,
+			_ => false,
+		}));
+		assert_noop!(Loan::repossess(Origin::signed(3), id), "You are not the lender for this loan");
+		assert_ok!(Loan::repossess(Origin::signed(2), id));
+		let seized = match loan_events().pop() {
+			Some(RawEvent::LoanRepossessed(2, 1, seized)) => seized,
+			e => panic!("unexpected event {:?}", e),
+		};
+		// the debt with interest and penalty is just above the loan, and the rest goes back
+		assert!(seized > 1_000 && seized < 1_100);
+		assert_eq!(Balances::free_balance(&2), 9_000 + seized);
+		assert_eq!(Balances::reserved_balance(&1), genesis_deposit);
+		assert_eq!(Loan::defaulted_at(id), None);
+		assert_eq!(Loan::credit_history(BORROWER_DID.to_vec()).defaulted, 1);
+	});
+}
+
+#[test]
+fn repossess_without_a_price_is_refused() {
+	new_test_ext().execute_with(|| {
+		let id = request_loan(Schedule::Bullet);
+		assert_ok!(Loan::approve(Origin::signed(2), id, None, None));
+		run_to_block(112);
+		set_price(None);
+		assert_noop!(Loan::repossess(Origin::signed(2), id), "The collateral asset has no price");
+		assert_eq!(Loan::active_bonds(), vec![id]);
+		set_price(Some(Fixed64::from_natural(1)));
+		assert_ok!(Loan::repossess(Origin::signed(2), id));
+		assert!(Loan::active_bonds().is_empty());
+	});
+}
+
+#[test]
+fn guarantors_co_sign_before_approval() {
+	new_test_ext().execute_with(|| {
+		let id = request_loan(Schedule::Bullet);
+		// 2_000 of collateral is worth 800, which needs 700 of guarantees to reach 150%
+		set_price(Some(Fixed64::from_rational(2, 5)));
+		assert_noop!(Loan::approve(Origin::signed(2), id, None, None), "The collateral does not cover the loan");
+		assert_noop!(Loan::add_guarantor(Origin::signed(3), id, 4, 280), "You are not the borrower for this loan");
+		assert_noop!(
+			Loan::add_guarantor(Origin::signed(1), id, 2, 280),
+			"The guarantor cannot be a party of the loan"
+		);
+		assert_ok!(Loan::add_guarantor(Origin::signed(1), id, 3, 420));
+		assert_ok!(Loan::add_guarantor(Origin::signed(1), id, 4, 280));
+		assert_noop!(Loan::add_guarantor(Origin::signed(1), id, 4, 280), "The guarantor is already named");
+		assert_ok!(Loan::accept_guarantee(Origin::signed(3), id));
+		assert_eq!(Balances::reserved_balance(&3), 420);
+		assert_noop!(Loan::accept_guarantee(Origin::signed(3), id), "You already accepted this guarantee");
+		assert_noop!(Loan::accept_guarantee(Origin::signed(2), id), "You are not a guarantor of this loan");
+		assert_noop!(
+			Loan::approve(Origin::signed(2), id, None, None),
+			"A guarantor has not accepted the guarantee yet"
+		);
+		assert_ok!(Loan::accept_guarantee(Origin::signed(4), id));
+		assert_eq!(Loan::guaranteed(id), 700);
+		assert_ok!(Loan::approve(Origin::signed(2), id, None, None));
+		assert!(loan_events().contains(&RawEvent::GuaranteedLoanApproved(id, 3)));
+		assert_noop!(Loan::add_guarantor(Origin::signed(1), id, 99, 1), "The loan is already approved");
+		assert_ok!(Loan::redeem(Origin::signed(1), id));
+		assert_eq!(Balances::reserved_balance(&3), 0);
+		assert_eq!(Balances::reserved_balance(&4), 0);
+		assert!(Loan::guarantees(id).is_empty());
+		assert!(loan_events().contains(&RawEvent::GuaranteeReleased(id, 4, 280)));
+	});
+}
+
+#[test]
+fn repossess_draws_the_rest_of_the_debt_from_guarantors_pro_rata() {
+	new_test_ext().execute_with(|| {
+		let id = request_loan(Schedule::Bullet);
+		set_price(Some(Fixed64::from_rational(2, 5)));
+		assert_ok!(Loan::add_guarantor(Origin::signed(1), id, 3, 420));
+		assert_ok!(Loan::add_guarantor(Origin::signed(1), id, 4, 280));
+		assert_ok!(Loan::accept_guarantee(Origin::signed(3), id));
+		assert_ok!(Loan::accept_guarantee(Origin::signed(4), id));
+		assert_ok!(Loan::approve(Origin::signed(2), id, None, None));
+		run_to_block(112);
+		assert!(loan_events().contains(&RawEvent::GuaranteedLoanDefaulted(id, 4)));
+		// the whole collateral is worth 800, and the guarantors pay the rest of the debt
+		let unpaid = Loan::accrue_penalty(Loan::bond(id), 112).amount - 800;
+		let share = Perbill::from_rational_approximation(unpaid, 700);
+		assert_ok!(Loan::repossess(Origin::signed(2), id));
+		let events = loan_events();
+		assert!(events.contains(&RawEvent::GuaranteeDrawn(id, 3, share * 420)));
+		assert!(events.contains(&RawEvent::GuaranteeDrawn(id, 4, share * 280)));
+		assert!(events.contains(&RawEvent::GuaranteeReleased(id, 3, 420 - share * 420)));
+		assert_eq!(Balances::free_balance(&2), 9_000 + 2_000 + share * 420 + share * 280);
+		assert_eq!(Balances::free_balance(&3), 10_000 - share * 420);
+		assert_eq!(Balances::free_balance(&4), 10_000 - share * 280);
+		assert_eq!(Balances::reserved_balance(&3), 0);
+		assert_eq!(Balances::reserved_balance(&4), 0);
+	});
+}
+
+#[test]
+fn collateral_ratios_are_checked_in_turn() {
+	new_test_ext().execute_with(|| {
+		let first = request_loan(Schedule::Bullet);
+		let second = request_loan(Schedule::Bullet);
+		assert_ok!(Loan::approve(Origin::signed(2), first, None, None));
+		assert_ok!(Loan::approve(Origin::signed(2), second, None, None));
+		set_price(Some(Fixed64::from_rational(11, 20)));
+		// the mock checks one bond a block
+		run_to_block(2);
+		assert_eq!(Loan::undercollateralised(first), Some(2));
+		assert_eq!(Loan::undercollateralised(second), None);
+		run_to_block(3);
+		assert_eq!(Loan::undercollateralised(second), Some(3));
+		assert_eq!(Loan::collateral_cursor(), 0);
+		// liquidation checks the ratio itself
+		set_price(Some(Fixed64::from_natural(1)));
+		assert_noop!(Loan::liquidate(Origin::signed(3), first), "The bond is above the maintenance ratio");
+	});
+}
+
+#[test]
+fn liquidate_below_maintenance_ratio() {
+	new_test_ext().execute_with(|| {
+		let id = request_loan(Schedule::Bullet);
+		assert_ok!(Loan::approve(Origin::signed(2), id, None, None));
+		assert_noop!(Loan::liquidate(Origin::signed(3), id), "The bond is above the maintenance ratio");
+		// 2_000 of collateral is worth 1_100, below 120% of the debt
+		set_price(Some(Fixed64::from_rational(11, 20)));
+		run_to_block(2);
+		assert_eq!(Loan::undercollateralised(id), Some(2));
+		assert_ok!(Loan::liquidate(Origin::signed(3), id));
+		// the liquidator gets collateral worth the debt and the 5% bonus
+		let seized = Perbill::from_rational_approximation(1_050u64, 1_100u64) * 2_000;
+		assert_eq!(Balances::free_balance(&3), 9_000 + seized);
+		assert_eq!(Balances::free_balance(&2), 10_000);
+		assert_eq!(loan_events().pop(), Some(RawEvent::LoanLiquidated(id, 3, 1_000, seized)));
+		assert!(Loan::active_bonds().is_empty());
+	});
+}
+
+#[test]
+fn bids_are_escrowed_until_accepted_or_cancelled() {
+	new_test_ext().execute_with(|| {
+		let id = request_loan(Schedule::Bullet);
+		assert_noop!(Loan::place_bid(Origin::signed(3), id, 900), "The loan is not approved yet");
+		assert_ok!(Loan::approve(Origin::signed(2), id, None, None));
+		assert_noop!(Loan::place_bid(Origin::signed(1), id, 900), "You cannot buy your own debt");
+		assert_ok!(Loan::place_bid(Origin::signed(3), id, 900));
+		assert_ok!(Loan::place_bid(Origin::signed(4), id, 950));
+		assert_eq!(Balances::reserved_balance(&3), 900);
+		assert_eq!(Balances::reserved_balance(&4), 950);
+		assert_ok!(Loan::accept_bid(Origin::signed(2), id, 1));
+		assert_eq!(Balances::free_balance(&2), 9_950);
+		assert_eq!(Balances::reserved_balance(&4), 0);
+		assert_eq!(Loan::lender_bonds(4), vec![id]);
+		assert!(Loan::lender_bonds(2).is_empty());
+		assert_eq!(Loan::orders(id).unwrap().bids.len(), 1);
+		assert_noop!(Loan::cancel_bid(Origin::signed(4), id, 0), "You are not the bidder of this bid");
+		assert_ok!(Loan::cancel_bid(Origin::signed(3), id, 0));
+		assert_eq!(Balances::reserved_balance(&3), 0);
+		assert!(Loan::orders(id).unwrap().bids.is_empty());
+	});
+}
+
+#[test]
+fn bonds_are_sold_at_the_ask() {
+	new_test_ext().execute_with(|| {
+		let id = request_loan(Schedule::Bullet);
+		assert_ok!(Loan::approve(Origin::signed(2), id, None, None));
+		assert_noop!(Loan::buy_bond(Origin::signed(3), id), "The bond is not open for sale");
+		assert_ok!(Loan::sell_bond(Origin::signed(2), id, 800));
+		assert_eq!(Loan::for_sale(), vec![id]);
+		assert_ok!(Loan::buy_bond(Origin::signed(3), id));
+		assert_eq!(Balances::free_balance(&2), 9_800);
+		assert_eq!(Balances::free_balance(&3), 9_200);
+		assert_eq!(Loan::bond(id).lender, 3);
+		assert!(Loan::for_sale().is_empty());
+		assert_eq!(Loan::bonds_by_lender(3).len(), 2);
+	});
+}
@@ -0,0 +1,305 @@
+// Copyright 2019 Parity Technologies (UK) Ltd.
+// This file is part of Substrate.
+
+// Substrate is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Substrate is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Substrate.  If not, see <http://www.gnu.org/licenses/>.
+
+//! # Scheduler Module
+//!
+//! ## Overview
+//!
+//! A module which dispatches calls at a future block, as root.
+//!
+//! ## Interface
+//!
+//! Calls are stored in the agenda of the block they are due at, and dispatched when that block
+//! is initialized. A call can repeat every given number of blocks until it is cancelled.
+//!
+//! Anonymous calls are cancelled with their address, the block and the index in its agenda they
+//! were scheduled at. The address stays valid while the call waits or repeats. Named calls are
+//! cancelled with the id they were scheduled under.
+//!
+//! The calls of a block are dispatched until their weight reaches `MaximumWeight`. The calls
+//! which are left move to the end of the agenda of the next block, but the first call of a
+//! block is always dispatched. Repeats are counted from the block a call was due at, so a call
+//! which waits does not shift the schedule of its repeats.
+//!
+//! Other modules schedule calls through the `Anon` and `Named` traits of
+//! `support::traits::schedule`, which this module implements.
+//!
+//! ### Dispatchable Functions
+//!
+//! - `schedule` - Root schedules an anonymous call.
+//! - `cancel` - Root cancels the anonymous call at an address.
+//! - `schedule_named` - Root schedules a call under an id.
+//! - `cancel_named` - Root cancels the call scheduled under an id.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use rstd::prelude::*;
+use codec::{Encode, Decode};
+use support::{decl_module, decl_storage, decl_event, ensure, Parameter};
+use support::dispatch::Result;
+use support::traits::{Get, schedule};
+use sr_primitives::traits::{Dispatchable, One, Saturating, Zero};
+use sr_primitives::weights::{GetDispatchInfo, SimpleDispatchInfo, Weight};
+use system::ensure_root;
+
+#[cfg(test)]
+mod mock;
+#[cfg(test)]
+mod tests;
+
+/// The block a call is scheduled at, and its index in the agenda of that block.
+pub type TaskAddress<BlockNumber> = (BlockNumber, u32);
+
+/// A call in the agenda of a block.
+#[derive(Encode, Decode, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "std", derive(Debug))]
+pub struct Scheduled<Call, BlockNumber> {
+	/// The id the call is scheduled under, if it is named.
+	pub maybe_id: Option<Vec<u8>>,
+	/// The address the call was scheduled at.
+	pub address: TaskAddress<BlockNumber>,
+	/// The block the call is due at, which is before the block of its agenda if it waits.
+	pub when: BlockNumber,
+	/// The number of blocks after which the call is dispatched again.
+	pub maybe_period: Option<BlockNumber>,
+	/// The call to dispatch.
+	pub call: Call,
+}
+
+impl<Call, BlockNumber: Encode> Scheduled<Call, BlockNumber> {
+	/// The key the address of the call is looked up with: its id if it is named, or else the
+	/// address it was scheduled at.
+	fn key(&self) -> Vec<u8> {
+		self.maybe_id.clone().unwrap_or_else(|| anon_key(&self.address))
+	}
+}
+
+/// The key the address of the anonymous call scheduled at `address` is looked up with.
+fn anon_key<BlockNumber: Encode>(address: &TaskAddress<BlockNumber>) -> Vec<u8> {
+	(b"scheduler/anon", address).encode()
+}
+
+pub type ScheduledOf<T> = Scheduled<<T as Trait>::Call, <T as system::Trait>::BlockNumber>;
+
+/// The module's configuration trait.
+pub trait Trait: system::Trait {
+	/// The overarching event type.
+	type Event: From<Event<Self>> + Into<<Self as system::Trait>::Event>;
+
+	/// The overarching call type, which scheduled calls are dispatched as.
+	type Call: Parameter + Dispatchable<Origin=Self::Origin> + GetDispatchInfo;
+
+	/// The weight of the scheduled calls dispatched in a block, past which the rest wait for
+	/// the next block.
+	type MaximumWeight: Get<Weight>;
+}
+
+decl_storage! {
+	trait Store for Module<T: Trait> as Scheduler {
+		/// Calls due at each block. Cancelled calls leave an empty slot, so the addresses of
+		/// the other calls stay valid.
+		pub Agenda get(agenda): map T::BlockNumber => Vec<Option<ScheduledOf<T>>>;
+		/// Address of the call scheduled under each id, and of each anonymous call under the
+		/// address it was scheduled at.
+		pub Lookup get(lookup): map Vec<u8> => Option<TaskAddress<T::BlockNumber>>;
+	}
+}
+
+decl_event!(
+	pub enum Event<T> where BlockNumber = <T as system::Trait>::BlockNumber {
+		/// A call was scheduled at an address.
+		Scheduled(TaskAddress<BlockNumber>),
+		/// The call at an address was cancelled.
+		Cancelled(TaskAddress<BlockNumber>),
+		/// The call at an address, with the id it is scheduled under, was dispatched, and
+		/// whether it succeeded.
+		Dispatched(TaskAddress<BlockNumber>, Option<Vec<u8>>, bool),
+	}
+);
+
+decl_module! {
+	pub struct Module<T: Trait> for enum Call where origin: T::Origin {
+		fn deposit_event() = default;
+
+		/// The weight of the scheduled calls dispatched in a block.
+		const MaximumWeight: Weight = T::MaximumWeight::get();
+
+		fn on_initialize(now: T::BlockNumber) {
+			Self::dispatch_agenda(now);
+		}
+
+		/// Dispatch `call` as root at block `when`, and every `maybe_period` blocks after it.
+		#[weight = SimpleDispatchInfo::FreeOperational]
+		fn schedule(origin, when: T::BlockNumber, maybe_period: Option<T::BlockNumber>, call: Box<<T as Trait>::Call>) {
+			ensure_root(origin)?;
+			ensure!(when > <system::Module<T>>::block_number(), "The block is not in the future");
+			Self::do_schedule(None, when, maybe_period, *call);
+		}
+
+		/// Cancel the anonymous call scheduled at block `when` with `index` in its agenda, with
+		/// any repeats of it.
+		#[weight = SimpleDispatchInfo::FreeOperational]
+		fn cancel(origin, when: T::BlockNumber, index: u32) {
+			ensure_root(origin)?;
+			Self::do_cancel((when, index))?;
+		}
+
+		/// Dispatch `call` as root under `id` at block `when`, and every `maybe_period` blocks
+		/// after it.
+		#[weight = SimpleDispatchInfo::FreeOperational]
+		fn schedule_named(origin, id: Vec<u8>, when: T::BlockNumber, maybe_period: Option<T::BlockNumber>, call: Box<<T as Trait>::Call>) {
+			ensure_root(origin)?;
+			ensure!(when > <system::Module<T>>::block_number(), "The block is not in the future");
+			ensure!(!<Lookup<T>>::exists(&id), "A call is already scheduled under this id");
+			Self::do_schedule(Some(id), when, maybe_period, *call);
+		}
+
+		/// Cancel the call scheduled under `id`.
+		#[weight = SimpleDispatchInfo::FreeOperational]
+		fn cancel_named(origin, id: Vec<u8>) {
+			ensure_root(origin)?;
+			Self::do_cancel_named(id)?;
+		}
+	}
+}
+
+impl<T: Trait> Module<T> {
+	/// Append `task` to the agenda of block `at`, and return its current address.
+	fn push(at: T::BlockNumber, task: ScheduledOf<T>) -> TaskAddress<T::BlockNumber> {
+		let index = <Agenda<T>>::mutate(at, |agenda| {
+			agenda.push(Some(task.clone()));
+			agenda.len() as u32 - 1
+		});
+		<Lookup<T>>::insert(task.key(), (at, index));
+		(at, index)
+	}
+
+	fn do_schedule(
+		maybe_id: Option<Vec<u8>>,
+		when: T::BlockNumber,
+		maybe_period: Option<T::BlockNumber>,
+		call: <T as Trait>::Call,
+	) -> TaskAddress<T::BlockNumber> {
+		let when = when.max(<system::Module<T>>::block_number() + One::one());
+		let maybe_period = maybe_period.filter(|period| !period.is_zero());
+		let address = (when, <Agenda<T>>::get(when).len() as u32);
+		Self::push(when, Scheduled { maybe_id, address, when, maybe_period, call });
+		Self::deposit_event(RawEvent::Scheduled(address));
+		address
+	}
+
+	/// Remove the call whose address is looked up under `key` from its agenda.
+	fn cancel_key(key: &[u8]) -> Option<TaskAddress<T::BlockNumber>> {
+		let (at, index) = <Lookup<T>>::take(key)?;
+		// the agenda of the block which is dispatched right now is already taken, and its calls
+		// are only dispatched while they are still looked up at their address
+		if <Agenda<T>>::exists(at) {
+			<Agenda<T>>::mutate(at, |agenda| {
+				if let Some(slot) = agenda.get_mut(index as usize) {
+					*slot = None;
+				}
+			});
+		}
+		Self::deposit_event(RawEvent::Cancelled((at, index)));
+		Some((at, index))
+	}
+
+	fn do_cancel(address: TaskAddress<T::BlockNumber>) -> Result {
+		Self::cancel_key(&anon_key(&address)).map(|_| ()).ok_or("No call is scheduled at this address")
+	}
+
+	fn do_cancel_named(id: Vec<u8>) -> Result {
+		Self::cancel_key(&id).map(|_| ()).ok_or("No call is scheduled under this id")
+	}
+
+	/// Dispatch the calls due at `now` up to the maximum weight, and move the rest to the
+	/// next block.
+	fn dispatch_agenda(now: T::BlockNumber) {
+		let next = now + One::one();
+		let limit = T::MaximumWeight::get();
+		let mut weight: Weight = 0;
+		let mut dispatched = 0;
+		let mut postponed = Vec::new();
+		for (index, task) in <Agenda<T>>::take(now).into_iter().enumerate() {
+			let task = match task {
+				Some(task) => task,
+				None => continue,
+			};
+			let address = (now, index as u32);
+			// an earlier call of this block can cancel or reschedule the call
+			if <Lookup<T>>::get(task.key()) != Some(address) {
+				continue;
+			}
+			let call_weight = task.call.get_dispatch_info().weight;
+			if dispatched > 0 && weight.saturating_add(call_weight) > limit {
+				postponed.push((address, task));
+				continue;
+			}
+			weight = weight.saturating_add(call_weight);
+			dispatched += 1;
+			let ok = task.call.clone().dispatch(system::RawOrigin::Root.into()).is_ok();
+			Self::deposit_event(RawEvent::Dispatched(address, task.maybe_id.clone(), ok));
+			let key = task.key();
+			// a call can cancel itself while it is dispatched
+			if <Lookup<T>>::get(&key) != Some(address) {
+				continue;
+			}
+			match task.maybe_period {
+				Some(period) => {
+					// a repeat which is already due after waiting is dispatched next
+					let when = task.when.saturating_add(period);
+					Self::push(when.max(next), Scheduled { when, ..task });
+				}
+				None => <Lookup<T>>::remove(&key),
+			}
+		}
+		for (address, task) in postponed {
+			if <Lookup<T>>::get(task.key()) == Some(address) {
+				Self::push(next, task);
+			}
+		}
+	}
+}
+
+impl<T: Trait> schedule::Anon<T::BlockNumber, <T as Trait>::Call> for Module<T> {
+	type Address = TaskAddress<T::BlockNumber>;
+
+	fn schedule(when: T::BlockNumber, maybe_period: Option<T::BlockNumber>, call: <T as Trait>::Call) -> Self::Address {
+		Self::do_schedule(None, when, maybe_period, call)
+	}
+
+	fn cancel(address: Self::Address) -> Result {
+		Self::do_cancel(address)
+	}
+}
+
+impl<T: Trait> schedule::Named<T::BlockNumber, <T as Trait>::Call> for Module<T> {
+	type Address = TaskAddress<T::BlockNumber>;
+
+	fn schedule_named(
+		id: Vec<u8>,
+		when: T::BlockNumber,
+		maybe_period: Option<T::BlockNumber>,
+		call: <T as Trait>::Call,
+	) -> rstd::result::Result<Self::Address, &'static str> {
+		ensure!(!<Lookup<T>>::exists(&id), "A call is already scheduled under this id");
+		Ok(Self::do_schedule(Some(id), when, maybe_period, call))
+	}
+
+	fn cancel_named(id: Vec<u8>) -> Result {
+		Self::do_cancel_named(id)
+	}
+}
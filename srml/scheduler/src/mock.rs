@@ -0,0 +1,111 @@
+// Copyright 2019 Parity Technologies (UK) Ltd.
+// This file is part of Substrate.
+
+// Substrate is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Substrate is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Substrate.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Test utilities
+//! Test utilities
+
+use super::*;
+
+use support::parameter_types;
+use primitives::H256;
+use sr_primitives::{
+	Perbill, traits::{BlakeTwo256, IdentityLookup, OnInitialize}, testing::Header,
+};
+use crate as scheduler;
+
+parameter_types! {
+	pub const BlockHashCount: u64 = 250;
+	pub const MaximumBlockWeight: u32 = 1024;
+	pub const MaximumBlockLength: u32 = 2 * 1024;
+	pub const AvailableBlockRatio: Perbill = Perbill::one();
+
+	pub const MaximumWeight: Weight = 25_000;
+}
+
+impl system::Trait for Test {
+	type Origin = Origin;
+	type Index = u64;
+	type BlockNumber = u64;
+	type Call = Call;
+	type Hash = H256;
+	type Hashing = BlakeTwo256;
+	type AccountId = u64;
+	type Lookup = IdentityLookup<Self::AccountId>;
+	type Header = Header;
+	type Event = Event;
+	type BlockHashCount = BlockHashCount;
+	type MaximumBlockWeight = MaximumBlockWeight;
+	type MaximumBlockLength = MaximumBlockLength;
+	type AvailableBlockRatio = AvailableBlockRatio;
+	type Version = ();
+}
+
+impl Trait for Test {
+	type Event = Event;
+	type Call = Call;
+	type MaximumWeight = MaximumWeight;
+}
+
+pub type Block = sr_primitives::generic::Block<Header, UncheckedExtrinsic>;
+pub type UncheckedExtrinsic = sr_primitives::generic::UncheckedExtrinsic<u32, u64, Call, ()>;
+
+support::construct_runtime!(
+	pub enum Test where
+		Block = Block,
+		NodeBlock = Block,
+		UncheckedExtrinsic = UncheckedExtrinsic
+	{
+		System: system::{Module, Call, Event},
+		Scheduler: scheduler::{Module, Call, Storage, Event<T>},
+	}
+);
+
+pub fn new_test_ext() -> runtime_io::TestExternalities {
+	let mut ext: runtime_io::TestExternalities = system::GenesisConfig::default()
+		.build_storage::<Test>().unwrap().into();
+	ext.execute_with(|| System::set_block_number(1));
+	ext
+}
+
+/// A call of weight 10_000 which sets the storage at `key` when it is dispatched as root.
+pub fn mark(key: u8) -> Call {
+	Call::System(system::Call::set_storage(vec![(vec![key], vec![1])]))
+}
+
+/// Whether the call from `mark(key)` was dispatched, and clear it for the next check.
+pub fn take_mark(key: u8) -> bool {
+	let marked = support::storage::unhashed::exists(&[key]);
+	support::storage::unhashed::kill(&[key]);
+	marked
+}
+
+/// Advance to block `n`, initializing every block on the way.
+pub fn run_to_block(n: u64) {
+	while System::block_number() < n {
+		System::set_block_number(System::block_number() + 1);
+		Scheduler::on_initialize(System::block_number());
+	}
+}
+
+/// The events deposited by the scheduler module so far.
+pub fn scheduler_events() -> Vec<RawEvent<u64>> {
+	System::events().into_iter()
+		.filter_map(|record| match record.event {
+			Event::scheduler(event) => Some(event),
+			_ => None,
+		})
+		.collect()
+}
@@ -0,0 +1,157 @@
+// Copyright 2019 Parity Technologies (UK) Ltd.
+// This file is part of Substrate.
+
+// Substrate is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Substrate is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Substrate.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Tests for the module.
+
+use crate::{RawEvent, schedule::{Anon, Named}};
+use mock::*;
+
+use support::{assert_ok, assert_noop};
+
+#[test]
+fn calls_are_dispatched_as_root_at_their_block() {
+	new_test_ext().execute_with(|| {
+		assert_noop!(Scheduler::schedule(Origin::signed(1), 3, None, Box::new(mark(1))), "RequireRootOrigin");
+		assert_noop!(Scheduler::schedule(Origin::ROOT, 1, None, Box::new(mark(1))), "The block is not in the future");
+		assert_ok!(Scheduler::schedule(Origin::ROOT, 3, None, Box::new(mark(1))));
+		run_to_block(2);
+		assert!(!take_mark(1));
+		run_to_block(3);
+		assert!(take_mark(1));
+		assert!(Scheduler::agenda(3).is_empty());
+		assert_eq!(scheduler_events(), vec![
+			RawEvent::Scheduled((3, 0)),
+			RawEvent::Dispatched((3, 0), None, true),
+		]);
+	});
+}
+
+#[test]
+fn cancel_leaves_other_calls_of_the_block() {
+	new_test_ext().execute_with(|| {
+		let first = <Scheduler as Anon<_, _>>::schedule(3, None, mark(1));
+		let second = <Scheduler as Anon<_, _>>::schedule(3, None, mark(2));
+		assert_eq!(second, (3, 1));
+		assert_ok!(Scheduler::cancel(Origin::ROOT, 3, 0));
+		assert_eq!(<Scheduler as Anon<_, _>>::cancel(first), Err("No call is scheduled at this address"));
+		run_to_block(3);
+		assert!(!take_mark(1));
+		assert!(take_mark(2));
+	});
+}
+
+#[test]
+fn call_cancelled_by_an_earlier_call_of_the_block_is_not_dispatched() {
+	new_test_ext().execute_with(|| {
+		let cancel = Call::Scheduler(crate::Call::cancel(3, 1));
+		assert_ok!(Scheduler::schedule(Origin::ROOT, 3, None, Box::new(cancel)));
+		assert_ok!(Scheduler::schedule(Origin::ROOT, 3, None, Box::new(mark(1))));
+		let cancel_named = Call::Scheduler(crate::Call::cancel_named(b"later".to_vec()));
+		assert_ok!(Scheduler::schedule(Origin::ROOT, 3, None, Box::new(cancel_named)));
+		assert_ok!(Scheduler::schedule_named(Origin::ROOT, b"later".to_vec(), 3, None, Box::new(mark(2))));
+		run_to_block(3);
+		assert!(!take_mark(1));
+		assert!(!take_mark(2));
+		assert!(!scheduler_events().contains(&RawEvent::Dispatched((3, 1), None, true)));
+		assert_eq!(Scheduler::lookup(b"later".to_vec()), None);
+	});
+}
+
+#[test]
+fn named_calls_are_unique_and_cancelled_by_id() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Scheduler::schedule_named(Origin::ROOT, b"one".to_vec(), 3, None, Box::new(mark(1))));
+		assert_noop!(
+			Scheduler::schedule_named(Origin::ROOT, b"one".to_vec(), 4, None, Box::new(mark(2))),
+			"A call is already scheduled under this id"
+		);
+		assert_eq!(<Scheduler as Named<_, _>>::schedule_named(b"two".to_vec(), 3, None, mark(2)), Ok((3, 1)));
+		assert_ok!(<Scheduler as Named<_, _>>::cancel_named(b"two".to_vec()));
+		assert_noop!(Scheduler::cancel_named(Origin::ROOT, b"two".to_vec()), "No call is scheduled under this id");
+		run_to_block(3);
+		assert!(take_mark(1));
+		assert!(!take_mark(2));
+		assert_eq!(Scheduler::lookup(b"one".to_vec()), None);
+	});
+}
+
+#[test]
+fn periodic_calls_repeat_until_cancelled() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Scheduler::schedule_named(Origin::ROOT, b"tick".to_vec(), 2, Some(3), Box::new(mark(1))));
+		run_to_block(2);
+		assert!(take_mark(1));
+		assert_eq!(Scheduler::lookup(b"tick".to_vec()), Some((5, 0)));
+		run_to_block(5);
+		assert!(take_mark(1));
+		assert_ok!(Scheduler::cancel_named(Origin::ROOT, b"tick".to_vec()));
+		run_to_block(10);
+		assert!(!take_mark(1));
+	});
+}
+
+#[test]
+fn periodic_call_can_cancel_itself() {
+	new_test_ext().execute_with(|| {
+		let call = Call::Scheduler(crate::Call::cancel_named(b"once".to_vec()));
+		assert_ok!(Scheduler::schedule_named(Origin::ROOT, b"once".to_vec(), 2, Some(1), Box::new(call)));
+		run_to_block(2);
+		assert_eq!(Scheduler::lookup(b"once".to_vec()), None);
+		assert!(Scheduler::agenda(3).is_empty());
+		assert!(scheduler_events().contains(&RawEvent::Dispatched((2, 0), Some(b"once".to_vec()), true)));
+	});
+}
+
+#[test]
+fn calls_over_the_maximum_weight_wait_for_the_next_block() {
+	new_test_ext().execute_with(|| {
+		for key in 1..=3 {
+			assert_ok!(Scheduler::schedule(Origin::ROOT, 2, None, Box::new(mark(key))));
+		}
+		assert_ok!(Scheduler::schedule_named(Origin::ROOT, b"late".to_vec(), 2, None, Box::new(mark(4))));
+		// two calls of 10_000 fit in 25_000
+		run_to_block(2);
+		assert!(take_mark(1) && take_mark(2));
+		assert!(!take_mark(3) && !take_mark(4));
+		assert_eq!(Scheduler::lookup(b"late".to_vec()), Some((3, 1)));
+		run_to_block(3);
+		assert!(take_mark(3) && take_mark(4));
+	});
+}
+
+#[test]
+fn waiting_periodic_calls_keep_their_schedule_and_address() {
+	new_test_ext().execute_with(|| {
+		for key in 1..=2 {
+			assert_ok!(Scheduler::schedule(Origin::ROOT, 2, None, Box::new(mark(key))));
+		}
+		let tick = <Scheduler as Anon<_, _>>::schedule(2, Some(3), mark(3));
+		assert_eq!(tick, (2, 2));
+		// the periodic call waits for block 3, and still repeats at block 5
+		run_to_block(2);
+		assert!(!take_mark(3));
+		run_to_block(3);
+		assert!(take_mark(3));
+		run_to_block(4);
+		assert!(!take_mark(3));
+		run_to_block(5);
+		assert!(take_mark(3));
+		assert_ok!(<Scheduler as Anon<_, _>>::cancel(tick));
+		assert!(scheduler_events().contains(&RawEvent::Cancelled((8, 0))));
+		run_to_block(10);
+		assert!(!take_mark(3));
+	});
+}
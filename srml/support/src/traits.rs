@@ -740,3 +740,47 @@ pub trait Randomness<Output> {
 		Self::random(&[][..])
 	}
 }
+
+/// Traits of modules which dispatch calls at a future block.
+pub mod schedule {
+	use rstd::prelude::*;
+	use codec::Codec;
+
+	/// A scheduler of anonymous calls, which are cancelled with the address they are stored at.
+	pub trait Anon<BlockNumber, Call> {
+		/// Address of a scheduled call.
+		type Address: Codec + Clone + Eq;
+
+		/// Dispatch `call` at block `when`, and again every `maybe_period` blocks after it if
+		/// given. A `when` which is not in the future dispatches the call in the next block.
+		fn schedule(when: BlockNumber, maybe_period: Option<BlockNumber>, call: Call) -> Self::Address;
+
+		/// Cancel the scheduled call at `address`, with any repeats of it.
+		///
+		/// This fails if no call is scheduled at `address`.
+		fn cancel(address: Self::Address) -> Result<(), &'static str>;
+	}
+
+	/// A scheduler of calls with an `id`, which are cancelled with that id.
+	pub trait Named<BlockNumber, Call> {
+		/// Address of a scheduled call.
+		type Address: Codec + Clone + Eq;
+
+		/// Dispatch `call` under `id` at block `when`, and again every `maybe_period` blocks
+		/// after it if given. A `when` which is not in the future dispatches the call in the
+		/// next block.
+		///
+		/// This fails if a call is already scheduled under `id`.
+		fn schedule_named(
+			id: Vec<u8>,
+			when: BlockNumber,
+			maybe_period: Option<BlockNumber>,
+			call: Call,
+		) -> Result<Self::Address, &'static str>;
+
+		/// Cancel the call scheduled under `id`, with any repeats of it.
+		///
+		/// This fails if no call is scheduled under `id`.
+		fn cancel_named(id: Vec<u8>) -> Result<(), &'static str>;
+	}
+}
@@ -597,6 +597,15 @@ impl<T: Trait> Module<T> {
 		AllExtrinsicsWeight::get().unwrap_or_default()
 	}
 
+	/// Note `weight` used by a module outside of extrinsics, such as in `on_initialize`, so
+	/// that fewer extrinsics fit in the block. Unlike the weight of extrinsics, it is not
+	/// checked against the maximum block weight.
+	pub fn register_extra_weight_unchecked(weight: Weight) {
+		AllExtrinsicsWeight::mutate(|current| {
+			*current = Some(current.unwrap_or_default().saturating_add(weight));
+		});
+	}
+
 	pub fn all_extrinsics_len() -> u32 {
 		AllExtrinsicsLen::get().unwrap_or_default()
 	}
@@ -1328,6 +1337,18 @@ mod tests {
 		})
 	}
 
+	#[test]
+	fn extra_weight_leaves_less_room_for_extrinsics() {
+		new_test_ext().execute_with(|| {
+			let normal_limit = normal_weight_limit();
+			let info = DispatchInfo { weight: 100, ..Default::default() };
+
+			System::register_extra_weight_unchecked(normal_limit - 50);
+			assert_eq!(System::all_extrinsics_weight(), normal_limit - 50);
+			assert!(CheckWeight::<Test>(PhantomData).pre_dispatch(&1, CALL, info, 0).is_err());
+		})
+	}
+
 	#[test]
 	fn signed_ext_check_weight_max_works() {
 		new_test_ext().execute_with(|| {